@@ -0,0 +1,112 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Audiologist signing the reports. The profiles are kept in the user settings, and
+/// each report records a copy of the profile of its examiner.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClinicianProfile {
+    pub name: String,
+    /// Degrees after the name, such as "MPA".
+    pub credentials: String,
+    /// Profession, in French, translated in the report when it is in the catalogue.
+    pub title: String,
+    /// Professional order and licence number, such as "OOAQ #4182".
+    pub licence: String,
+    /// Path of the image of the handwritten signature, drawn above the signature line.
+    pub signature_image: String,
+    /// Path of the logo of the professional order, next to the signature.
+    pub order_logo: String,
+}
+
+impl Default for ClinicianProfile {
+    fn default() -> Self {
+        Self {
+            name: String::from("Roxanne Bolduc"),
+            credentials: String::from("MPA"),
+            title: String::from("Audiologiste"),
+            licence: String::from("OOAQ #4182"),
+            signature_image: String::new(),
+            order_logo: String::from("images/ordre256.jpg"),
+        }
+    }
+}
+
+impl ClinicianProfile {
+    /// Blank profile added from the profile editor.
+    pub fn blank() -> Self {
+        Self {
+            name: String::new(),
+            credentials: String::new(),
+            licence: String::new(),
+            signature_image: String::new(),
+            ..Self::default()
+        }
+    }
+
+    pub fn set(&mut self, field: ClinicianField, value: String) {
+        *match field {
+            ClinicianField::Name => &mut self.name,
+            ClinicianField::Credentials => &mut self.credentials,
+            ClinicianField::Title => &mut self.title,
+            ClinicianField::Licence => &mut self.licence,
+            ClinicianField::SignatureImage => &mut self.signature_image,
+            ClinicianField::OrderLogo => &mut self.order_logo,
+        } = value;
+    }
+
+    pub fn get(&self, field: ClinicianField) -> &str {
+        match field {
+            ClinicianField::Name => &self.name,
+            ClinicianField::Credentials => &self.credentials,
+            ClinicianField::Title => &self.title,
+            ClinicianField::Licence => &self.licence,
+            ClinicianField::SignatureImage => &self.signature_image,
+            ClinicianField::OrderLogo => &self.order_logo,
+        }
+    }
+}
+
+impl fmt::Display for ClinicianProfile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.name.is_empty() {
+            write!(f, "(sans nom)")
+        } else {
+            write!(f, "{}", self.name)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClinicianField {
+    Name,
+    Credentials,
+    Title,
+    Licence,
+    SignatureImage,
+    OrderLogo,
+}
+
+impl ClinicianField {
+    /// French label of the field in the profile editor.
+    pub fn label(&self) -> &'static str {
+        match self {
+            ClinicianField::Name => "Nom",
+            ClinicianField::Credentials => "Titres",
+            ClinicianField::Title => "Profession",
+            ClinicianField::Licence => "Numéro de permis",
+            ClinicianField::SignatureImage => "Image de la signature",
+            ClinicianField::OrderLogo => "Logo de l'ordre",
+        }
+    }
+}
+
+pub const CLINICIAN_FIELDS: [ClinicianField; 6] = [
+    ClinicianField::Name,
+    ClinicianField::Credentials,
+    ClinicianField::Title,
+    ClinicianField::Licence,
+    ClinicianField::SignatureImage,
+    ClinicianField::OrderLogo,
+];
@@ -7,6 +7,8 @@ use iced::widget::container;
 use iced::widget::{button, canvas};
 use iced::font:: Font;
 
+use crate::track_theme::TrackTheme;
+
 
 pub const DEFAULT_FONT: Font =  iced::font::Font {
     family: iced::font::Family::Name("Lato"),
@@ -41,13 +43,17 @@ pub const FIRA: Font = iced::font::Font {
 
 
 pub const ICON_SIZE: f32 = 16.0;
+/// Distance from the sides of the legend to the centre of its icons.
+pub const LEGEND_ICON_X: f32 = 21.0;
 
-pub const WINDOW_WIDTH: u32 = 1117; // 1000
+/// Logical width the report is laid out at, on screen and in the exports. On screen
+/// the page is then scaled to the width of the window.
+pub const PAGE_WIDTH: f32 = 1117.0;
+pub const SCROLLBAR_WIDTH: f32 = 10.0;
 pub const WINDOW_HEIGHT: u32 = 800; //1333
 
 
 pub const SECTION_TITLE_HORIZONTAL_SPACE: f32 = 3.0;
-pub const SECTION_TITLE_TEXT_COLOR: Color = Color::from_rgb(0.02, 0.02, 0.02);
 
 pub const SECTION_SEPARATOR_SPACE: f32 = 15.0;
 pub const SPACE_BELOW_SECTION_TITLE: f32 = 8.0;
@@ -65,22 +71,17 @@ pub const DEFAULT_TEXT_INPUT_CONTENT_SIZE: f32 = 12.0;
 
 
 
-pub const GRAY: Color = Color::from_rgb(0.98, 0.98, 0.98);
 
 pub const TEXT_LINE_VSPACE: f32 = 20.0;
 pub const TEXT_INPUT_HEIGHT: f32 = 23.;
 
 
-pub const TABLE_BORDER_COLOR: Color = Color::from_rgb(0.5, 0.5, 0.5);
-pub const TABLE_TITLE_TEXT_COLOR: Color = Color::from_rgb(0.05, 0.02, 0.030);
 pub const TONAL_TABLE_COL_WIDTH: f32 = 60.0;
 pub const TYMPA_TABLE_COL_WIDTH: f32 = 50.0;
 pub const VOCAL_TABLE_CONTENT_HEIGHT: f32 = 45.0;
 
 pub const SPACE: f32 = 1.0;
 pub const CORNER_RADIUS: f32 = 15.0;
-pub const AXIS_LABEL_COLOR: Color = Color::from_rgb(0.15, 0.15, 0.157);
-pub const GRID_COLOR: Color = Color::from_rgb(0.7, 0.7, 0.7);
 
 pub const RADIO_SIZE: f32 = 14.;
 pub const RADIO_SPACING: f32 = 1.5;
@@ -90,10 +91,6 @@ pub const RADIO_TEXT_SIZE: f32 = 14.0;
 pub const LEGEND_HEIGHT: f32 = 275.0;
 pub const LEGEND_WIDTH: f32 = 218.0;
 pub const LEGEND_BOTTOM_SPACE: f32 = 5.0;
-pub const LEGEND_TITLES_COLOR: Color = Color::from_rgb(0.05, 0.05, 0.05);
-pub const LEGEND_BORDER_COLOR: Color = Color::from_rgb(0.5, 0.5, 0.5);
-pub const LEGEND_TEXT_COLOR: Color = Color::from_rgb(0.05, 0.05, 0.05);
-pub const LEGEND_SYMBOL_STROKE_COLOR: Color = Color::from_rgb(0.4, 0.4, 0.4);
 
 pub const PLOT_SPACE: f32 = 10.0;
 pub const PLOT_LEGEMD_SPACE: f32 = 10.0;
@@ -105,7 +102,18 @@ pub const PLOT_SHAPE_SIZE: f32 = 10.0;
 pub const PLOT_TICK_LABEL_SPACE: f32 = 8.0;
 pub const PLOT_TICK_SIZE: f32 = 28.0;
 pub const PLOT_CA_CO_Y_SPACE: f32 = 12.0;
-pub const PLOT_X_AXIS: [isize; 8] = [0, 125, 250, 500, 1000, 2000, 4000, 8000];
+pub const PLOT_OCTAVES: [u32; 7] = [125, 250, 500, 1000, 2000, 4000, 8000];
+pub const PLOT_INTER_OCTAVES: [u32; 4] = [750, 1500, 3000, 6000];
+pub const PLOT_EXTENDED: [u32; 5] = [10000, 11200, 12500, 14000, 16000];
+
+pub const DEGREE_OF_LOSS_COLORS: [Color; 5] = [
+    Color::from_rgba(0.4, 0.7, 0.4, 0.08),
+    Color::from_rgba(0.95, 0.85, 0.3, 0.1),
+    Color::from_rgba(0.95, 0.6, 0.2, 0.1),
+    Color::from_rgba(0.9, 0.35, 0.2, 0.1),
+    Color::from_rgba(0.6, 0.2, 0.2, 0.12),
+];
+pub const SPEECH_BANANA_COLOR: Color = Color::from_rgba(0.95, 0.75, 0.2, 0.3);
 pub const PLOT_Y_AXIS: [isize; 14] = [-10, 0, 10, 20, 30, 40, 50, 60, 70, 80, 90, 100, 110, 120];
 
 pub const IM_PLOT_TICK_SIZE: f32 = 30.0;
@@ -170,19 +178,19 @@ pub struct CustomButtonStyle;
 impl button::StyleSheet for CustomButtonStyle {
     type Style = Theme;
 
-    fn active(&self, _style: &Self::Style) -> button::Appearance {
+    fn active(&self, style: &Self::Style) -> button::Appearance {
         button::Appearance {
             background: None,
-            text_color: Color::from_rgb(0.2, 0.3, 0.1),
+            text_color: TrackTheme::of(style).link,
             border_width: 0.,
             ..Default::default()
         }
     }
 
-    fn hovered(&self, _style: &Self::Style) -> button::Appearance {
+    fn hovered(&self, style: &Self::Style) -> button::Appearance {
         button::Appearance {
             background: None,
-            text_color: Color::from_rgb(0.1, 0.15, 0.8),
+            text_color: TrackTheme::of(style).link_hovered,
             ..Default::default()
         }
     }
@@ -207,13 +215,14 @@ pub struct TitleContainerCustomStyle;
 impl container::StyleSheet for TitleContainerCustomStyle {
     type Style = Theme;
 
-    fn appearance(&self, _style: &Self::Style) -> container::Appearance {
+    fn appearance(&self, style: &Self::Style) -> container::Appearance {
+        let track = TrackTheme::of(style);
         container::Appearance {
-            text_color: Some(SECTION_TITLE_TEXT_COLOR),
-            background: Some(GRAY.into()),
+            text_color: Some(track.section_title_text),
+            background: Some(track.section_background.into()),
             border_radius: 25.0.into(),
             border_width: 1.0,
-            border_color: Color::from_rgb(0.5, 0.25, 0.25),
+            border_color: track.section_border,
         }
     }
 }
@@ -222,13 +231,31 @@ pub struct LegendCustomStyle;
 impl container::StyleSheet for LegendCustomStyle {
     type Style = Theme;
 
-    fn appearance(&self, _style: &Self::Style) -> container::Appearance {
+    fn appearance(&self, style: &Self::Style) -> container::Appearance {
+        let track = TrackTheme::of(style);
         container::Appearance {
-            text_color: Some(LEGEND_TEXT_COLOR),
+            text_color: Some(track.legend_text),
             background: None,
             border_radius: 6.0.into(),
             border_width: 2.0,
-            border_color: LEGEND_BORDER_COLOR,
+            border_color: track.legend_border,
+        }
+    }
+}
+
+pub const PRINT_PREVIEW_BACKGROUND: Color = Color::from_rgb(0.55, 0.55, 0.57);
+
+pub struct PrintPreviewCustomStyle;
+impl container::StyleSheet for PrintPreviewCustomStyle {
+    type Style = Theme;
+
+    fn appearance(&self, _style: &Self::Style) -> container::Appearance {
+        container::Appearance {
+            text_color: None,
+            background: Some(PRINT_PREVIEW_BACKGROUND.into()),
+            border_radius: 0.0.into(),
+            border_width: 0.0,
+            border_color: Color::TRANSPARENT,
         }
     }
 }
@@ -0,0 +1,237 @@
+use iced::keyboard::KeyCode;
+
+use crate::config::{PLOT_EXTENDED, PLOT_INTER_OCTAVES, PLOT_OCTAVES, PLOT_Y_AXIS};
+use crate::plot::{Conduction, EarSide, Threshold, ThresholdModifier, Thresholds};
+use crate::util::AudioRox;
+
+const LEVEL_STEP: f32 = 5.0;
+const MIN_LEVEL: f32 = PLOT_Y_AXIS[0] as f32;
+const MAX_LEVEL: f32 = PLOT_Y_AXIS[PLOT_Y_AXIS.len() - 1] as f32;
+
+/// Curve edited by the cursor. B switches between the two curves of the chart: air and
+/// bone conduction on the charts of the ears, without and with hearing aids on the
+/// binaural chart. U switches the charts of the ears to the discomfort levels and back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CursorCurve {
+    Air,
+    Bone,
+    Discomfort,
+    Unaided,
+    Aided,
+}
+
+impl CursorCurve {
+    /// The curve of the same kind on the chart of the ear.
+    pub fn on(self, ear: EarSide) -> Self {
+        match (ear, self) {
+            (EarSide::Free, CursorCurve::Air | CursorCurve::Bone | CursorCurve::Discomfort) => {
+                CursorCurve::Unaided
+            }
+            (EarSide::Right | EarSide::Left, CursorCurve::Unaided | CursorCurve::Aided) => CursorCurve::Air,
+            (_, curve) => curve,
+        }
+    }
+
+    fn switched(self) -> Self {
+        match self {
+            CursorCurve::Air => CursorCurve::Bone,
+            CursorCurve::Bone | CursorCurve::Discomfort => CursorCurve::Air,
+            CursorCurve::Unaided => CursorCurve::Aided,
+            CursorCurve::Aided => CursorCurve::Unaided,
+        }
+    }
+}
+
+/// Position on the audiograms where thresholds are entered with the keyboard.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudiogramCursor {
+    pub ear: EarSide,
+    pub curve: CursorCurve,
+    pub frequency: u32,
+    pub level: f32,
+    /// Level being typed, recorded at the frequency of the cursor with Enter.
+    pub typed: String,
+}
+
+impl AudiogramCursor {
+    pub fn new(ear: EarSide, frequency: u32, level: f32) -> Self {
+        Self {
+            ear,
+            curve: CursorCurve::Air.on(ear),
+            frequency,
+            level: snap_level(level),
+            typed: String::new(),
+        }
+    }
+
+    /// Moves to the next or previous frequency. The chart of the right ear is on the
+    /// left of the page, so going past its last frequency continues on the left ear.
+    fn step_frequency(&mut self, step: isize, extended: bool) {
+        let frequencies = frequencies(extended);
+        let index = frequencies
+            .iter()
+            .position(|f| *f >= self.frequency)
+            .unwrap_or(frequencies.len() - 1) as isize
+            + step;
+
+        if index < 0 {
+            if self.ear == EarSide::Left {
+                self.ear = EarSide::Right;
+                self.frequency = frequencies[frequencies.len() - 1];
+            }
+        } else if index as usize >= frequencies.len() {
+            if self.ear == EarSide::Right {
+                self.ear = EarSide::Left;
+                self.frequency = frequencies[0];
+            }
+        } else {
+            self.frequency = frequencies[index as usize];
+        }
+    }
+
+    fn type_key(&mut self, key: KeyCode) {
+        let character = match key {
+            KeyCode::Minus | KeyCode::NumpadSubtract if self.typed.is_empty() => '-',
+            KeyCode::Key0 | KeyCode::Numpad0 => '0',
+            KeyCode::Key1 | KeyCode::Numpad1 => '1',
+            KeyCode::Key2 | KeyCode::Numpad2 => '2',
+            KeyCode::Key3 | KeyCode::Numpad3 => '3',
+            KeyCode::Key4 | KeyCode::Numpad4 => '4',
+            KeyCode::Key5 | KeyCode::Numpad5 => '5',
+            KeyCode::Key6 | KeyCode::Numpad6 => '6',
+            KeyCode::Key7 | KeyCode::Numpad7 => '7',
+            KeyCode::Key8 | KeyCode::Numpad8 => '8',
+            KeyCode::Key9 | KeyCode::Numpad9 => '9',
+            _ => return,
+        };
+        if self.typed.len() < 4 {
+            self.typed.push(character);
+        }
+    }
+}
+
+/// Frequencies of the audiograms, in increasing order.
+pub fn frequencies(extended: bool) -> Vec<u32> {
+    let extended: &[u32] = if extended { &PLOT_EXTENDED } else { &[] };
+    let mut frequencies: Vec<u32> = PLOT_OCTAVES
+        .iter()
+        .chain(PLOT_INTER_OCTAVES.iter())
+        .chain(extended)
+        .copied()
+        .collect();
+    frequencies.sort();
+    frequencies
+}
+
+/// Rounds to the step of the audiometer and keeps the level on the chart.
+pub fn snap_level(level: f32) -> f32 {
+    ((level / LEVEL_STEP).round() * LEVEL_STEP).clamp(MIN_LEVEL, MAX_LEVEL)
+}
+
+impl AudioRox {
+    /// Thresholds edited by the cursor. The binaural chart holds the sound-field
+    /// thresholds.
+    fn cursor_thresholds(&mut self, ear: EarSide, curve: CursorCurve) -> &mut Thresholds {
+        let audiogram = match ear {
+            EarSide::Right => &mut self.audiogram_right,
+            EarSide::Left => &mut self.audiogram_left,
+            EarSide::Free => {
+                return match curve {
+                    CursorCurve::Aided => &mut self.free_field.aided,
+                    _ => &mut self.free_field.unaided,
+                }
+            }
+        };
+        match curve {
+            CursorCurve::Bone => audiogram.thresholds_mut(Conduction::Bone),
+            CursorCurve::Discomfort => &mut audiogram.discomfort,
+            _ => audiogram.thresholds_mut(Conduction::Air),
+        }
+    }
+
+    /// Records the stimulus a sound-field threshold of a child was obtained with.
+    fn record_stimulus(&mut self, ear: EarSide, frequency: u32) {
+        if ear == EarSide::Free && self.paediatric.enabled {
+            self.paediatric.stimuli.insert(frequency, self.paediatric.stimulus);
+        }
+    }
+
+    /// Forgets the stimulus at a frequency once the sound field has no threshold there.
+    fn forget_stimulus(&mut self, ear: EarSide, frequency: u32) {
+        let free_field = &self.free_field;
+        if ear == EarSide::Free && !free_field.unaided.contains_key(&frequency) && !free_field.aided.contains_key(&frequency) {
+            self.paediatric.stimuli.remove(&frequency);
+        }
+    }
+
+    /// Toggles a modifier of the threshold at the cursor, recording the level of the
+    /// cursor first when there is none.
+    pub fn toggle_modifier(&mut self, modifier: ThresholdModifier) {
+        let Some(cursor) = &self.audiogram_cursor else {
+            return;
+        };
+        let (ear, curve, frequency, level) = (cursor.ear, cursor.curve, cursor.frequency, cursor.level);
+        let thresholds = self.cursor_thresholds(ear, curve);
+        let recorded = !thresholds.contains_key(&frequency);
+        thresholds
+            .entry(frequency)
+            .or_insert(Threshold { level, ..Threshold::default() })
+            .toggle(modifier);
+        if recorded {
+            self.record_stimulus(ear, frequency);
+        }
+    }
+
+    /// Arrows move the cursor, showing it first if needed. A typed level is recorded
+    /// with Enter, or the level of the cursor when nothing was typed. N, V and M toggle
+    /// the modifiers of the threshold at the cursor.
+    pub fn audiogram_key(&mut self, key: KeyCode) {
+        let extended = self.extended_frequencies;
+
+        let Some(cursor) = &mut self.audiogram_cursor else {
+            if let KeyCode::Left | KeyCode::Right = key {
+                self.audiogram_cursor = Some(AudiogramCursor::new(EarSide::Right, 1000, 20.0));
+            }
+            return;
+        };
+
+        match key {
+            KeyCode::Left => cursor.step_frequency(-1, extended),
+            KeyCode::Right => cursor.step_frequency(1, extended),
+            KeyCode::Up => cursor.level = snap_level(cursor.level - LEVEL_STEP),
+            KeyCode::Down => cursor.level = snap_level(cursor.level + LEVEL_STEP),
+            KeyCode::B => cursor.curve = cursor.curve.switched(),
+            KeyCode::U if cursor.ear != EarSide::Free => {
+                cursor.curve = match cursor.curve {
+                    CursorCurve::Discomfort => CursorCurve::Air,
+                    _ => CursorCurve::Discomfort,
+                }
+            }
+            KeyCode::N => self.toggle_modifier(ThresholdModifier::NoResponse),
+            KeyCode::V => self.toggle_modifier(ThresholdModifier::Vibrotactile),
+            KeyCode::M => self.toggle_modifier(ThresholdModifier::InsufficientMasking),
+            KeyCode::Backspace => {
+                cursor.typed.pop();
+            }
+            KeyCode::Delete => {
+                let (ear, curve, frequency) = (cursor.ear, cursor.curve, cursor.frequency);
+                self.cursor_thresholds(ear, curve).remove(&frequency);
+                self.forget_stimulus(ear, frequency);
+            }
+            KeyCode::Enter | KeyCode::NumpadEnter => {
+                if let Ok(level) = cursor.typed.parse::<f32>() {
+                    cursor.level = snap_level(level);
+                }
+                cursor.typed.clear();
+
+                let (ear, curve, frequency, level) = (cursor.ear, cursor.curve, cursor.frequency, cursor.level);
+                self.cursor_thresholds(ear, curve)
+                    .entry(frequency)
+                    .or_default()
+                    .level = level;
+                self.record_stimulus(ear, frequency);
+            }
+            key => cursor.type_key(key),
+        }
+    }
+}
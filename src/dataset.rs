@@ -0,0 +1,303 @@
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::field::{ear_key, parse_entry, Column, FieldPath, Section};
+use crate::handicap::{self, Bareme, Formula, HandicapResult};
+use crate::partners::{get_partner_name, PartnerAndSuccursale};
+use crate::cursor::frequencies;
+use crate::plot::{Conduction, EarSide, Threshold, THRESHOLD_MODIFIERS};
+use crate::recovery::write_atomically;
+use crate::settings::UserSettings;
+use crate::util::AudioRox;
+
+/// Table entries of the export, by section. The columns are named after the keys of
+/// `FieldPath`, without the ear, so that they stay the same from one export to the next.
+const TABLE_COLUMNS: [(Section, &[Column]); 6] = [
+    (Section::TonalAverage, &[Column::Msp, Column::Msp4, Column::Fletcher]),
+    (Section::SpeechThreshold, &[Column::Srp, Column::Sdp, Column::Comfort, Column::List]),
+    (
+        Section::SpeechIdentification,
+        &[Column::Level1, Column::List1, Column::Result1, Column::Level2, Column::List2, Column::Result2],
+    ),
+    (Section::Tympanometry, &[Column::Volume, Column::Pressure, Column::Compliance]),
+    (Section::ReflexIpsi, &[Column::Khz500, Column::Khz1000, Column::Khz2000, Column::Khz4000]),
+    (Section::ReflexContra, &[Column::Khz500, Column::Khz1000, Column::Khz2000, Column::Khz4000]),
+];
+
+/// Columns describing the visit, before the table entries.
+const VISIT_COLUMNS: [&str; 7] = ["file", "visit_date", "patient", "partner", "examiner", "revision", "ear"];
+
+/// Type of the tympanogram derived from the entries, after the columns of the tympanometry.
+const TYMPANOGRAM_TYPE: &str = "tympanometry.type";
+
+/// Percentages of hearing loss of the ear and of both ears, by formula, after the table
+/// entries. Empty on the binaural row.
+const HANDICAP_COLUMNS: [(Formula, &str, &str); 2] = [
+    (Formula::Ama, "handicap.ama", "handicap.ama.binaural"),
+    (Formula::Bareme, "handicap.bareme", "handicap.bareme.binaural"),
+];
+
+/// Curves of the audiograms, by name, after the percentages of hearing loss. Each has
+/// a column of levels in dB HL and one of modifiers at every frequency of the charts,
+/// including the extended ones so that the columns do not depend on the report.
+const THRESHOLD_CURVES: [(Conduction, &str); 2] = [(Conduction::Air, "air"), (Conduction::Bone, "bone")];
+
+pub fn header() -> Vec<String> {
+    let mut header: Vec<String> = VISIT_COLUMNS.iter().map(|column| column.to_string()).collect();
+    for (section, columns) in TABLE_COLUMNS {
+        for column in columns {
+            header.push(format!("{}.{}", section.key(), column.key()));
+        }
+        if section == Section::Tympanometry {
+            header.push(TYMPANOGRAM_TYPE.to_string());
+        }
+    }
+    for (_, monaural, binaural) in HANDICAP_COLUMNS {
+        header.push(monaural.to_string());
+        header.push(binaural.to_string());
+    }
+    for (_, curve) in THRESHOLD_CURVES {
+        for frequency in frequencies(true) {
+            header.push(format!("{}.{}", curve, frequency));
+            header.push(format!("{}.{}.modifiers", curve, frequency));
+        }
+    }
+    header
+}
+
+/// Modifiers of a threshold, by key, separated by spaces.
+fn modifiers(threshold: &Threshold) -> String {
+    THRESHOLD_MODIFIERS
+        .iter()
+        .filter(|modifier| threshold.has(**modifier))
+        .map(|modifier| modifier.key())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Jerger type of the tympanogram, from the peak pressure in daPa and the compliance in
+/// mL. A pressure that is not a number, such as "NP", means that there was no peak.
+/// Empty when nothing was entered.
+pub fn tympanogram_type(pressure: &str, compliance: &str) -> &'static str {
+    if pressure.trim().is_empty() && compliance.trim().is_empty() {
+        return "";
+    }
+    let compliance = parse_entry(compliance);
+    let Some(pressure) = parse_entry(pressure) else {
+        return "B";
+    };
+    match compliance {
+        Some(compliance) if compliance < 0.1 => "B",
+        _ if pressure < -100.0 => "C",
+        Some(compliance) if compliance < 0.3 => "As",
+        Some(compliance) if compliance > 1.75 => "Ad",
+        _ => "A",
+    }
+}
+
+/// Date of the visit: the day the report was finalized, or the day the file was last
+/// saved for a draft.
+fn visit_date(audiorox: &AudioRox, path: &Path) -> String {
+    if let Some(signature) = &audiorox.signature {
+        return signature.signed_at.get(..10).unwrap_or(&signature.signed_at).to_string();
+    }
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .map(|modified| chrono::DateTime::<chrono::Local>::from(modified).format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+/// One row per ear of the report, and one for the binaural results when there are any.
+pub fn rows(audiorox: &AudioRox, path: &Path, bareme: &Bareme) -> Vec<Vec<String>> {
+    let visit = [
+        path.display().to_string(),
+        visit_date(audiorox, path),
+        audiorox.patient_name.clone(),
+        match audiorox.partner {
+            PartnerAndSuccursale::None => String::new(),
+            partner => get_partner_name(&partner).to_string(),
+        },
+        audiorox.examiner.name.clone(),
+        audiorox.signature.as_ref().map_or(String::new(), |signature| signature.revision.to_string()),
+    ];
+
+    let handicaps: Vec<HandicapResult> = HANDICAP_COLUMNS
+        .iter()
+        .map(|(formula, _, _)| {
+            handicap::compute(*formula, bareme, &audiorox.audiogram_right.air, &audiorox.audiogram_left.air)
+        })
+        .collect();
+    let percent = |percent: Option<f32>| percent.map_or(String::new(), |percent| format!("{:.1}", percent));
+
+    let mut rows = vec![];
+    for ear in [EarSide::Right, EarSide::Left, EarSide::Free] {
+        let mut row: Vec<String> = visit.to_vec();
+        row.push(ear_key(ear).to_string());
+
+        let mut has_entries = false;
+        for (section, columns) in TABLE_COLUMNS {
+            for column in columns {
+                let entry = audiorox
+                    .field(FieldPath::new(ear, section, *column))
+                    .map_or("", |entry| entry.trim());
+                has_entries |= !entry.is_empty();
+                row.push(entry.to_string());
+            }
+            if section == Section::Tympanometry {
+                let entry = |column| audiorox.field(FieldPath::new(ear, section, column)).map_or("", |e| e.as_str());
+                row.push(tympanogram_type(entry(Column::Pressure), entry(Column::Compliance)).to_string());
+            }
+        }
+
+        for handicap in &handicaps {
+            let monaural = match ear {
+                EarSide::Right => handicap.monaural_right,
+                EarSide::Left => handicap.monaural_left,
+                EarSide::Free => None,
+            };
+            let binaural = handicap.binaural.filter(|_| ear != EarSide::Free);
+            row.push(percent(monaural));
+            row.push(percent(binaural));
+        }
+
+        let audiogram = match ear {
+            EarSide::Right => Some(&audiorox.audiogram_right),
+            EarSide::Left => Some(&audiorox.audiogram_left),
+            EarSide::Free => None,
+        };
+        for (conduction, _) in THRESHOLD_CURVES {
+            for frequency in frequencies(true) {
+                let threshold = audiogram.and_then(|audiogram| audiogram.thresholds(conduction).get(&frequency));
+                row.push(threshold.map_or(String::new(), |threshold| threshold.level.to_string()));
+                row.push(threshold.map_or(String::new(), modifiers));
+            }
+        }
+
+        if ear != EarSide::Free || has_entries {
+            rows.push(row);
+        }
+    }
+    rows
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// The report files given, and the ones found in the folders given, in order.
+fn report_files(inputs: &[String]) -> Vec<PathBuf> {
+    fn visit(path: PathBuf, files: &mut Vec<PathBuf>) {
+        if path.is_dir() {
+            let Ok(entries) = std::fs::read_dir(&path) else {
+                return;
+            };
+            let mut entries: Vec<PathBuf> = entries.filter_map(|entry| entry.ok().map(|entry| entry.path())).collect();
+            entries.sort();
+            for entry in entries {
+                if entry.is_dir() || entry.extension().is_some_and(|extension| extension == "json") {
+                    visit(entry, files);
+                }
+            }
+        } else {
+            files.push(path);
+        }
+    }
+
+    let mut files = vec![];
+    for input in inputs {
+        visit(PathBuf::from(input), &mut files);
+    }
+    files
+}
+
+/// Flattens the saved reports into a CSV file, one row per ear per visit, for
+/// `--export-csv <output> <reports or folders>...`. Files that are not reports, such
+/// as the settings, are skipped. The barème of the percentages of hearing loss is the
+/// one of the user settings. Returns the number of reports exported.
+pub fn export_csv(inputs: &[String], output: &str) -> Result<usize, Box<dyn std::error::Error>> {
+    // the byte order mark lets spreadsheets read the accents
+    let mut csv = String::from("\u{feff}");
+    csv.push_str(&header().join(","));
+    csv.push_str("\r\n");
+
+    let bareme = UserSettings::load().bareme;
+    let mut count = 0;
+    for path in report_files(inputs) {
+        let Some(audiorox) = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<Value>(&contents).ok())
+            .filter(|value| value.get("tonal_table_right").is_some())
+            .and_then(|value| serde_json::from_value::<AudioRox>(value).ok())
+        else {
+            eprintln!("Skipped {}: not a report", path.display());
+            continue;
+        };
+
+        for row in rows(&audiorox, &path, &bareme) {
+            csv.push_str(&row.iter().map(|value| csv_field(value)).collect::<Vec<_>>().join(","));
+            csv.push_str("\r\n");
+        }
+        count += 1;
+    }
+
+    write_atomically(output, &csv)?;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value<'a>(row: &'a [String], column: &str) -> &'a str {
+        let index = header().iter().position(|name| name == column).unwrap();
+        &row[index]
+    }
+
+    #[test]
+    fn tympanogram_types() {
+        assert_eq!(tympanogram_type("", " "), "");
+        assert_eq!(tympanogram_type("-20", "0,9"), "A");
+        assert_eq!(tympanogram_type("", "0.9"), "B");
+        assert_eq!(tympanogram_type("NP", "0.9"), "B");
+        assert_eq!(tympanogram_type("0", "0.05"), "B");
+        assert_eq!(tympanogram_type("-150", "0.9"), "C");
+        assert_eq!(tympanogram_type("-150", ""), "C");
+        assert_eq!(tympanogram_type("10", "0.2"), "As");
+        assert_eq!(tympanogram_type("10", "2.0"), "Ad");
+        assert_eq!(tympanogram_type("10", ""), "A");
+    }
+
+    #[test]
+    fn threshold_columns_do_not_depend_on_the_report() {
+        let mut audiorox = AudioRox::default();
+        let empty = rows(&audiorox, Path::new("empty.json"), &Bareme::default());
+
+        audiorox.audiogram_right.air.insert(1000, Threshold { level: 25.0, ..Threshold::default() });
+        audiorox.audiogram_right.air.insert(
+            12500,
+            Threshold { level: 90.0, no_response: true, vibrotactile: true, ..Threshold::default() },
+        );
+        audiorox.audiogram_left.bone.insert(500, Threshold { level: 10.0, insufficient_masking: true, ..Threshold::default() });
+        let filled = rows(&audiorox, Path::new("filled.json"), &Bareme::default());
+
+        assert_eq!(empty.len(), 2);
+        assert_eq!(filled.len(), 2);
+        for row in empty.iter().chain(&filled) {
+            assert_eq!(row.len(), header().len());
+        }
+        let (right, left) = (&filled[0], &filled[1]);
+        assert_eq!(value(right, "air.1000"), "25");
+        assert_eq!(value(right, "air.1000.modifiers"), "");
+        assert_eq!(value(right, "air.12500"), "90");
+        assert_eq!(value(right, "air.12500.modifiers"), "no_response vibrotactile");
+        assert_eq!(value(right, "bone.500"), "");
+        assert_eq!(value(left, "bone.500"), "10");
+        assert_eq!(value(left, "bone.500.modifiers"), "insufficient_masking");
+        assert_eq!(value(left, "air.1000"), "");
+    }
+}
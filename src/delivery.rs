@@ -0,0 +1,492 @@
+use std::fmt;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment, Mailbox, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{SmtpTransport, Transport};
+
+use serde::{Deserialize, Serialize};
+
+use crate::distribution::{self, Recipient};
+use crate::locale::tr;
+use crate::partners::{get_chosen_succursale, get_partner_name, PartnerAndSuccursale};
+use crate::util::AudioRox;
+
+/// Log of the copies sent of a report, next to its file: `data.deliveries.jsonl` for
+/// `data.json`. One JSON record per line, appended after each delivery.
+fn log_path(report_path: &str) -> PathBuf {
+    Path::new(report_path).with_extension("deliveries.jsonl")
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SmtpSecurity {
+    /// Plain connection, for a relay on the local network or a test server.
+    #[default]
+    None,
+    StartTls,
+    Tls,
+}
+
+pub const SMTP_SECURITIES: [SmtpSecurity; 3] = [SmtpSecurity::None, SmtpSecurity::StartTls, SmtpSecurity::Tls];
+
+impl fmt::Display for SmtpSecurity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                SmtpSecurity::None => "Aucune",
+                SmtpSecurity::StartTls => "STARTTLS",
+                SmtpSecurity::Tls => "TLS",
+            }
+        )
+    }
+}
+
+/// Email address or fax number of a partner clinic, to send it its copy.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartnerContact {
+    pub partner: PartnerAndSuccursale,
+    pub contact: String,
+}
+
+/// Outgoing mail server, kept in the user settings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeliverySettings {
+    pub host: String,
+    pub port: u16,
+    pub security: SmtpSecurity,
+    /// Left empty when the server does not require authentication.
+    pub username: String,
+    /// Asked again in each session, never written to the settings file.
+    #[serde(skip)]
+    pub password: String,
+    /// Address the copies are sent from.
+    pub sender: String,
+    /// Domain of the fax-to-email gateway: a fax number is sent to `<number>@<domain>`.
+    pub fax_gateway: String,
+    pub partner_contacts: Vec<PartnerContact>,
+}
+
+impl Default for DeliverySettings {
+    fn default() -> Self {
+        Self {
+            host: String::from("localhost"),
+            port: 25,
+            security: SmtpSecurity::None,
+            username: String::new(),
+            password: String::new(),
+            sender: String::new(),
+            fax_gateway: String::new(),
+            partner_contacts: vec![],
+        }
+    }
+}
+
+impl DeliverySettings {
+    pub fn set(&mut self, field: DeliveryField, value: String) {
+        match field {
+            DeliveryField::Host => self.host = value,
+            DeliveryField::Port => {
+                if value.is_empty() {
+                    self.port = 0;
+                } else if let Ok(port) = value.parse() {
+                    self.port = port;
+                }
+            }
+            DeliveryField::Username => self.username = value,
+            DeliveryField::Password => self.password = value,
+            DeliveryField::Sender => self.sender = value,
+            DeliveryField::FaxGateway => self.fax_gateway = value,
+        }
+    }
+
+    pub fn get(&self, field: DeliveryField) -> String {
+        match field {
+            DeliveryField::Host => self.host.clone(),
+            DeliveryField::Port if self.port == 0 => String::new(),
+            DeliveryField::Port => self.port.to_string(),
+            DeliveryField::Username => self.username.clone(),
+            DeliveryField::Password => self.password.clone(),
+            DeliveryField::Sender => self.sender.clone(),
+            DeliveryField::FaxGateway => self.fax_gateway.clone(),
+        }
+    }
+
+    pub fn partner_contact(&self, partner: PartnerAndSuccursale) -> &str {
+        self.partner_contacts
+            .iter()
+            .find(|contact| contact.partner == partner)
+            .map_or("", |contact| &contact.contact)
+    }
+
+    pub fn set_partner_contact(&mut self, partner: PartnerAndSuccursale, contact: String) {
+        self.partner_contacts.retain(|c| c.partner != partner);
+        if !contact.is_empty() {
+            self.partner_contacts.push(PartnerContact { partner, contact });
+        }
+    }
+
+    /// Email address a copy is sent to: the contact itself when it is an email address,
+    /// or the fax number at the fax-to-email gateway.
+    pub fn address(&self, contact: &str) -> Option<String> {
+        let contact = contact.trim();
+        if contact.contains('@') {
+            return Some(contact.to_string());
+        }
+
+        let number: String = contact.chars().filter(char::is_ascii_digit).collect();
+        if number.is_empty() || self.fax_gateway.trim().is_empty() {
+            return None;
+        }
+        Some(format!("{}@{}", number, self.fax_gateway.trim()))
+    }
+
+    fn transport(&self) -> Result<SmtpTransport, lettre::transport::smtp::Error> {
+        let builder = match self.security {
+            SmtpSecurity::None => SmtpTransport::builder_dangerous(&self.host),
+            SmtpSecurity::StartTls => SmtpTransport::starttls_relay(&self.host)?,
+            SmtpSecurity::Tls => SmtpTransport::relay(&self.host)?,
+        }
+        .port(self.port);
+
+        Ok(if self.username.is_empty() {
+            builder.build()
+        } else {
+            builder
+                .credentials(Credentials::new(self.username.clone(), self.password.clone()))
+                .build()
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryField {
+    Host,
+    Port,
+    Username,
+    Password,
+    Sender,
+    FaxGateway,
+}
+
+pub const DELIVERY_FIELDS: [DeliveryField; 6] = [
+    DeliveryField::Host,
+    DeliveryField::Port,
+    DeliveryField::Username,
+    DeliveryField::Password,
+    DeliveryField::Sender,
+    DeliveryField::FaxGateway,
+];
+
+impl DeliveryField {
+    /// French label of the field in the delivery settings.
+    pub fn label(&self) -> &'static str {
+        match self {
+            DeliveryField::Host => "Serveur SMTP",
+            DeliveryField::Port => "Port",
+            DeliveryField::Username => "Utilisateur",
+            DeliveryField::Password => "Mot de passe (pour cette session)",
+            DeliveryField::Sender => "Adresse de l'expéditeur",
+            DeliveryField::FaxGateway => "Passerelle de télécopie",
+        }
+    }
+}
+
+/// Copy of the report waiting to be sent.
+#[derive(Debug, Clone)]
+pub struct Delivery {
+    pub recipient: String,
+    /// None when the recipient has no email address nor fax number that can be used.
+    pub address: Option<String>,
+    pub file: String,
+}
+
+/// Copies of a report to send, with what the messages say.
+#[derive(Debug, Clone)]
+pub struct DeliveryJob {
+    pub settings: DeliverySettings,
+    pub patient: String,
+    /// Hash of the finalized report, to know which revision was sent.
+    pub report_hash: Option<String>,
+    pub subject: String,
+    pub body: String,
+    pub deliveries: Vec<Delivery>,
+}
+
+/// Entry of the delivery log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeliveryRecord {
+    pub sent_at: String,
+    pub patient: String,
+    pub report_hash: Option<String>,
+    pub recipient: String,
+    pub address: String,
+    pub file: String,
+    /// Why the copy was not sent, None when the server accepted it.
+    pub error: Option<String>,
+}
+
+/// The partner clinic chosen for the report, as a recipient of a copy.
+fn partner_addressee(audiorox: &AudioRox) -> Option<Recipient> {
+    if audiorox.partner == PartnerAndSuccursale::None {
+        return None;
+    }
+    let (clinic, succursale) = get_chosen_succursale(&audiorox.partner);
+    let name = if clinic.is_empty() { get_partner_name(&audiorox.partner).to_string() } else { clinic };
+    Some(Recipient {
+        selected: true,
+        name,
+        organization: String::new(),
+        contact: audiorox.settings.delivery.partner_contact(audiorox.partner).to_string(),
+        address: succursale,
+    })
+}
+
+/// Recipients a copy is sent to: the checked recipients of the CC list, then the
+/// partner clinic.
+pub fn addressees(audiorox: &AudioRox) -> Vec<Recipient> {
+    let mut addressees = distribution::cc_addressees(audiorox);
+    addressees.extend(partner_addressee(audiorox));
+    addressees
+}
+
+/// Generates the copies of the recipients, to be sent with `send`.
+pub fn prepare(audiorox: &AudioRox) -> Result<DeliveryJob, Box<dyn std::error::Error>> {
+    let settings = &audiorox.settings.delivery;
+    let lang = audiorox.report_language;
+
+    let addressees = addressees(audiorox);
+    let files = distribution::distribute(audiorox, &addressees)?;
+
+    let deliveries = addressees
+        .iter()
+        .zip(files)
+        .map(|(recipient, file)| Delivery {
+            recipient: recipient.name.clone(),
+            address: settings.address(&recipient.contact),
+            file,
+        })
+        .collect();
+
+    Ok(DeliveryJob {
+        settings: settings.clone(),
+        patient: audiorox.patient_name.clone(),
+        report_hash: audiorox.signature.as_ref().map(|signature| signature.hash.clone()),
+        subject: format!("{} {}", tr(lang, "Évaluation audiologique —"), audiorox.patient_name),
+        body: tr(lang, "Veuillez trouver ci-joint le rapport d'évaluation audiologique.").to_string(),
+        deliveries,
+    })
+}
+
+fn email(job: &DeliveryJob, address: &str, file: &str) -> Result<lettre::Message, Box<dyn std::error::Error>> {
+    let sender: Mailbox = job.settings.sender.parse()?;
+    let attachment_name = std::path::Path::new(file)
+        .file_name()
+        .map_or_else(|| file.to_string(), |name| name.to_string_lossy().to_string());
+
+    Ok(lettre::Message::builder()
+        .from(sender)
+        .to(address.parse()?)
+        .subject(job.subject.as_str())
+        .multipart(
+            MultiPart::mixed()
+                .singlepart(SinglePart::plain(job.body.clone()))
+                .singlepart(
+                    Attachment::new(attachment_name)
+                        .body(std::fs::read(file)?, ContentType::parse("application/pdf")?),
+                ),
+        )?)
+}
+
+/// Sends the copies through the mail server, one message per recipient. Blocks until
+/// the server has answered for every copy.
+pub fn send(job: DeliveryJob) -> Vec<DeliveryRecord> {
+    let transport = job.settings.transport();
+
+    job.deliveries
+        .iter()
+        .map(|delivery| {
+            let result = match (&delivery.address, &transport) {
+                (None, _) => Err("Aucune adresse ni télécopieur utilisable".to_string()),
+                (_, Err(e)) => Err(e.to_string()),
+                (Some(address), Ok(transport)) => email(&job, address, &delivery.file)
+                    .and_then(|email| Ok(transport.send(&email).map(|_| ())?))
+                    .map_err(|e| e.to_string()),
+            };
+
+            DeliveryRecord {
+                sent_at: chrono::Local::now().to_rfc3339(),
+                patient: job.patient.clone(),
+                report_hash: job.report_hash.clone(),
+                recipient: delivery.recipient.clone(),
+                address: delivery.address.clone().unwrap_or_default(),
+                file: delivery.file.clone(),
+                error: result.err(),
+            }
+        })
+        .collect()
+}
+
+/// Runs `send` on a thread of its own, so that the interface keeps responding while
+/// the server answers.
+pub async fn send_in_background(job: DeliveryJob) -> Vec<DeliveryRecord> {
+    let (sender, receiver) = tokio::sync::oneshot::channel();
+    std::thread::spawn(move || {
+        let _ = sender.send(send(job));
+    });
+    receiver.await.unwrap_or_default()
+}
+
+pub fn append_log(report_path: &str, records: &[DeliveryRecord]) -> std::io::Result<()> {
+    append_to(&log_path(report_path), records)
+}
+
+fn append_to(path: &Path, records: &[DeliveryRecord]) -> std::io::Result<()> {
+    let mut log = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+    for record in records {
+        writeln!(log, "{}", serde_json::to_string(record)?)?;
+    }
+    Ok(())
+}
+
+/// Deliveries of the log of the report for the patient, oldest first. Lines that cannot
+/// be read are skipped, and the copies sent for other patients are left in the file.
+pub fn read_log(report_path: &str, patient: &str) -> Vec<DeliveryRecord> {
+    read_from(&log_path(report_path), patient)
+}
+
+fn read_from(path: &Path, patient: &str) -> Vec<DeliveryRecord> {
+    std::fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| serde_json::from_str::<DeliveryRecord>(line).ok())
+        .filter(|record| record.patient == patient)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader};
+    use std::net::TcpListener;
+
+    use super::*;
+
+    /// Mail server accepting every message, answering `connections` clients and
+    /// returning the recipients it was given.
+    fn mock_server(connections: usize) -> (u16, std::thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let server = std::thread::spawn(move || {
+            let mut recipients = vec![];
+            for _ in 0..connections {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut writer = stream;
+                writer.write_all(b"220 mock ESMTP\r\n").unwrap();
+
+                let mut in_data = false;
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap() > 0 {
+                    let command = line.trim_end().to_string();
+                    line.clear();
+                    let reply: &[u8] = if in_data {
+                        if command != "." {
+                            continue;
+                        }
+                        in_data = false;
+                        b"250 queued\r\n"
+                    } else if command.starts_with("EHLO") {
+                        b"250 mock\r\n"
+                    } else if let Some(to) = command.strip_prefix("RCPT TO:") {
+                        recipients.push(to.trim_matches(['<', '>', ' ']).to_string());
+                        b"250 OK\r\n"
+                    } else if command == "DATA" {
+                        in_data = true;
+                        b"354 go on\r\n"
+                    } else if command == "QUIT" {
+                        writer.write_all(b"221 bye\r\n").unwrap();
+                        break;
+                    } else {
+                        b"250 OK\r\n"
+                    };
+                    writer.write_all(reply).unwrap();
+                }
+            }
+            recipients
+        });
+        (port, server)
+    }
+
+    #[test]
+    fn log_next_to_the_report() {
+        assert_eq!(log_path("data.json"), Path::new("data.deliveries.jsonl"));
+        assert_eq!(log_path("rapports/tremblay.json"), Path::new("rapports/tremblay.deliveries.jsonl"));
+    }
+
+    #[test]
+    fn password_not_saved() {
+        let settings = DeliverySettings { password: String::from("secret"), ..DeliverySettings::default() };
+        let saved = serde_json::to_string(&settings).unwrap();
+        assert!(!saved.contains("secret"));
+        let read: DeliverySettings = serde_json::from_str(&saved).unwrap();
+        assert_eq!(read.password, "");
+    }
+
+    #[test]
+    fn copies_sent_to_the_server_and_logged() {
+        let directory = std::env::temp_dir().join(format!("delivery-test-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let file = directory.join("copy.pdf").display().to_string();
+        std::fs::write(&file, b"%PDF-1.4").unwrap();
+
+        let (port, server) = mock_server(2);
+        let settings = DeliverySettings {
+            host: String::from("127.0.0.1"),
+            port,
+            sender: String::from("clinique@example.com"),
+            fax_gateway: String::from("fax.example.com"),
+            ..DeliverySettings::default()
+        };
+        let delivery = |recipient: &str, contact: &str| Delivery {
+            recipient: recipient.to_string(),
+            address: settings.address(contact),
+            file: file.clone(),
+        };
+        let job = DeliveryJob {
+            settings: settings.clone(),
+            patient: String::from("Tremblay"),
+            report_hash: Some(String::from("abc123")),
+            subject: String::from("Évaluation audiologique — Tremblay"),
+            body: String::from("Veuillez trouver ci-joint le rapport."),
+            deliveries: vec![
+                delivery("Médecin de famille", "medecin@example.com"),
+                delivery("ORL", "(418) 555-0199"),
+                delivery("Patient", ""),
+            ],
+        };
+
+        let records = send(job);
+        assert_eq!(server.join().unwrap(), ["medecin@example.com", "4185550199@fax.example.com"]);
+
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].error, None);
+        assert_eq!(records[1].address, "4185550199@fax.example.com");
+        assert_eq!(records[1].error, None);
+        assert!(records[2].error.is_some());
+        assert!(records.iter().all(|record| record.report_hash.as_deref() == Some("abc123")));
+
+        let log = directory.join("deliveries.jsonl");
+        append_to(&log, &records[..2]).unwrap();
+        append_to(&log, &records[2..]).unwrap();
+        assert_eq!(std::fs::read_to_string(&log).unwrap().lines().count(), 3);
+        assert_eq!(read_from(&log, "Tremblay"), records);
+        assert_eq!(read_from(&log, "Gagnon"), []);
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,308 @@
+use iced::alignment::Horizontal;
+use iced::widget::{column, container, horizontal_space, row, text, vertical_space, Rule};
+use iced::Length;
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::export::{self, ExportDpi, SCREEN_DPI};
+use crate::locale::tr;
+use crate::partners::get_chosen_succursale;
+use crate::print::{self, PRINT_DPI};
+use crate::util::{AudioRox, CC};
+
+/// Directory of the copies generated for the recipients.
+pub const DISTRIBUTION_DIR: &str = "distribution";
+
+/// Recipient of a copy of the report.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Recipient {
+    pub selected: bool,
+    pub name: String,
+    pub organization: String,
+    /// Fax number or email address.
+    pub contact: String,
+    pub address: String,
+}
+
+impl Recipient {
+    pub fn set(&mut self, field: RecipientField, value: String) {
+        *match field {
+            RecipientField::Name => &mut self.name,
+            RecipientField::Organization => &mut self.organization,
+            RecipientField::Contact => &mut self.contact,
+            RecipientField::Address => &mut self.address,
+        } = value;
+    }
+
+    pub fn get(&self, field: RecipientField) -> &str {
+        match field {
+            RecipientField::Name => &self.name,
+            RecipientField::Organization => &self.organization,
+            RecipientField::Contact => &self.contact,
+            RecipientField::Address => &self.address,
+        }
+    }
+}
+
+/// Reports saved before the recipients only had a checkbox per category.
+pub fn deserialize_recipient<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Recipient, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Saved {
+        Checked(bool),
+        Recipient(Recipient),
+    }
+
+    Ok(match Saved::deserialize(deserializer)? {
+        Saved::Checked(selected) => Recipient {
+            selected,
+            ..Recipient::default()
+        },
+        Saved::Recipient(recipient) => recipient,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CCCategory {
+    Patient,
+    Audioprothesiste,
+    FamilyDoctor,
+    Readapt,
+    Orl,
+    Other,
+}
+
+/// Categories in the order of the CC list of the report.
+pub const CC_CATEGORIES: [CCCategory; 6] = [
+    CCCategory::Patient,
+    CCCategory::Audioprothesiste,
+    CCCategory::FamilyDoctor,
+    CCCategory::Readapt,
+    CCCategory::Orl,
+    CCCategory::Other,
+];
+
+impl CCCategory {
+    /// French label of the category, translated with `tr`.
+    pub fn label(&self) -> &'static str {
+        match self {
+            CCCategory::Patient => "Patient",
+            CCCategory::Audioprothesiste => "Audioprothésiste",
+            CCCategory::FamilyDoctor => "Médecin de famille",
+            CCCategory::Readapt => "Centre de réadaptation",
+            CCCategory::Orl => "ORL",
+            CCCategory::Other => "Autre",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecipientField {
+    Name,
+    Organization,
+    Contact,
+    Address,
+}
+
+pub const RECIPIENT_FIELDS: [RecipientField; 4] = [
+    RecipientField::Name,
+    RecipientField::Organization,
+    RecipientField::Contact,
+    RecipientField::Address,
+];
+
+impl RecipientField {
+    /// French label of the field in the recipient editor.
+    pub fn label(&self) -> &'static str {
+        match self {
+            RecipientField::Name => "Nom",
+            RecipientField::Organization => "Organisme",
+            RecipientField::Contact => "Télécopieur ou courriel",
+            RecipientField::Address => "Adresse",
+        }
+    }
+}
+
+impl CC {
+    pub fn recipient(&self, category: CCCategory) -> &Recipient {
+        match category {
+            CCCategory::Patient => &self.patient,
+            CCCategory::Audioprothesiste => &self.audioprothesiste,
+            CCCategory::FamilyDoctor => &self.family_doctor,
+            CCCategory::Readapt => &self.readapt,
+            CCCategory::Orl => &self.orl,
+            CCCategory::Other => &self.other,
+        }
+    }
+
+    pub fn recipient_mut(&mut self, category: CCCategory) -> &mut Recipient {
+        match category {
+            CCCategory::Patient => &mut self.patient,
+            CCCategory::Audioprothesiste => &mut self.audioprothesiste,
+            CCCategory::FamilyDoctor => &mut self.family_doctor,
+            CCCategory::Readapt => &mut self.readapt,
+            CCCategory::Orl => &mut self.orl,
+            CCCategory::Other => &mut self.other,
+        }
+    }
+}
+
+/// Checked recipients of the CC list, named after their category when their name is
+/// not filled in.
+pub fn cc_addressees(audiorox: &AudioRox) -> Vec<Recipient> {
+    let lang = audiorox.report_language;
+    CC_CATEGORIES
+        .iter()
+        .map(|category| (category, audiorox.cc.recipient(*category)))
+        .filter(|(_, recipient)| recipient.selected)
+        .map(|(category, recipient)| {
+            let mut recipient = recipient.clone();
+            if recipient.name.is_empty() {
+                recipient.name = tr(lang, category.label()).to_string();
+            }
+            recipient
+        })
+        .collect()
+}
+
+/// First page of the copy sent to a recipient, at the size of the paper.
+fn render_cover(
+    audiorox: &AudioRox,
+    recipient: &Recipient,
+    page_count: usize,
+    dpi: ExportDpi,
+) -> Result<image::RgbaImage, Box<dyn std::error::Error>> {
+    let lang = audiorox.report_language;
+    let setup = audiorox.page_setup;
+    let (paper_width, paper_height) = setup.paper.size_inches();
+    let page_width = paper_width * SCREEN_DPI as f32;
+    let page_height = paper_height * SCREEN_DPI as f32;
+    let margin = setup.margin.inches() * SCREEN_DPI as f32;
+
+    let (clinic, succursale) = get_chosen_succursale(&audiorox.partner);
+    let date = chrono::Local::now().format("%d-%m-%Y").to_string();
+    let examiner = &audiorox.examiner;
+
+    let mut addressee =
+        column![text(tr(lang, "Destinataire")).size(14), text(&recipient.name).size(24)].spacing(4);
+    for line in [&recipient.organization, &recipient.address, &recipient.contact] {
+        if !line.is_empty() {
+            addressee = addressee.push(text(line).size(18));
+        }
+    }
+
+    let cover = column![
+        row![
+            column![text(clinic).size(20), text(succursale).size(16)],
+            horizontal_space(Length::Fill),
+            text(date).size(16),
+        ],
+        vertical_space(80),
+        addressee,
+        vertical_space(60),
+        Rule::horizontal(1.),
+        text(format!(
+            "{} {}",
+            tr(lang, "Objet : Évaluation audiologique —"),
+            audiorox.patient_name
+        ))
+        .size(18),
+        text(format!("{} {}", tr(lang, "Nombre de pages, incluant celle-ci :"), page_count)).size(18),
+        text(format!(
+            "{} {}, {} {}",
+            tr(lang, "Expéditeur :"),
+            examiner.name,
+            tr(lang, &examiner.title),
+            examiner.licence
+        ))
+        .size(18),
+        Rule::horizontal(1.),
+        vertical_space(Length::Fill),
+        text(tr(
+            lang,
+            "Ce document contient des renseignements personnels et confidentiels destinés \
+             uniquement au destinataire. Si vous l'avez reçu par erreur, veuillez nous en aviser \
+             et le détruire."
+        ))
+        .size(12)
+        .horizontal_alignment(Horizontal::Center)
+        .width(Length::Fill),
+    ]
+    .spacing(8);
+
+    let page = container(cover)
+        .padding(margin)
+        .width(Length::Fixed(page_width))
+        .height(Length::Fixed(page_height));
+
+    export::rasterize(page.into(), page_width, dpi)
+}
+
+/// First path of `{stem}.{extension}`, `{stem}_2.{extension}`, ... that does not exist
+/// yet, so a copy sent earlier the same day is never overwritten.
+fn unused_path(stem: &str, extension: &str) -> String {
+    let mut path = format!("{}.{}", stem, extension);
+    let mut n = 2;
+    while std::path::Path::new(&path).exists() {
+        path = format!("{}_{}.{}", stem, n, extension);
+        n += 1;
+    }
+    path
+}
+
+/// Writes a PDF per recipient, with a cover page addressed to them followed by the
+/// pages of the report. Returns the file written for each recipient.
+///
+/// The files are numbered in the order of the recipients, since two recipients can
+/// have the same name once reduced to the characters allowed in a file name.
+pub fn distribute(
+    audiorox: &AudioRox,
+    addressees: &[Recipient],
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let pages = print::render_pages(audiorox, PRINT_DPI)?;
+    let signature = audiorox.export_signature();
+    std::fs::create_dir_all(DISTRIBUTION_DIR)?;
+
+    let mut written = vec![];
+    for (index, recipient) in addressees.iter().enumerate() {
+        let mut document = vec![render_cover(audiorox, recipient, pages.len() + 1, PRINT_DPI)?];
+        document.extend(pages.iter().cloned());
+
+        let name: String = recipient
+            .name
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect();
+        let export_name = export::default_export_name(audiorox, "pdf");
+        let stem = export_name.trim_end_matches(".pdf");
+        let filename = unused_path(
+            &format!("{}/{}_{}_{}", DISTRIBUTION_DIR, index + 1, name, stem),
+            "pdf",
+        );
+
+        print::write_pdf(&document, audiorox.page_setup.paper, signature.as_deref(), &filename)?;
+        written.push(filename);
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unused_path_skips_existing_files() {
+        let dir = std::env::temp_dir().join(format!("distribution-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let stem = dir.join("1_Tremblay").to_string_lossy().into_owned();
+
+        assert_eq!(unused_path(&stem, "pdf"), format!("{}.pdf", stem));
+        std::fs::write(format!("{}.pdf", stem), b"").unwrap();
+        assert_eq!(unused_path(&stem, "pdf"), format!("{}_2.pdf", stem));
+        std::fs::write(format!("{}_2.pdf", stem), b"").unwrap();
+        assert_eq!(unused_path(&stem, "pdf"), format!("{}_3.pdf", stem));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,177 @@
+use std::borrow::Cow;
+
+use iced::advanced::renderer;
+use iced::advanced::text::Renderer as _;
+use iced::advanced::layout;
+use iced::advanced::graphics::Viewport;
+use iced_runtime::user_interface::{self, UserInterface};
+use iced::{mouse, Element, Rectangle, Size};
+
+use crate::config::{self, PAGE_WIDTH};
+use crate::partners::get_partner_name;
+use crate::signing::EXPORT_METADATA_KEY;
+use crate::util::{AudioRox, Message, FONTS};
+use crate::track_theme::TrackTheme;
+use crate::view::report;
+
+/// Resolution of the logical pixels used by the on-screen layout.
+pub const SCREEN_DPI: u32 = 96;
+
+pub const EXPORT_DPIS: [ExportDpi; 4] = [
+    ExportDpi(SCREEN_DPI),
+    ExportDpi(150),
+    ExportDpi(300),
+    ExportDpi(600),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportDpi(pub u32);
+
+impl Default for ExportDpi {
+    fn default() -> Self {
+        ExportDpi(300)
+    }
+}
+
+impl std::fmt::Display for ExportDpi {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ppp", self.0)
+    }
+}
+
+impl ExportDpi {
+    pub fn scale_factor(&self) -> f64 {
+        self.0 as f64 / SCREEN_DPI as f64
+    }
+
+    /// Content of the pHYs chunk of a PNG: the pixels per metre on both axes, so that
+    /// the image is printed and placed in documents at the size of the page.
+    fn png_physical_dimensions(&self) -> [u8; 9] {
+        let pixels_per_metre = (self.0 as f64 / 0.0254).round() as u32;
+        let mut chunk = [1; 9];
+        chunk[0..4].copy_from_slice(&pixels_per_metre.to_be_bytes());
+        chunk[4..8].copy_from_slice(&pixels_per_metre.to_be_bytes());
+        chunk
+    }
+}
+
+/// Name of the exported file, following the "Partner_dd-mm-yy" convention of the
+/// screenshots that were shared by hand until now.
+pub fn default_export_name(audiorox: &AudioRox, extension: &str) -> String {
+    let date = chrono::Local::now().format("%d-%m-%y");
+    format!("{}_{}.{}", get_partner_name(&audiorox.partner), date, extension)
+}
+
+/// Lays out the report offscreen at the fixed page width and rasterizes it with the
+/// software renderer, so the result does not depend on the window size or the
+/// scale factor of the screen.
+pub fn render_report(
+    audiorox: &AudioRox,
+    dpi: ExportDpi,
+) -> Result<image::RgbaImage, Box<dyn std::error::Error>> {
+    rasterize(report(audiorox, false), PAGE_WIDTH, dpi)
+}
+
+/// Rasterizes any element at the given logical width. The height is the natural
+/// height of the element at that width.
+pub fn rasterize(
+    element: Element<'_, Message>,
+    width: f32,
+    dpi: ExportDpi,
+) -> Result<image::RgbaImage, Box<dyn std::error::Error>> {
+    let mut renderer = iced::Renderer::TinySkia(iced_tiny_skia::Renderer::new(
+        iced_tiny_skia::Backend::new(iced_tiny_skia::Settings {
+            default_font: config::DEFAULT_FONT,
+            default_text_size: 16.0,
+        }),
+    ));
+
+    for font in FONTS {
+        renderer.load_font(Cow::Borrowed(font));
+    }
+
+    // measure the natural height of the element at the requested width
+    let limits = layout::Limits::new(Size::ZERO, Size::new(width, f32::INFINITY));
+    let logical_size = element.as_widget().layout(&renderer, &limits).size();
+    let logical_size = Size::new(width, logical_size.height.ceil());
+
+    // the page is printed whatever the theme of the interface
+    let print = TrackTheme::print();
+
+    let mut ui = UserInterface::build(element, logical_size, user_interface::Cache::default(), &mut renderer);
+    let _ = ui.draw(
+        &mut renderer,
+        &print.theme(),
+        &renderer::Style {
+            text_color: print.palette.text,
+        },
+        mouse::Cursor::Unavailable,
+    );
+
+    let scale_factor = dpi.scale_factor();
+    let width = (logical_size.width as f64 * scale_factor).ceil() as u32;
+    let height = (logical_size.height as f64 * scale_factor).ceil() as u32;
+
+    let viewport = Viewport::with_physical_size(Size::new(width, height), scale_factor);
+    let mut pixmap = tiny_skia::Pixmap::new(width, height).ok_or("Invalid export size")?;
+    let mut clip_mask = tiny_skia::Mask::new(width, height).ok_or("Invalid export size")?;
+
+    let iced::Renderer::TinySkia(renderer) = &mut renderer else {
+        return Err("The export requires the software renderer".into());
+    };
+
+    renderer.with_primitives(|backend, primitives| {
+        backend.draw::<&str>(
+            &mut pixmap.as_mut(),
+            &mut clip_mask,
+            primitives,
+            &viewport,
+            &[Rectangle::with_size(Size::new(width as f32, height as f32))],
+            print.palette.background,
+            &[],
+        );
+    });
+
+    // the software renderer writes BGRA (the softbuffer layout) on an opaque white page
+    let mut pixels = pixmap.take();
+    for pixel in pixels.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
+
+    image::RgbaImage::from_raw(width, height, pixels).ok_or_else(|| "Invalid export buffer".into())
+}
+
+pub fn export_png(
+    audiorox: &AudioRox,
+    filename: &str,
+    dpi: ExportDpi,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let image = render_report(audiorox, dpi)?;
+
+    let file = std::io::BufWriter::new(std::fs::File::create(filename)?);
+    let mut encoder = png::Encoder::new(file, image.width(), image.height());
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    // the signature of a finalized report goes with the image
+    if let Some(signature) = audiorox.export_signature() {
+        encoder.add_itxt_chunk(EXPORT_METADATA_KEY.to_string(), signature)?;
+    }
+    let mut writer = encoder.write_header()?;
+    writer.write_chunk(png::chunk::pHYs, &dpi.png_physical_dimensions())?;
+    writer.write_image_data(image.as_raw())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn png_physical_dimensions_in_pixels_per_metre() {
+        let dimensions = ExportDpi(300).png_physical_dimensions();
+        assert_eq!(dimensions[0..4], 11811u32.to_be_bytes());
+        assert_eq!(dimensions[4..8], 11811u32.to_be_bytes());
+        // the unit is the metre
+        assert_eq!(dimensions[8], 1);
+    }
+}
@@ -0,0 +1,799 @@
+use rand_core::{OsRng, RngCore};
+use serde_json::{json, Value};
+
+use crate::clinician::ClinicianProfile;
+use crate::field::{parse_entry, Column, FieldPath, Section};
+use crate::plot::{Conduction, EarSide, Threshold, Thresholds};
+use crate::recovery::write_atomically;
+use crate::util::AudioRox;
+
+const LOINC: &str = "http://loinc.org";
+/// Code system of the measures of the observations. LOINC has no code per measure
+/// alone, its audiometry codes being made for a given ear and frequency, so the measures
+/// are sent with these codes for the receiving system to map, along with the body site
+/// and the frequency of each observation. Only verified LOINC codes are sent as such.
+const MEASURE_CODES: &str = "urn:sequoia-report-maker:measure";
+const SNOMED: &str = "http://snomed.info/sct";
+const UCUM: &str = "http://unitsofmeasure.org";
+
+/// Measures of the report sent as observations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Measure {
+    AirThreshold,
+    BoneThreshold,
+    DiscomfortLevel,
+    PureToneAverage,
+    PureToneAverage4,
+    FletcherAverage,
+    SpeechReceptionThreshold,
+    SpeechDetectionThreshold,
+    MostComfortableLevel,
+    WordRecognition,
+    EarCanalVolume,
+    PeakPressure,
+    StaticCompliance,
+    ReflexIpsi,
+    ReflexContra,
+}
+
+const MEASURES: [Measure; 15] = [
+    Measure::AirThreshold,
+    Measure::BoneThreshold,
+    Measure::DiscomfortLevel,
+    Measure::PureToneAverage,
+    Measure::PureToneAverage4,
+    Measure::FletcherAverage,
+    Measure::SpeechReceptionThreshold,
+    Measure::SpeechDetectionThreshold,
+    Measure::MostComfortableLevel,
+    Measure::WordRecognition,
+    Measure::EarCanalVolume,
+    Measure::PeakPressure,
+    Measure::StaticCompliance,
+    Measure::ReflexIpsi,
+    Measure::ReflexContra,
+];
+
+impl Measure {
+    /// Code in `MEASURE_CODES` and display name.
+    pub fn code(&self) -> (&'static str, &'static str) {
+        match self {
+            Measure::AirThreshold => ("air-threshold", "Hearing threshold by air conduction"),
+            Measure::BoneThreshold => ("bone-threshold", "Hearing threshold by bone conduction"),
+            Measure::DiscomfortLevel => ("discomfort-level", "Uncomfortable loudness level"),
+            Measure::PureToneAverage => ("pta", "Pure tone average 500, 1000, 2000 Hz"),
+            Measure::PureToneAverage4 => ("pta4", "Pure tone average 500, 1000, 2000, 4000 Hz"),
+            Measure::FletcherAverage => ("fletcher", "Fletcher average"),
+            Measure::SpeechReceptionThreshold => ("srt", "Speech reception threshold"),
+            Measure::SpeechDetectionThreshold => ("sdt", "Speech detection threshold"),
+            Measure::MostComfortableLevel => ("mcl", "Most comfortable level for speech"),
+            Measure::WordRecognition => ("word-recognition", "Word recognition score"),
+            Measure::EarCanalVolume => ("ear-canal-volume", "Ear canal volume by tympanometry"),
+            Measure::PeakPressure => ("peak-pressure", "Tympanometric peak pressure"),
+            Measure::StaticCompliance => ("static-compliance", "Static compliance by tympanometry"),
+            Measure::ReflexIpsi => ("reflex-ipsi", "Acoustic reflex threshold ipsilateral"),
+            Measure::ReflexContra => ("reflex-contra", "Acoustic reflex threshold contralateral"),
+        }
+    }
+
+    /// UCUM unit of the values.
+    fn unit(&self) -> &'static str {
+        match self {
+            Measure::WordRecognition => "%",
+            Measure::EarCanalVolume | Measure::StaticCompliance => "mL",
+            Measure::PeakPressure => "daPa",
+            _ => "dB[HL]",
+        }
+    }
+}
+
+/// Audiology study, the code of the diagnostic report.
+const AUDIOLOGY_STUDY: (&str, &str) = ("28615-3", "Audiology study");
+
+fn coding(system: &str, (code, display): (&str, &str)) -> Value {
+    json!({ "coding": [{ "system": system, "code": code, "display": display }], "text": display })
+}
+
+fn body_site(ear: EarSide) -> Value {
+    match ear {
+        EarSide::Right => coding(SNOMED, ("25577004", "Right ear structure")),
+        EarSide::Left => coding(SNOMED, ("89644007", "Left ear structure")),
+        EarSide::Free => coding(SNOMED, ("34338003", "Both ears")),
+    }
+}
+
+fn quantity(value: f32, unit: &str) -> Value {
+    // through the decimal text, so that 0.6 is not sent as 0.6000000238418579
+    let value: f64 = value.to_string().parse().unwrap_or_default();
+    json!({ "value": value, "unit": unit, "system": UCUM, "code": unit })
+}
+
+fn uuid() -> String {
+    let mut bytes = [0u8; 16];
+    OsRng.fill_bytes(&mut bytes);
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex = hex::encode(bytes);
+    format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+}
+
+const AVERAGES: [(Column, Measure); 3] = [
+    (Column::Msp, Measure::PureToneAverage),
+    (Column::Msp4, Measure::PureToneAverage4),
+    (Column::Fletcher, Measure::FletcherAverage),
+];
+
+const SPEECH_THRESHOLDS: [(Column, Measure); 3] = [
+    (Column::Srp, Measure::SpeechReceptionThreshold),
+    (Column::Sdp, Measure::SpeechDetectionThreshold),
+    (Column::Comfort, Measure::MostComfortableLevel),
+];
+
+const TYMPANOMETRY: [(Column, Measure); 3] = [
+    (Column::Volume, Measure::EarCanalVolume),
+    (Column::Pressure, Measure::PeakPressure),
+    (Column::Compliance, Measure::StaticCompliance),
+];
+
+/// The tables above with their section, to find where a measure read back goes.
+const TABLES: [(Section, [(Column, Measure); 3]); 3] = [
+    (Section::TonalAverage, AVERAGES),
+    (Section::SpeechThreshold, SPEECH_THRESHOLDS),
+    (Section::Tympanometry, TYMPANOMETRY),
+];
+
+/// The two scores of the speech identification, each with the level and the list it
+/// was obtained with.
+const WORD_RECOGNITION: [(Column, Column, Column); 2] = [
+    (Column::Result1, Column::Level1, Column::List1),
+    (Column::Result2, Column::Level2, Column::List2),
+];
+
+const REFLEXES: [(Section, Measure); 2] = [
+    (Section::ReflexIpsi, Measure::ReflexIpsi),
+    (Section::ReflexContra, Measure::ReflexContra),
+];
+
+const REFLEX_FREQUENCIES: [(Column, u32); 4] = [
+    (Column::Khz500, 500),
+    (Column::Khz1000, 1000),
+    (Column::Khz2000, 2000),
+    (Column::Khz4000, 4000),
+];
+
+const NO_RESPONSE_NOTE: &str = "No response at the maximum output of the audiometer";
+const VIBROTACTILE_NOTE: &str = "Vibrotactile response";
+const MASKING_NOTE: &str = "Overmasking or insufficient masking";
+
+/// Observations of the report, as the entries of the bundle are built.
+struct Observations {
+    patient: String,
+    effective: String,
+    status: &'static str,
+    entries: Vec<(String, Value)>,
+}
+
+impl Observations {
+    fn push(&mut self, measure: Measure, ear: EarSide, value: Value, extra: Value) {
+        let mut observation = json!({
+            "resourceType": "Observation",
+            "status": self.status,
+            "category": [coding(
+                "http://terminology.hl7.org/CodeSystem/observation-category",
+                ("exam", "Exam"),
+            )],
+            "code": coding(MEASURE_CODES, measure.code()),
+            "subject": { "reference": self.patient },
+            "effectiveDateTime": self.effective,
+            "bodySite": body_site(ear),
+        });
+        let object = observation.as_object_mut().unwrap();
+        if let Value::Object(value) = value {
+            object.extend(value);
+        }
+        if let Value::Object(extra) = extra {
+            object.extend(extra);
+        }
+        self.entries.push((format!("urn:uuid:{}", uuid()), observation));
+    }
+
+    /// An entry of the tables, left out when empty.
+    fn push_entry(&mut self, measure: Measure, ear: EarSide, entry: &str, extra: Value) {
+        let entry = entry.trim();
+        if entry.is_empty() {
+            return;
+        }
+        let value = match parse_entry(entry) {
+            Some(value) => json!({ "valueQuantity": quantity(value, measure.unit()) }),
+            None => json!({ "valueString": entry }),
+        };
+        self.push(measure, ear, value, extra);
+    }
+
+    fn push_thresholds(&mut self, measure: Measure, ear: EarSide, thresholds: &Thresholds) {
+        for (frequency, threshold) in thresholds {
+            let mut value = quantity(threshold.level, measure.unit());
+            if threshold.no_response {
+                // no response at the maximum output of the audiometer
+                value["comparator"] = json!(">");
+            }
+            let extra = json!({
+                "component": [frequency_component(*frequency)],
+                "note": threshold_notes(threshold),
+            });
+            self.push(measure, ear, json!({ "valueQuantity": value }), extra);
+        }
+    }
+
+    fn push_table(&mut self, audiorox: &AudioRox, ear: EarSide, section: Section, columns: &[(Column, Measure)]) {
+        for (column, measure) in columns {
+            if let Some(entry) = audiorox.field(FieldPath::new(ear, section, *column)) {
+                self.push_entry(*measure, ear, entry, json!({}));
+            }
+        }
+    }
+}
+
+fn frequency_component(frequency: u32) -> Value {
+    json!({
+        "code": { "text": "Frequency" },
+        "valueQuantity": quantity(frequency as f32, "Hz"),
+    })
+}
+
+fn threshold_notes(threshold: &Threshold) -> Value {
+    let mut notes = vec![];
+    if threshold.no_response {
+        notes.push(json!({ "text": NO_RESPONSE_NOTE }));
+    }
+    if threshold.vibrotactile {
+        notes.push(json!({ "text": VIBROTACTILE_NOTE }));
+    }
+    if threshold.insufficient_masking {
+        notes.push(json!({ "text": MASKING_NOTE }));
+    }
+    Value::Array(notes)
+}
+
+/// The report as a FHIR R4 bundle: the patient, the examiner, an observation per
+/// measure and the diagnostic report grouping them.
+pub fn bundle(audiorox: &AudioRox) -> Value {
+    let patient_url = format!("urn:uuid:{}", uuid());
+    let examiner_url = format!("urn:uuid:{}", uuid());
+    let finalized = audiorox.is_finalized();
+
+    let mut observations = Observations {
+        patient: patient_url.clone(),
+        effective: chrono::Local::now().to_rfc3339(),
+        status: if finalized { "final" } else { "preliminary" },
+        entries: vec![],
+    };
+
+    for (ear, audiogram) in [(EarSide::Right, &audiorox.audiogram_right), (EarSide::Left, &audiorox.audiogram_left)] {
+        for conduction in [Conduction::Air, Conduction::Bone] {
+            let (measure, thresholds) = match conduction {
+                Conduction::Air => (Measure::AirThreshold, &audiogram.air),
+                Conduction::Bone => (Measure::BoneThreshold, &audiogram.bone),
+            };
+            observations.push_thresholds(measure, ear, thresholds);
+        }
+        observations.push_thresholds(Measure::DiscomfortLevel, ear, &audiogram.discomfort);
+    }
+
+    for ear in [EarSide::Right, EarSide::Left] {
+        observations.push_table(audiorox, ear, Section::TonalAverage, &AVERAGES);
+    }
+
+    for ear in [EarSide::Right, EarSide::Left, EarSide::Free] {
+        observations.push_table(audiorox, ear, Section::SpeechThreshold, &SPEECH_THRESHOLDS);
+
+        for (result, level, list) in WORD_RECOGNITION {
+            let entry = |column| {
+                audiorox
+                    .field(FieldPath::new(ear, Section::SpeechIdentification, column))
+                    .map_or("", |entry| entry.trim())
+            };
+            let mut components = vec![];
+            if let Some(level) = parse_entry(entry(level)) {
+                components.push(json!({
+                    "code": { "text": "Presentation level" },
+                    "valueQuantity": quantity(level, "dB[HL]"),
+                }));
+            }
+            if !entry(list).is_empty() {
+                components.push(json!({ "code": { "text": "Word list" }, "valueString": entry(list) }));
+            }
+            observations.push_entry(Measure::WordRecognition, ear, entry(result), json!({ "component": components }));
+        }
+    }
+
+    for ear in [EarSide::Right, EarSide::Left] {
+        observations.push_table(audiorox, ear, Section::Tympanometry, &TYMPANOMETRY);
+
+        for (section, measure) in REFLEXES {
+            for (column, frequency) in REFLEX_FREQUENCIES {
+                if let Some(entry) = audiorox.field(FieldPath::new(ear, section, column)) {
+                    let extra = json!({ "component": [frequency_component(frequency)] });
+                    observations.push_entry(measure, ear, entry, extra);
+                }
+            }
+        }
+    }
+
+    let examiner = &audiorox.examiner;
+    let patient = json!({
+        "resourceType": "Patient",
+        "name": [{ "text": audiorox.patient_name }],
+    });
+    let practitioner = json!({
+        "resourceType": "Practitioner",
+        "name": [{ "text": examiner.name }],
+        "identifier": [{ "value": examiner.licence }],
+        "qualification": [
+            { "code": { "text": examiner.title } },
+            { "code": { "text": examiner.credentials } },
+        ],
+    });
+    let mut report = json!({
+        "resourceType": "DiagnosticReport",
+        "status": observations.status,
+        "code": coding(LOINC, AUDIOLOGY_STUDY),
+        "subject": { "reference": patient_url },
+        "effectiveDateTime": observations.effective,
+        "issued": observations.effective,
+        "performer": [{ "reference": examiner_url }],
+        "result": observations
+            .entries
+            .iter()
+            .map(|(url, _)| json!({ "reference": url }))
+            .collect::<Vec<_>>(),
+    });
+    let conclusion: Vec<&str> = audiorox.notes.lines.iter().map(|line| line.trim()).filter(|line| !line.is_empty()).collect();
+    if !conclusion.is_empty() {
+        report["conclusion"] = json!(conclusion.join(" "));
+    }
+    if let Some(signature) = &audiorox.signature {
+        // the revision of the signed report the data comes from
+        report["identifier"] = json!([{ "system": "urn:ietf:rfc:3986", "value": format!("urn:sha256:{}", signature.hash) }]);
+    }
+
+    let mut entries = vec![
+        json!({ "fullUrl": patient_url, "resource": patient }),
+        json!({ "fullUrl": examiner_url, "resource": practitioner }),
+        json!({ "fullUrl": format!("urn:uuid:{}", uuid()), "resource": report }),
+    ];
+    entries.extend(
+        observations
+            .entries
+            .into_iter()
+            .map(|(url, observation)| json!({ "fullUrl": url, "resource": observation })),
+    );
+
+    let mut bundle = json!({
+        "resourceType": "Bundle",
+        "type": "collection",
+        "timestamp": chrono::Local::now().to_rfc3339(),
+        "entry": entries,
+    });
+    prune(&mut bundle);
+    bundle
+}
+
+/// FHIR does not allow empty strings, arrays or objects: entries left blank in the
+/// report are removed.
+fn prune(value: &mut Value) -> bool {
+    match value {
+        Value::String(string) => !string.is_empty(),
+        Value::Array(array) => {
+            array.retain_mut(prune);
+            !array.is_empty()
+        }
+        Value::Object(object) => {
+            object.retain(|_, value| prune(value));
+            !object.is_empty()
+        }
+        _ => true,
+    }
+}
+
+pub fn export_fhir(audiorox: &AudioRox, filename: &str) -> Result<(), Box<dyn std::error::Error>> {
+    write_atomically(filename, &serde_json::to_string_pretty(&bundle(audiorox))?)?;
+    Ok(())
+}
+
+/// Number of a quantity as it is typed in the tables.
+fn entry_of(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{}", value as i64)
+    } else {
+        value.to_string()
+    }
+}
+
+/// Value of the component of an observation with the given text, such as "Frequency".
+fn component<'a>(observation: &'a Value, text: &str) -> Option<&'a Value> {
+    observation["component"]
+        .as_array()?
+        .iter()
+        .find(|component| component["code"]["text"] == text)
+}
+
+fn ear_of(site: &Value) -> Option<EarSide> {
+    [EarSide::Right, EarSide::Left, EarSide::Free]
+        .into_iter()
+        .find(|ear| site["coding"][0]["code"] == body_site(*ear)["coding"][0]["code"])
+}
+
+/// Reads back a bundle made by `bundle`: the patient, the examiner, the conclusion and
+/// the observations. What the bundle does not carry, such as the layout of the report,
+/// is left to its default.
+pub fn read_bundle(bundle: &Value) -> Result<AudioRox, Box<dyn std::error::Error>> {
+    if bundle["resourceType"] != "Bundle" {
+        return Err("Not a FHIR bundle".into());
+    }
+    let mut audiorox = AudioRox::default();
+
+    for entry in bundle["entry"].as_array().ok_or("Bundle without entries")? {
+        let resource = &entry["resource"];
+        match resource["resourceType"].as_str() {
+            Some("Patient") => {
+                audiorox.patient_name = resource["name"][0]["text"].as_str().unwrap_or_default().to_string();
+            }
+            Some("Practitioner") => {
+                let text = |value: &Value| value.as_str().unwrap_or_default().to_string();
+                audiorox.examiner = ClinicianProfile {
+                    name: text(&resource["name"][0]["text"]),
+                    licence: text(&resource["identifier"][0]["value"]),
+                    title: text(&resource["qualification"][0]["code"]["text"]),
+                    credentials: text(&resource["qualification"][1]["code"]["text"]),
+                    ..ClinicianProfile::blank()
+                };
+            }
+            Some("DiagnosticReport") => {
+                if let Some(conclusion) = resource["conclusion"].as_str() {
+                    audiorox.notes.set_line(0, conclusion.to_string());
+                }
+            }
+            Some("Observation") => read_observation(&mut audiorox, resource),
+            _ => (),
+        }
+    }
+    Ok(audiorox)
+}
+
+fn read_observation(audiorox: &mut AudioRox, observation: &Value) {
+    let code = &observation["code"]["coding"][0];
+    let Some(measure) = MEASURES
+        .into_iter()
+        .find(|measure| code["system"] == MEASURE_CODES && code["code"] == measure.code().0)
+    else {
+        return;
+    };
+    let Some(ear) = ear_of(&observation["bodySite"]) else {
+        return;
+    };
+    let quantity = observation["valueQuantity"]["value"].as_f64();
+    let entry = match (quantity, observation["valueString"].as_str()) {
+        (Some(value), _) => entry_of(value),
+        (None, Some(text)) => text.to_string(),
+        (None, None) => return,
+    };
+    let frequency = component(observation, "Frequency").and_then(|frequency| frequency["valueQuantity"]["value"].as_f64());
+
+    let audiogram = match ear {
+        EarSide::Right => Some(&mut audiorox.audiogram_right),
+        EarSide::Left => Some(&mut audiorox.audiogram_left),
+        EarSide::Free => None,
+    };
+    let thresholds = match (measure, audiogram) {
+        (Measure::AirThreshold, Some(audiogram)) => Some(&mut audiogram.air),
+        (Measure::BoneThreshold, Some(audiogram)) => Some(&mut audiogram.bone),
+        (Measure::DiscomfortLevel, Some(audiogram)) => Some(&mut audiogram.discomfort),
+        _ => None,
+    };
+    if let Some(thresholds) = thresholds {
+        let (Some(level), Some(frequency)) = (quantity, frequency) else {
+            return;
+        };
+        let has_note = |text: &str| {
+            observation["note"]
+                .as_array()
+                .is_some_and(|notes| notes.iter().any(|note| note["text"] == text))
+        };
+        thresholds.insert(
+            frequency as u32,
+            Threshold {
+                level: level as f32,
+                no_response: observation["valueQuantity"]["comparator"] == ">",
+                vibrotactile: has_note(VIBROTACTILE_NOTE),
+                insufficient_masking: has_note(MASKING_NOTE),
+            },
+        );
+        return;
+    }
+
+    let table = TABLES.into_iter().find_map(|(section, columns)| {
+        columns.into_iter().find(|(_, m)| *m == measure).map(|(column, _)| (section, column))
+    });
+    if let Some((section, column)) = table {
+        if let Some(field) = audiorox.field_mut(FieldPath::new(ear, section, column)) {
+            *field = entry;
+        }
+    } else if measure == Measure::WordRecognition {
+        // the lists in their order, the first free one taking the result
+        let path = |column| FieldPath::new(ear, Section::SpeechIdentification, column);
+        let Some((result, level, list)) = WORD_RECOGNITION
+            .into_iter()
+            .find(|(result, _, _)| audiorox.field(path(*result)).is_some_and(|result| result.is_empty()))
+        else {
+            return;
+        };
+        let level_entry = component(observation, "Presentation level")
+            .and_then(|level| level["valueQuantity"]["value"].as_f64())
+            .map(entry_of);
+        let list_entry = component(observation, "Word list").and_then(|list| list["valueString"].as_str());
+        for (column, value) in [(result, Some(entry)), (level, level_entry), (list, list_entry.map(str::to_string))] {
+            if let (Some(field), Some(value)) = (audiorox.field_mut(path(column)), value) {
+                *field = value;
+            }
+        }
+    } else if let Some((section, _)) = REFLEXES.into_iter().find(|(_, m)| *m == measure) {
+        let column = REFLEX_FREQUENCIES
+            .into_iter()
+            .find(|(_, f)| Some(*f as f64) == frequency)
+            .map(|(column, _)| column);
+        if let Some(field) = column.and_then(|column| audiorox.field_mut(FieldPath::new(ear, section, column))) {
+            *field = entry;
+        }
+    }
+}
+
+/// Converts a bundle back to a report file, for `--import-fhir <bundle> <report>`.
+pub fn import_fhir(filename: &str, output: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let bundle: Value = serde_json::from_str(&std::fs::read_to_string(filename)?)?;
+    read_bundle(&bundle)?.save_to_file(output)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REPORT_BUNDLE: &str = include_str!("../tests/fixtures/fhir_report.json");
+    const EMPTY_BUNDLE: &str = include_str!("../tests/fixtures/fhir_empty.json");
+
+    fn threshold(level: f32) -> Threshold {
+        Threshold { level, ..Threshold::default() }
+    }
+
+    /// A report with an entry in each part sent as observations.
+    fn sample() -> AudioRox {
+        let mut audiorox = AudioRox { patient_name: String::from("Julie Tremblay"), ..AudioRox::default() };
+
+        let right = &mut audiorox.audiogram_right;
+        right.air.insert(500, threshold(20.0));
+        right.air.insert(1000, threshold(25.0));
+        right.air.insert(2000, threshold(40.0));
+        right.air.insert(4000, Threshold { no_response: true, ..threshold(120.0) });
+        right.bone.insert(1000, Threshold { insufficient_masking: true, ..threshold(15.0) });
+        let left = &mut audiorox.audiogram_left;
+        left.air.insert(250, Threshold { vibrotactile: true, ..threshold(85.0) });
+        left.discomfort.insert(1000, threshold(100.0));
+
+        for (ear, section, column, entry) in [
+            (EarSide::Right, Section::TonalAverage, Column::Msp, "28"),
+            (EarSide::Left, Section::TonalAverage, Column::Fletcher, "31.5"),
+            (EarSide::Right, Section::SpeechThreshold, Column::Srp, "30"),
+            (EarSide::Free, Section::SpeechThreshold, Column::Sdp, "20"),
+            (EarSide::Right, Section::SpeechIdentification, Column::Result1, "92"),
+            (EarSide::Right, Section::SpeechIdentification, Column::Level1, "65"),
+            (EarSide::Right, Section::SpeechIdentification, Column::List1, "1A"),
+            (EarSide::Free, Section::SpeechIdentification, Column::Result2, "88"),
+            (EarSide::Right, Section::Tympanometry, Column::Volume, "1.2"),
+            (EarSide::Right, Section::Tympanometry, Column::Pressure, "-50"),
+            (EarSide::Left, Section::Tympanometry, Column::Compliance, "0.6"),
+            (EarSide::Right, Section::ReflexIpsi, Column::Khz1000, "90"),
+            (EarSide::Left, Section::ReflexContra, Column::Khz500, "NR"),
+        ] {
+            *audiorox.field_mut(FieldPath::new(ear, section, column)).unwrap() = entry.to_string();
+        }
+        audiorox.notes.set_line(0, String::from("Audition normale à gauche."));
+        audiorox
+    }
+
+    /// The bundle without what changes from one export to the next: the identifiers,
+    /// numbered in the order they first appear, and the times.
+    fn normalized(bundle: &Value) -> Value {
+        fn normalize(value: &mut Value, uuids: &mut Vec<String>) {
+            match value {
+                Value::String(string) if string.starts_with("urn:uuid:") => {
+                    let index = match uuids.iter().position(|uuid| uuid == string) {
+                        Some(index) => index,
+                        None => {
+                            uuids.push(string.clone());
+                            uuids.len() - 1
+                        }
+                    };
+                    *string = format!("urn:uuid:{}", index);
+                }
+                Value::Array(array) => array.iter_mut().for_each(|value| normalize(value, uuids)),
+                Value::Object(object) => {
+                    for (key, value) in object.iter_mut() {
+                        if ["timestamp", "effectiveDateTime", "issued"].contains(&key.as_str()) {
+                            *value = json!("<time>");
+                        } else {
+                            normalize(value, uuids);
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+        let mut bundle = bundle.clone();
+        normalize(&mut bundle, &mut vec![]);
+        bundle
+    }
+
+    fn observations(bundle: &Value) -> Vec<&Value> {
+        bundle["entry"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| &entry["resource"])
+            .filter(|resource| resource["resourceType"] == "Observation")
+            .collect()
+    }
+
+    #[test]
+    fn export_matches_the_sample_bundles() {
+        let expected: Value = serde_json::from_str(REPORT_BUNDLE).unwrap();
+        assert_eq!(normalized(&bundle(&sample())), expected);
+        let expected: Value = serde_json::from_str(EMPTY_BUNDLE).unwrap();
+        assert_eq!(normalized(&bundle(&AudioRox::default())), expected);
+    }
+
+    #[test]
+    fn observations_per_ear_and_measure() {
+        let bundle = bundle(&sample());
+        let coded: Vec<(&str, &str)> = observations(&bundle)
+            .iter()
+            .map(|observation| {
+                (
+                    observation["code"]["coding"][0]["code"].as_str().unwrap(),
+                    observation["bodySite"]["coding"][0]["code"].as_str().unwrap(),
+                )
+            })
+            .collect();
+        let count = |measure: Measure, ear| {
+            let site = body_site(ear)["coding"][0]["code"].clone();
+            coded.iter().filter(|(code, body)| *code == measure.code().0 && site == *body).count()
+        };
+
+        assert_eq!(count(Measure::AirThreshold, EarSide::Right), 4);
+        assert_eq!(count(Measure::AirThreshold, EarSide::Left), 1);
+        assert_eq!(count(Measure::BoneThreshold, EarSide::Right), 1);
+        assert_eq!(count(Measure::BoneThreshold, EarSide::Left), 0);
+        assert_eq!(count(Measure::DiscomfortLevel, EarSide::Left), 1);
+        assert_eq!(count(Measure::PureToneAverage, EarSide::Right), 1);
+        assert_eq!(count(Measure::FletcherAverage, EarSide::Left), 1);
+        assert_eq!(count(Measure::SpeechReceptionThreshold, EarSide::Right), 1);
+        assert_eq!(count(Measure::SpeechDetectionThreshold, EarSide::Free), 1);
+        assert_eq!(count(Measure::WordRecognition, EarSide::Right), 1);
+        assert_eq!(count(Measure::WordRecognition, EarSide::Free), 1);
+        assert_eq!(count(Measure::EarCanalVolume, EarSide::Right), 1);
+        assert_eq!(count(Measure::PeakPressure, EarSide::Right), 1);
+        assert_eq!(count(Measure::StaticCompliance, EarSide::Left), 1);
+        assert_eq!(count(Measure::ReflexIpsi, EarSide::Right), 1);
+        assert_eq!(count(Measure::ReflexContra, EarSide::Left), 1);
+        assert_eq!(coded.len(), 18);
+    }
+
+    #[test]
+    fn thresholds_with_their_modifiers() {
+        let bundle = bundle(&sample());
+        let no_response = observations(&bundle)
+            .into_iter()
+            .find(|observation| observation["valueQuantity"]["value"] == 120.0)
+            .unwrap();
+        assert_eq!(no_response["valueQuantity"]["comparator"], ">");
+        assert_eq!(no_response["component"][0]["valueQuantity"]["value"], 4000.0);
+        assert_eq!(no_response["note"][0]["text"], NO_RESPONSE_NOTE);
+
+        let reflex = observations(&bundle)
+            .into_iter()
+            .find(|observation| observation["code"]["coding"][0]["code"] == Measure::ReflexContra.code().0)
+            .unwrap();
+        assert_eq!(reflex["valueString"], "NR");
+        assert_eq!(reflex["component"][0]["valueQuantity"]["value"], 500.0);
+    }
+
+    #[test]
+    fn bundle_read_back() {
+        let sample = sample();
+        let read = read_bundle(&serde_json::from_str(REPORT_BUNDLE).unwrap()).unwrap();
+
+        assert_eq!(read.patient_name, sample.patient_name);
+        assert_eq!(read.examiner.name, sample.examiner.name);
+        assert_eq!(read.examiner.licence, sample.examiner.licence);
+        assert_eq!(read.examiner.title, sample.examiner.title);
+        assert_eq!(read.examiner.credentials, sample.examiner.credentials);
+        assert_eq!(read.notes.lines[0], sample.notes.lines[0]);
+        for (read, sample) in [(&read.audiogram_right, &sample.audiogram_right), (&read.audiogram_left, &sample.audiogram_left)] {
+            assert_eq!(read.air, sample.air);
+            assert_eq!(read.bone, sample.bone);
+            assert_eq!(read.discomfort, sample.discomfort);
+        }
+        let entry = |audiorox: &AudioRox, ear, section, column| audiorox.field(FieldPath::new(ear, section, column)).cloned();
+        for (ear, section, column) in [
+            (EarSide::Right, Section::TonalAverage, Column::Msp),
+            (EarSide::Left, Section::TonalAverage, Column::Fletcher),
+            (EarSide::Free, Section::SpeechThreshold, Column::Sdp),
+            (EarSide::Right, Section::SpeechIdentification, Column::Level1),
+            (EarSide::Right, Section::SpeechIdentification, Column::List1),
+            (EarSide::Right, Section::Tympanometry, Column::Pressure),
+            (EarSide::Left, Section::Tympanometry, Column::Compliance),
+            (EarSide::Right, Section::ReflexIpsi, Column::Khz1000),
+            (EarSide::Left, Section::ReflexContra, Column::Khz500),
+        ] {
+            assert_eq!(entry(&read, ear, section, column), entry(&sample, ear, section, column));
+        }
+        // the second score of the sound field comes back as the first one
+        let free_score = entry(&read, EarSide::Free, Section::SpeechIdentification, Column::Result1);
+        assert_eq!(free_score.as_deref(), Some("88"));
+
+        // and exported again, the bundle is the same
+        assert_eq!(normalized(&bundle(&read)), normalized(&bundle(&sample)));
+    }
+
+    /// Whether the last digit of a LOINC code is its mod 10 check digit.
+    fn has_check_digit(code: &str) -> bool {
+        let Some((number, check)) = code.split_once('-') else {
+            return false;
+        };
+        let digits: Option<Vec<u32>> = number.chars().rev().map(|c| c.to_digit(10)).collect();
+        let Some(digits) = digits else {
+            return false;
+        };
+        // doubled from the rightmost digit on, and the digits of the products summed
+        let sum: u32 = digits
+            .iter()
+            .enumerate()
+            .map(|(i, digit)| if i % 2 == 0 { digit * 2 / 10 + digit * 2 % 10 } else { *digit })
+            .sum();
+        check == ((10 - sum % 10) % 10).to_string()
+    }
+
+    fn loinc_codes(value: &Value, codes: &mut Vec<String>) {
+        match value {
+            Value::Array(array) => array.iter().for_each(|value| loinc_codes(value, codes)),
+            Value::Object(object) => {
+                if object.get("system").is_some_and(|system| system == LOINC) {
+                    codes.push(object["code"].as_str().unwrap_or_default().to_string());
+                }
+                object.values().for_each(|value| loinc_codes(value, codes));
+            }
+            _ => (),
+        }
+    }
+
+    #[test]
+    fn loinc_check_digits() {
+        assert!(has_check_digit("28615-3"));
+        assert!(has_check_digit("54108-6"));
+        assert!(!has_check_digit("89016-3"));
+        assert!(!has_check_digit("28615"));
+
+        let mut codes = vec![];
+        loinc_codes(&bundle(&sample()), &mut codes);
+        assert_eq!(codes, [AUDIOLOGY_STUDY.0]);
+        for code in codes {
+            assert!(has_check_digit(&code), "{} is not a LOINC code", code);
+        }
+    }
+
+    #[test]
+    fn only_bundles_are_read() {
+        assert!(read_bundle(&json!({ "resourceType": "Patient" })).is_err());
+    }
+}
+
@@ -0,0 +1,456 @@
+use iced::advanced::widget::operation::{Focusable, Operation, Outcome};
+use iced::advanced::widget::Id;
+use iced::widget::{text_input, TextInput};
+use iced::{Command, Rectangle};
+
+use serde::{Deserialize, Serialize};
+
+use crate::plot::EarSide;
+use crate::tonal_tables::TonalTable;
+use crate::util::{AudioRox, IdLang, KHzList, Message, Tympa, VocalTable};
+
+/// Tables of the report holding one entry per ear.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Section {
+    TonalAverage,
+    SpeechThreshold,
+    SpeechIdentification,
+    Tympanometry,
+    ReflexIpsi,
+    ReflexContra,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Column {
+    Msp,
+    Msp4,
+    Fletcher,
+    Srp,
+    Sdp,
+    Comfort,
+    /// Word list of the speech thresholds, not shown in the tables.
+    List,
+    Level1,
+    List1,
+    Result1,
+    Level2,
+    List2,
+    Result2,
+    Volume,
+    Pressure,
+    Compliance,
+    Khz500,
+    Khz1000,
+    Khz2000,
+    Khz4000,
+}
+
+impl Section {
+    pub fn key(&self) -> &'static str {
+        match self {
+            Section::TonalAverage => "tonal_average",
+            Section::SpeechThreshold => "speech_threshold",
+            Section::SpeechIdentification => "speech_identification",
+            Section::Tympanometry => "tympanometry",
+            Section::ReflexIpsi => "reflex_ipsi",
+            Section::ReflexContra => "reflex_contra",
+        }
+    }
+}
+
+impl Column {
+    pub fn key(&self) -> &'static str {
+        match self {
+            Column::Msp => "msp",
+            Column::Msp4 => "msp4",
+            Column::Fletcher => "fletcher",
+            Column::Srp => "srp",
+            Column::Sdp => "sdp",
+            Column::Comfort => "comfort",
+            Column::List => "list",
+            Column::Level1 => "level1",
+            Column::List1 => "list1",
+            Column::Result1 => "result1",
+            Column::Level2 => "level2",
+            Column::List2 => "list2",
+            Column::Result2 => "result2",
+            Column::Volume => "volume",
+            Column::Pressure => "pressure",
+            Column::Compliance => "compliance",
+            Column::Khz500 => "500",
+            Column::Khz1000 => "1000",
+            Column::Khz2000 => "2000",
+            Column::Khz4000 => "4000",
+        }
+    }
+}
+
+/// Number typed in a table, with a comma or a point and an optional percent sign. None
+/// for the other entries, such as "NR" or "Abs".
+pub fn parse_entry(entry: &str) -> Option<f32> {
+    entry.trim().trim_end_matches('%').trim().replace(',', ".").parse().ok()
+}
+
+pub fn ear_key(ear: EarSide) -> &'static str {
+    match ear {
+        EarSide::Right => "right",
+        EarSide::Left => "left",
+        EarSide::Free => "binaural",
+    }
+}
+
+/// Address of a table entry of the report: ear × section × column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FieldPath {
+    pub ear: EarSide,
+    pub section: Section,
+    pub column: Column,
+}
+
+impl FieldPath {
+    pub const fn new(ear: EarSide, section: Section, column: Column) -> Self {
+        Self {
+            ear,
+            section,
+            column,
+        }
+    }
+
+    /// Stable name of the entry, e.g. "left.tympanometry.volume".
+    pub fn key(&self) -> String {
+        format!(
+            "{}.{}.{}",
+            ear_key(self.ear),
+            self.section.key(),
+            self.column.key()
+        )
+    }
+
+    /// Identifier of the text input of the entry, to move the focus to it.
+    pub fn input_id(&self) -> text_input::Id {
+        text_input::Id::new(self.key())
+    }
+}
+
+/// Text input of a table entry. Enter moves on to the next entry.
+pub fn field_input<'a>(placeholder: &str, value: &str, path: FieldPath) -> TextInput<'a, Message> {
+    text_input(placeholder, value)
+        .id(path.input_id())
+        .on_input(move |value| Message::FieldChanged(path, value))
+        .on_submit(Message::MoveFocus(FocusMove::Next))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FocusMove {
+    Next,
+    Previous,
+}
+
+/// Order in which Tab and Enter go through the table entries: the rows of tables from
+/// the top of the report down, each row from the right ear to the left ear as on the
+/// page, and each table row by row.
+pub fn focus_order() -> Vec<FieldPath> {
+    use Column::*;
+    use EarSide::{Free, Left, Right};
+
+    let groups: [(&[EarSide], &[Section], &[Column]); 5] = [
+        (&[Right, Left], &[Section::TonalAverage], &[Msp, Msp4, Fletcher]),
+        (&[Right, Left], &[Section::SpeechThreshold], &[Srp, Sdp, Comfort]),
+        (
+            &[Right, Free, Left],
+            &[Section::SpeechIdentification],
+            &[Level1, List1, Result1, Level2, List2, Result2],
+        ),
+        (&[Right, Left], &[Section::Tympanometry], &[Volume, Pressure, Compliance]),
+        (
+            &[Right, Left],
+            &[Section::ReflexIpsi, Section::ReflexContra],
+            &[Khz500, Khz1000, Khz2000],
+        ),
+    ];
+
+    let mut order = Vec::new();
+    for (ears, sections, columns) in groups {
+        for ear in ears {
+            for section in sections {
+                for column in columns {
+                    order.push(FieldPath::new(*ear, *section, *column));
+                }
+            }
+        }
+    }
+    order
+}
+
+/// Looks for the entry that has the focus, to move the focus from it.
+pub fn move_focus(step: FocusMove) -> Command<Message> {
+    Command::widget(find_focused()).map(move |focused| Message::FocusFound(focused, step))
+}
+
+/// Focuses the entry after or before the focused one, or the first or last entry when
+/// no entry has the focus.
+pub fn focus_from(focused: Option<Id>, step: FocusMove) -> Command<Message> {
+    let order = focus_order();
+    let index = focused.and_then(|id| {
+        order
+            .iter()
+            .position(|path| Id::from(path.input_id()) == id)
+    });
+
+    let next = match (index, step) {
+        (Some(index), FocusMove::Next) => (index + 1) % order.len(),
+        (Some(index), FocusMove::Previous) => (index + order.len() - 1) % order.len(),
+        (None, FocusMove::Next) => 0,
+        (None, FocusMove::Previous) => order.len() - 1,
+    };
+    text_input::focus(order[next].input_id())
+}
+
+/// Unlike `iced`'s own, always finishes, with `None` when no widget has the focus.
+fn find_focused() -> impl Operation<Option<Id>> {
+    struct FindFocused {
+        focused: Option<Id>,
+    }
+
+    impl Operation<Option<Id>> for FindFocused {
+        fn focusable(&mut self, state: &mut dyn Focusable, id: Option<&Id>) {
+            if state.is_focused() && id.is_some() {
+                self.focused = id.cloned();
+            }
+        }
+
+        fn container(
+            &mut self,
+            _id: Option<&Id>,
+            _bounds: Rectangle,
+            operate_on_children: &mut dyn FnMut(&mut dyn Operation<Option<Id>>),
+        ) {
+            operate_on_children(self)
+        }
+
+        fn finish(&self) -> Outcome<Option<Id>> {
+            Outcome::Some(self.focused.clone())
+        }
+    }
+
+    FindFocused { focused: None }
+}
+
+impl TonalTable {
+    fn column(&self, column: Column) -> Option<&String> {
+        match column {
+            Column::Msp => Some(&self.msp),
+            Column::Msp4 => Some(&self.msp4),
+            Column::Fletcher => Some(&self.fletcher),
+            _ => None,
+        }
+    }
+
+    fn column_mut(&mut self, column: Column) -> Option<&mut String> {
+        match column {
+            Column::Msp => Some(&mut self.msp),
+            Column::Msp4 => Some(&mut self.msp4),
+            Column::Fletcher => Some(&mut self.fletcher),
+            _ => None,
+        }
+    }
+}
+
+impl VocalTable {
+    fn column(&self, column: Column) -> Option<&String> {
+        match column {
+            Column::Srp => Some(&self.srp),
+            Column::Sdp => Some(&self.sdp),
+            Column::Comfort => Some(&self.misc),
+            Column::List => Some(&self.list),
+            _ => None,
+        }
+    }
+
+    fn column_mut(&mut self, column: Column) -> Option<&mut String> {
+        match column {
+            Column::Srp => Some(&mut self.srp),
+            Column::Sdp => Some(&mut self.sdp),
+            Column::Comfort => Some(&mut self.misc),
+            Column::List => Some(&mut self.list),
+            _ => None,
+        }
+    }
+}
+
+impl IdLang {
+    fn column(&self, column: Column) -> Option<&String> {
+        match column {
+            Column::Level1 => Some(&self.level1),
+            Column::List1 => Some(&self.list1),
+            Column::Result1 => Some(&self.result1),
+            Column::Level2 => Some(&self.level2),
+            Column::List2 => Some(&self.list2),
+            Column::Result2 => Some(&self.result2),
+            _ => None,
+        }
+    }
+
+    fn column_mut(&mut self, column: Column) -> Option<&mut String> {
+        match column {
+            Column::Level1 => Some(&mut self.level1),
+            Column::List1 => Some(&mut self.list1),
+            Column::Result1 => Some(&mut self.result1),
+            Column::Level2 => Some(&mut self.level2),
+            Column::List2 => Some(&mut self.list2),
+            Column::Result2 => Some(&mut self.result2),
+            _ => None,
+        }
+    }
+}
+
+impl Tympa {
+    fn column(&self, column: Column) -> Option<&String> {
+        match column {
+            Column::Volume => Some(&self.volume),
+            Column::Pressure => Some(&self.pressure),
+            Column::Compliance => Some(&self.compliance),
+            _ => None,
+        }
+    }
+
+    fn column_mut(&mut self, column: Column) -> Option<&mut String> {
+        match column {
+            Column::Volume => Some(&mut self.volume),
+            Column::Pressure => Some(&mut self.pressure),
+            Column::Compliance => Some(&mut self.compliance),
+            _ => None,
+        }
+    }
+}
+
+impl KHzList {
+    fn column(&self, column: Column) -> Option<&String> {
+        match column {
+            Column::Khz500 => Some(&self.khz_500),
+            Column::Khz1000 => Some(&self.khz_1000),
+            Column::Khz2000 => Some(&self.khz_2000),
+            Column::Khz4000 => Some(&self.khz_4000),
+            _ => None,
+        }
+    }
+
+    fn column_mut(&mut self, column: Column) -> Option<&mut String> {
+        match column {
+            Column::Khz500 => Some(&mut self.khz_500),
+            Column::Khz1000 => Some(&mut self.khz_1000),
+            Column::Khz2000 => Some(&mut self.khz_2000),
+            Column::Khz4000 => Some(&mut self.khz_4000),
+            _ => None,
+        }
+    }
+}
+
+impl AudioRox {
+    /// The table entry at the given path, `None` if the table has no such entry.
+    pub fn field(&self, path: FieldPath) -> Option<&String> {
+        let (ear, column) = (path.ear, path.column);
+
+        match (path.section, ear) {
+            (Section::TonalAverage, EarSide::Right) => self.tonal_table_right.column(column),
+            (Section::TonalAverage, EarSide::Left) => self.tonal_table_left.column(column),
+
+            (Section::SpeechThreshold, EarSide::Right) => self.vocal_table_right.column(column),
+            (Section::SpeechThreshold, EarSide::Left) => self.vocal_table_left.column(column),
+            (Section::SpeechThreshold, EarSide::Free) => self.vocal_table_binaural.column(column),
+
+            (Section::SpeechIdentification, EarSide::Right) => self.id_lang_right.column(column),
+            (Section::SpeechIdentification, EarSide::Left) => self.id_lang_left.column(column),
+            (Section::SpeechIdentification, EarSide::Free) => self.id_lang_bin.column(column),
+
+            (Section::Tympanometry, EarSide::Right) => self.tympa_right.column(column),
+            (Section::Tympanometry, EarSide::Left) => self.tympa_left.column(column),
+
+            (Section::ReflexIpsi, EarSide::Right) => self.stap_right.ipsi.column(column),
+            (Section::ReflexIpsi, EarSide::Left) => self.stap_left.ipsi.column(column),
+            (Section::ReflexContra, EarSide::Right) => self.stap_right.control.column(column),
+            (Section::ReflexContra, EarSide::Left) => self.stap_left.control.column(column),
+
+            _ => None,
+        }
+    }
+
+    pub fn field_mut(&mut self, path: FieldPath) -> Option<&mut String> {
+        let (ear, column) = (path.ear, path.column);
+
+        match (path.section, ear) {
+            (Section::TonalAverage, EarSide::Right) => self.tonal_table_right.column_mut(column),
+            (Section::TonalAverage, EarSide::Left) => self.tonal_table_left.column_mut(column),
+
+            (Section::SpeechThreshold, EarSide::Right) => self.vocal_table_right.column_mut(column),
+            (Section::SpeechThreshold, EarSide::Left) => self.vocal_table_left.column_mut(column),
+            (Section::SpeechThreshold, EarSide::Free) => {
+                self.vocal_table_binaural.column_mut(column)
+            }
+
+            (Section::SpeechIdentification, EarSide::Right) => {
+                self.id_lang_right.column_mut(column)
+            }
+            (Section::SpeechIdentification, EarSide::Left) => self.id_lang_left.column_mut(column),
+            (Section::SpeechIdentification, EarSide::Free) => self.id_lang_bin.column_mut(column),
+
+            (Section::Tympanometry, EarSide::Right) => self.tympa_right.column_mut(column),
+            (Section::Tympanometry, EarSide::Left) => self.tympa_left.column_mut(column),
+
+            (Section::ReflexIpsi, EarSide::Right) => self.stap_right.ipsi.column_mut(column),
+            (Section::ReflexIpsi, EarSide::Left) => self.stap_left.ipsi.column_mut(column),
+            (Section::ReflexContra, EarSide::Right) => self.stap_right.control.column_mut(column),
+            (Section::ReflexContra, EarSide::Left) => self.stap_left.control.column_mut(column),
+
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries() {
+        assert_eq!(parse_entry(" 25 "), Some(25.0));
+        assert_eq!(parse_entry("0,6"), Some(0.6));
+        assert_eq!(parse_entry("92 %"), Some(92.0));
+        assert_eq!(parse_entry("NR"), None);
+        assert_eq!(parse_entry(""), None);
+    }
+
+    #[test]
+    fn keys_of_the_paths() {
+        let path = FieldPath::new(EarSide::Left, Section::Tympanometry, Column::Volume);
+        assert_eq!(path.key(), "left.tympanometry.volume");
+        let path = FieldPath::new(EarSide::Free, Section::SpeechIdentification, Column::Result2);
+        assert_eq!(path.key(), "binaural.speech_identification.result2");
+    }
+
+    #[test]
+    fn each_entry_has_its_own_field() {
+        let mut audiorox = AudioRox::default();
+        let paths = focus_order();
+        for path in &paths {
+            let field = audiorox.field_mut(*path).unwrap_or_else(|| panic!("{} has no field", path.key()));
+            *field = path.key();
+        }
+        for path in &paths {
+            assert_eq!(audiorox.field(*path), Some(&path.key()));
+        }
+    }
+
+    #[test]
+    fn paths_without_a_table() {
+        let mut audiorox = AudioRox::default();
+        for path in [
+            FieldPath::new(EarSide::Free, Section::TonalAverage, Column::Msp),
+            FieldPath::new(EarSide::Free, Section::Tympanometry, Column::Volume),
+            FieldPath::new(EarSide::Right, Section::Tympanometry, Column::Msp),
+        ] {
+            assert_eq!(audiorox.field(path), None);
+            assert_eq!(audiorox.field_mut(path), None);
+        }
+    }
+}
@@ -0,0 +1,240 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::plot::Thresholds;
+
+/// Frequencies averaged by the AMA formula (AAO-1979).
+pub const AMA_FREQUENCIES: [u32; 4] = [500, 1000, 2000, 3000];
+/// Average hearing level below which there is no impairment, in dB HL.
+const AMA_LOW_FENCE: f32 = 25.0;
+/// Impairment of an ear for each dB of average above the low fence, in %.
+const AMA_SLOPE: f32 = 1.5;
+/// Weight of the better ear in the binaural impairment, the worse ear weighing 1.
+const AMA_BETTER_EAR_WEIGHT: f32 = 5.0;
+
+/// Formula turning the thresholds into a percentage of hearing loss.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Formula {
+    /// Monaural and binaural impairment of the AMA Guides.
+    #[default]
+    Ama,
+    /// Percentages of the barème of the settings, for compensation claims.
+    Bareme,
+}
+
+pub const FORMULAS: [Formula; 2] = [Formula::Ama, Formula::Bareme];
+
+impl fmt::Display for Formula {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Formula::Ama => "AMA (AAO 1979)",
+                Formula::Bareme => "Barème",
+            }
+        )
+    }
+}
+
+/// Percentage of an ear from the average of its thresholds at or above `from`, in dB HL.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BaremeStep {
+    pub from: f32,
+    pub percent: f32,
+}
+
+/// Table of a Québec-style barème, kept in the user settings so that it can be made to
+/// follow the regulation in force. The default steps are an example to be replaced.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Bareme {
+    /// Frequencies averaged for each ear.
+    pub frequencies: Vec<u32>,
+    /// Steps in increasing order of the average. Below the first one, there is no loss.
+    pub steps: Vec<BaremeStep>,
+    /// Weight of the better ear in the binaural percentage, the worse ear weighing 1.
+    pub better_ear_weight: f32,
+}
+
+impl Default for Bareme {
+    fn default() -> Self {
+        let steps = [
+            (30.0, 3.0),
+            (35.0, 7.0),
+            (40.0, 12.0),
+            (45.0, 18.0),
+            (50.0, 24.0),
+            (55.0, 31.0),
+            (60.0, 38.0),
+            (65.0, 46.0),
+            (70.0, 54.0),
+            (75.0, 62.0),
+            (80.0, 70.0),
+            (85.0, 80.0),
+            (90.0, 90.0),
+            (95.0, 100.0),
+        ];
+        Self {
+            frequencies: vec![500, 1000, 2000, 4000],
+            steps: steps
+                .iter()
+                .map(|(from, percent)| BaremeStep { from: *from, percent: *percent })
+                .collect(),
+            better_ear_weight: 5.0,
+        }
+    }
+}
+
+impl Bareme {
+    fn percent(&self, average: f32) -> f32 {
+        self.steps
+            .iter()
+            .take_while(|step| average >= step.from)
+            .last()
+            .map_or(0.0, |step| step.percent)
+    }
+}
+
+/// Hearing loss of the report, in %.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct HandicapResult {
+    /// Averages of the frequencies of the formula, in dB HL.
+    pub average_right: Option<f32>,
+    pub average_left: Option<f32>,
+    pub monaural_right: Option<f32>,
+    pub monaural_left: Option<f32>,
+    /// None unless both ears could be computed.
+    pub binaural: Option<f32>,
+}
+
+/// Percentage of hearing loss, shown in a box of the report when enabled.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HearingHandicap {
+    pub enabled: bool,
+    pub formula: Formula,
+}
+
+/// Average of the air conduction thresholds at the frequencies, None unless each was
+/// measured. A lack of response counts at the maximum output of the audiometer.
+pub fn average(thresholds: &Thresholds, frequencies: &[u32]) -> Option<f32> {
+    let levels: Option<Vec<f32>> = frequencies
+        .iter()
+        .map(|frequency| thresholds.get(frequency).map(|threshold| threshold.level))
+        .collect();
+    levels
+        .filter(|levels| !levels.is_empty())
+        .map(|levels| levels.iter().sum::<f32>() / levels.len() as f32)
+}
+
+fn weighted(better_ear_weight: f32, right: Option<f32>, left: Option<f32>) -> Option<f32> {
+    let (right, left) = (right?, left?);
+    let (better, worse) = (right.min(left), right.max(left));
+    Some((better_ear_weight * better + worse) / (better_ear_weight + 1.0))
+}
+
+/// Hearing loss of the air conduction thresholds of each ear with the formula.
+pub fn compute(formula: Formula, bareme: &Bareme, right: &Thresholds, left: &Thresholds) -> HandicapResult {
+    let (frequencies, better_ear_weight): (&[u32], f32) = match formula {
+        Formula::Ama => (&AMA_FREQUENCIES, AMA_BETTER_EAR_WEIGHT),
+        Formula::Bareme => (&bareme.frequencies, bareme.better_ear_weight),
+    };
+    let monaural = |average: f32| match formula {
+        Formula::Ama => ((average - AMA_LOW_FENCE) * AMA_SLOPE).clamp(0.0, 100.0),
+        Formula::Bareme => bareme.percent(average),
+    };
+
+    let (average_right, average_left) = (average(right, frequencies), average(left, frequencies));
+    let (monaural_right, monaural_left) = (average_right.map(monaural), average_left.map(monaural));
+    HandicapResult {
+        average_right,
+        average_left,
+        monaural_right,
+        monaural_left,
+        binaural: weighted(better_ear_weight, monaural_right, monaural_left),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plot::Threshold;
+
+    fn thresholds(levels: &[(u32, f32)]) -> Thresholds {
+        levels
+            .iter()
+            .map(|(frequency, level)| (*frequency, Threshold { level: *level, ..Threshold::default() }))
+            .collect()
+    }
+
+    /// The same level at each frequency of the AMA formula.
+    fn flat(level: f32) -> Thresholds {
+        thresholds(&AMA_FREQUENCIES.map(|frequency| (frequency, level)))
+    }
+
+    fn ama(right: &Thresholds, left: &Thresholds) -> HandicapResult {
+        compute(Formula::Ama, &Bareme::default(), right, left)
+    }
+
+    #[test]
+    fn ama_monaural() {
+        // 1.5 % per dB above the low fence of 25 dB HL
+        assert_eq!(ama(&flat(25.0), &flat(20.0)).monaural_right, Some(0.0));
+        assert_eq!(ama(&flat(40.0), &flat(20.0)).monaural_right, Some(22.5));
+        assert_eq!(ama(&flat(55.0), &flat(20.0)).monaural_right, Some(45.0));
+        assert_eq!(ama(&flat(20.0), &flat(20.0)).monaural_right, Some(0.0));
+        assert_eq!(ama(&flat(100.0), &flat(20.0)).monaural_right, Some(100.0));
+
+        let right = thresholds(&[(500, 40.0), (1000, 45.0), (2000, 50.0), (3000, 45.0), (4000, 70.0)]);
+        let left = thresholds(&[(500, 20.0), (1000, 25.0), (2000, 25.0), (3000, 25.0)]);
+        let result = ama(&right, &left);
+        assert_eq!(result.average_right, Some(45.0));
+        assert_eq!(result.average_left, Some(23.75));
+        assert_eq!(result.monaural_right, Some(30.0));
+        assert_eq!(result.monaural_left, Some(0.0));
+        assert_eq!(result.binaural, Some(5.0));
+    }
+
+    #[test]
+    fn ama_binaural_weights_the_better_ear() {
+        let result = ama(&flat(40.0), &flat(55.0));
+        assert_eq!(result.binaural, Some((5.0 * 22.5 + 45.0) / 6.0));
+        assert_eq!(ama(&flat(55.0), &flat(40.0)).binaural, result.binaural);
+    }
+
+    #[test]
+    fn ear_without_all_the_frequencies() {
+        let mut right = flat(40.0);
+        right.remove(&3000);
+        let result = ama(&right, &flat(55.0));
+        assert_eq!(result.average_right, None);
+        assert_eq!(result.monaural_right, None);
+        assert_eq!(result.monaural_left, Some(45.0));
+        assert_eq!(result.binaural, None);
+    }
+
+    #[test]
+    fn bareme_steps() {
+        let bareme = Bareme::default();
+        assert_eq!(bareme.percent(10.0), 0.0);
+        assert_eq!(bareme.percent(29.9), 0.0);
+        assert_eq!(bareme.percent(30.0), 3.0);
+        assert_eq!(bareme.percent(34.9), 3.0);
+        assert_eq!(bareme.percent(35.0), 7.0);
+        assert_eq!(bareme.percent(94.0), 90.0);
+        assert_eq!(bareme.percent(120.0), 100.0);
+
+        // averaged at 500, 1000, 2000 and 4000 Hz
+        let right = thresholds(&[(500, 30.0), (1000, 40.0), (2000, 50.0), (4000, 60.0)]);
+        let left = thresholds(&[(500, 20.0), (1000, 20.0), (2000, 30.0), (4000, 50.0)]);
+        let result = compute(Formula::Bareme, &bareme, &right, &left);
+        assert_eq!(result.average_right, Some(45.0));
+        assert_eq!(result.monaural_right, Some(18.0));
+        assert_eq!(result.average_left, Some(30.0));
+        assert_eq!(result.monaural_left, Some(3.0));
+        assert_eq!(result.binaural, Some((5.0 * 3.0 + 18.0) / 6.0));
+        assert_eq!(compute(Formula::Ama, &bareme, &right, &left).average_right, None);
+    }
+}
@@ -0,0 +1,124 @@
+use std::mem::Discriminant;
+
+use crate::distribution::{CCCategory, RecipientField};
+use crate::field::FieldPath;
+use crate::util::Message;
+
+/// Number of changes that can be undone.
+const HISTORY_LIMIT: usize = 200;
+
+/// A text input of the report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextField {
+    Table(FieldPath),
+    /// A line of the notes.
+    Note(usize),
+    Recipient(CCCategory, RecipientField),
+    Other(Discriminant<Message>),
+}
+
+/// Undo/redo stacks of the report, kept as serialized snapshots so that every field
+/// saved in the report file is covered without listing them.
+#[derive(Default, Debug)]
+pub struct History {
+    undo: Vec<String>,
+    redo: Vec<String>,
+    /// Text field changed by the last recorded edit. Consecutive keystrokes in the same
+    /// field are merged into a single change.
+    last_text_field: Option<TextField>,
+}
+
+impl History {
+    /// Records the state of the report before a change.
+    pub fn record(&mut self, before: String, text_field: Option<TextField>) {
+        self.redo.clear();
+
+        if text_field.is_some() && text_field == self.last_text_field {
+            return;
+        }
+        self.last_text_field = text_field;
+
+        self.undo.push(before);
+        if self.undo.len() > HISTORY_LIMIT {
+            self.undo.remove(0);
+        }
+    }
+
+    /// Returns the snapshot to restore, given the current one.
+    pub fn undo(&mut self, current: String) -> Option<String> {
+        let previous = self.undo.pop()?;
+        self.redo.push(current);
+        self.last_text_field = None;
+        Some(previous)
+    }
+
+    pub fn redo(&mut self, current: String) -> Option<String> {
+        let next = self.redo.pop()?;
+        self.undo.push(current);
+        self.last_text_field = None;
+        Some(next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::{Column, Section};
+    use crate::plot::EarSide;
+
+    const MSP: TextField = TextField::Table(FieldPath::new(EarSide::Right, Section::TonalAverage, Column::Msp));
+
+    #[test]
+    fn undo_and_redo() {
+        let mut history = History::default();
+        history.record(String::from("a"), None);
+        history.record(String::from("b"), None);
+
+        assert_eq!(history.undo(String::from("c")).as_deref(), Some("b"));
+        assert_eq!(history.undo(String::from("b")).as_deref(), Some("a"));
+        assert_eq!(history.undo(String::from("a")), None);
+        assert_eq!(history.redo(String::from("a")).as_deref(), Some("b"));
+        assert_eq!(history.redo(String::from("b")).as_deref(), Some("c"));
+        assert_eq!(history.redo(String::from("c")), None);
+
+        // a new change drops what could be redone
+        history.undo(String::from("c"));
+        history.record(String::from("b"), None);
+        assert_eq!(history.redo(String::from("d")), None);
+    }
+
+    #[test]
+    fn keystrokes_in_a_field_are_one_change() {
+        let mut history = History::default();
+        let note = TextField::Note(0);
+        history.record(String::from(""), Some(MSP));
+        history.record(String::from("2"), Some(MSP));
+        history.record(String::from("25"), Some(note));
+        history.record(String::from("25 a"), Some(note));
+        history.record(String::from("25 ab"), Some(MSP));
+
+        assert_eq!(history.undo(String::from("250 ab")).as_deref(), Some("25 ab"));
+        assert_eq!(history.undo(String::from("25 ab")).as_deref(), Some("25"));
+        assert_eq!(history.undo(String::from("25")).as_deref(), Some(""));
+        assert_eq!(history.undo(String::from("")), None);
+
+        // typing again in the field after an undo is a new change
+        history.redo(String::from(""));
+        history.record(String::from("25"), Some(MSP));
+        assert_eq!(history.undo(String::from("26")).as_deref(), Some("25"));
+    }
+
+    #[test]
+    fn oldest_changes_are_forgotten() {
+        let mut history = History::default();
+        for i in 0..HISTORY_LIMIT + 10 {
+            history.record(i.to_string(), None);
+        }
+        let mut undone = vec![];
+        while let Some(snapshot) = history.undo(String::new()) {
+            undone.push(snapshot);
+        }
+        assert_eq!(undone.len(), HISTORY_LIMIT);
+        assert_eq!(undone.last().map(String::as_str), Some("10"));
+    }
+}
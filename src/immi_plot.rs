@@ -17,10 +17,14 @@ use crate::config::{
     PLOT_Y_OFFSET_START,
 
 };
+use crate::locale::{tr, Language};
+use crate::track_theme::TrackTheme;
 use crate::Message;
 
 
-pub struct ImmitPlot;
+pub struct ImmitPlot {
+    language: Language,
+}
 
 
 
@@ -42,11 +46,12 @@ impl canvas::Program<Message, Renderer> for ImmitPlot {
         &self,
         _state: &Self::State,
         renderer: &Renderer,
-        _theme: &Theme,
+        theme: &Theme,
         bounds: Rectangle,
         _cursor: mouse::Cursor,
     ) -> Vec<canvas::Geometry> {
         let mut frame = canvas::Frame::new(renderer, bounds.size());
+        let track = TrackTheme::of(theme);
 
         let space = 2.0;
 
@@ -65,7 +70,7 @@ impl canvas::Program<Message, Renderer> for ImmitPlot {
         let x_unit = IM_PLOT_TICK_SIZE;
 
         let legend_text = Text {
-            color: config::AXIS_LABEL_COLOR,
+            color: track.axis_label,
             size: 13.0,
             font: config::DEFAULT_FONT,
             ..Text::default()
@@ -73,7 +78,7 @@ impl canvas::Program<Message, Renderer> for ImmitPlot {
 
         let mut y: f32 ;
         let y_stroke = canvas::Stroke {
-            style: canvas::Style::Solid(config::GRID_COLOR),
+            style: canvas::Style::Solid(track.grid),
             width: 1.0,
             line_cap: canvas::LineCap::Round,
             line_join: canvas::LineJoin::Round,
@@ -102,7 +107,7 @@ impl canvas::Program<Message, Renderer> for ImmitPlot {
             let mut units = format!("{}", y_axis[y_usize]);
 
             if units == "0" {
-                units = "0 ml".into();
+                units = tr(self.language, "0 ml").into();
             }
             frame.fill_text(Text {
                 content: units,
@@ -114,7 +119,7 @@ impl canvas::Program<Message, Renderer> for ImmitPlot {
         }
 
         let x_stroke = canvas::Stroke {
-            style: canvas::Style::Solid(config::GRID_COLOR),
+            style: canvas::Style::Solid(track.grid),
             width: 1.0,
             line_cap: canvas::LineCap::Round,
             line_join: canvas::LineJoin::Round,
@@ -135,7 +140,7 @@ impl canvas::Program<Message, Renderer> for ImmitPlot {
 
             if x_axis[x_usize] == 200 {
                 frame.fill_text(Text {
-                    content: "daPa".to_string(),
+                    content: tr(self.language, "daPa").to_string(),
                     horizontal_alignment: Horizontal::Left,
                     vertical_alignment: Vertical::Bottom,
                     position: Point::new(
@@ -164,8 +169,8 @@ impl canvas::Program<Message, Renderer> for ImmitPlot {
     }
 }
 
-pub fn im_plot<'a>() -> Element<'a, Message> {
-    let plotter = ImmitPlot;
+pub fn im_plot<'a>(language: Language) -> Element<'a, Message> {
+    let plotter = ImmitPlot { language };
     let can = Canvas::new(plotter)
         .width(Length::Fixed(IMMIT_CANVAS_WIDTH))
         .height(Length::Fixed(IMMIT_CANVAS_HEIGHT));
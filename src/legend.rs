@@ -10,23 +10,24 @@ use iced::widget::canvas::event::{self, Event};
 use iced::{mouse, Theme, Renderer, Element, Length, Point, Rectangle, Size, Vector};
 
 use crate::config::{LEGEND_SELECT_STROKE,
-     GRAY, LEGEND_BORDER_COLOR, LEGEND_HEIGHT, LEGEND_SYMBOL_STROKE_COLOR,
-    LEGEND_TEXT_COLOR, LEGEND_TITLES_COLOR, LEGEND_WIDTH, SPACE, ICON_SIZE,
+     LEGEND_HEIGHT, LEGEND_ICON_X, LEGEND_WIDTH, SPACE, ICON_SIZE,
     LEGEND_SELECT_MODIFIER_STROKE, self
 };
-use crate::plot::{add_contour, Shape};
+use crate::plot::{add_contour, Shape, ThresholdModifier};
+use crate::locale::{tr, Language};
+use crate::track_theme::TrackTheme;
 use crate::Message;
 
 pub struct Legend {
     space: f32,
-    icon_positions: LegendLRPositions,
+    language: Language,
 }
 
 impl Default for Legend {
     fn default() -> Self {
         Self {
             space: SPACE,
-            icon_positions: LegendLRPositions::default(),
+            language: Language::default(),
         }
     }
 }
@@ -55,58 +56,96 @@ pub enum LegendIcon {
 }
 
 
+/// Size of the symbols drawn in the legend.
+const SYMBOL_SIZE: f32 = 10.0;
+/// Height of a row of the legend.
+const ROW_HEIGHT: f32 = 2.0 * SYMBOL_SIZE - 1.0;
+
+/// Vertical centres of the rows of the legend, shared by the drawing and the icons that
+/// can be clicked.
+#[derive(Debug)]
+struct LegendRows {
+    ears: f32,
+    air_title: f32,
+    air_not_masked: f32,
+    air_masked: f32,
+    discomfort: f32,
+    free_field: f32,
+    hearing_aid: f32,
+    bone_title: f32,
+    bone_not_masked: f32,
+    bone_masked: f32,
+    separator: f32,
+    no_response: f32,
+    vibrotactile: f32,
+    insufficient: f32,
+}
+
+impl LegendRows {
+    fn new() -> Self {
+        let ears = 9.0;
+        let air_title = ears + ROW_HEIGHT * 0.95;
+        let air_not_masked = air_title + ROW_HEIGHT;
+        let air_masked = air_not_masked + ROW_HEIGHT;
+        let discomfort = air_masked + ROW_HEIGHT;
+        let free_field = discomfort + ROW_HEIGHT;
+        let hearing_aid = free_field + ROW_HEIGHT;
+        let bone_title = hearing_aid + ROW_HEIGHT;
+        let bone_not_masked = bone_title + ROW_HEIGHT;
+        let bone_masked = bone_not_masked + ROW_HEIGHT;
+        let separator = bone_masked + ROW_HEIGHT;
+        let no_response = separator + ROW_HEIGHT / 2.0;
+        let vibrotactile = no_response + ROW_HEIGHT;
+        let insufficient = vibrotactile + ROW_HEIGHT;
+        Self {
+            ears,
+            air_title,
+            air_not_masked,
+            air_masked,
+            discomfort,
+            free_field,
+            hearing_aid,
+            bone_title,
+            bone_not_masked,
+            bone_masked,
+            separator,
+            no_response,
+            vibrotactile,
+            insufficient,
+        }
+    }
+}
+
+/// Centres of the icons of the legend.
 #[derive( Debug)]
 pub struct LegendLRPositions {
     l: LegendPos,
     r: LegendPos,
 }
 
-impl Default for LegendLRPositions {
-    fn default() -> Self {
-
-        let xl = 21.0;
-        let xr = 197.0;
-
-        let y0 = 46.05;
-        let y1 = 160.05;
-        let y2 = 207.05;
-        let y3 = 250.55;
-
-        let dx = 5.;
-        let dy = ICON_SIZE / 2.;
-
-        let vs = 19.;
+impl LegendLRPositions {
+    /// Icons of the left ear along the left side of a legend of the given width, and
+    /// those of the right ear along its right side.
+    pub fn new(width: f32) -> Self {
+        let rows = LegendRows::new();
+        let icons = |x: f32| LegendPos {
+            sa_not_masked: Point { x, y: rows.air_not_masked },
+            sa_masked: Point { x, y: rows.air_masked },
+            sa_discomfort: Point { x, y: rows.discomfort },
+            sa_champs_libre: Point { x, y: rows.free_field },
+            sa_aa: Point { x, y: rows.hearing_aid },
+            so_not_masked: Point { x, y: rows.bone_not_masked },
+            so_masked: Point { x, y: rows.bone_masked },
+            other_no_response: Point { x, y: rows.no_response },
+            other_no_vibro: Point { x, y: rows.vibrotactile },
+            // between the two lines of the text
+            other_insufficient: Point { x, y: rows.insufficient + 7.5 },
+        };
 
-        LegendLRPositions { 
-            l: LegendPos { 
-                sa_not_masked: Point { x: xl, y: y0 }, 
-                sa_masked: Point { x: xl, y: y0 + vs }, 
-                sa_discomfort: Point { x: xl, y: y0 + 2. * vs }, // y: 84.05 }, 
-                sa_champs_libre: Point { x: xl, y: y0 + 3. * vs }, //y: 103.05 }, 
-                sa_aa: Point { x: xl, y: y0 + 4. * vs }, // y: 122.05 }, 
-                so_not_masked: Point { x: xl, y: y1 }, // y: 160.05 }, 
-                so_masked: Point { x: xl, y: y1 + vs }, // y: 179.05 }, 
-                
-                other_no_response: Point { x: xl + dx, y: y2 - dy}, // y: 207.55 }, 
-                other_no_vibro: Point { x: xl, y: y2 + vs }, //y: 226.55 }, 
-                other_insufficient: Point { x: xl, y: y3 }, // y: y1 + 2. * vs }, // y: 250.55 } 
-            }, 
-  
-            r: LegendPos { 
-                sa_not_masked: Point { x: xr, y: y0 }, 
-                sa_masked: Point { x: xr, y: y0 + vs }, 
-                sa_discomfort: Point { x: xr, y: y0 + 2.*vs }, 
-                sa_champs_libre: Point { x: xr, y: y0 + 3.*vs }, 
-                sa_aa: Point { x: xr, y: y0 + 4. * vs }, 
-                so_masked: Point { x: xr, y: y1}, 
-                so_not_masked: Point { x: xr, y: y1 + vs }, 
-                other_no_response: Point { x: xr - dx, y: y2 - dy }, 
-                other_no_vibro: Point { x: xr, y: y2 + vs }, 
-                other_insufficient: Point { x: xr, y: y3 } } 
-                
+        LegendLRPositions {
+            l: icons(LEGEND_ICON_X),
+            r: icons(width - LEGEND_ICON_X),
         }
-
-
     }
 }
 
@@ -128,8 +167,6 @@ pub struct LegendPos {
  impl LegendLRPositions {
     pub fn get_icon_under_cursor(&self, cursor: Point) -> Option<(Side, LegendIcon, Point)> {
      
-        let dv  = Vector::new(ICON_SIZE/2. + 3.0, ICON_SIZE/2. + 2.);
-        
         let mut lv = vec![
             (self.l.sa_not_masked, Side::LeftShape, LegendIcon::ANonMasque),
             (self.l.sa_masked, Side::LeftShape,  LegendIcon::AMasque),
@@ -139,13 +176,11 @@ pub struct LegendPos {
             (self.l.so_masked, Side::LeftShape,  LegendIcon::OMasque),
             (self.l.so_not_masked, Side::LeftShape,  LegendIcon::ONonMasque),
             
-            (self.l.other_no_response + dv, Side::LeftModifier,  LegendIcon::PasDeReponse),
+            (self.l.other_no_response, Side::LeftModifier,  LegendIcon::PasDeReponse),
             (self.l.other_no_vibro, Side::LeftModifier,  LegendIcon::Vibrotactile),
             (self.l.other_insufficient, Side::LeftModifier,  LegendIcon::Insufficient),
         ];
 
-        let dv  = Vector::new(ICON_SIZE/2. + 3.0, ICON_SIZE/2. + 2.);
-        
         let mut rv = vec![
             (self.r.sa_not_masked, Side::RightShape,LegendIcon::ANonMasque),
             (self.r.sa_masked, Side::RightShape,  LegendIcon::AMasque),
@@ -155,7 +190,7 @@ pub struct LegendPos {
             (self.r.so_masked, Side::RightShape, LegendIcon::OMasque),
             (self.r.so_not_masked, Side::RightShape, LegendIcon::ONonMasque),
            
-            (self.r.other_no_response + dv, Side::RightModifier, LegendIcon::PasDeReponse),
+            (self.r.other_no_response, Side::RightModifier, LegendIcon::PasDeReponse),
             (self.r.other_no_vibro, Side::RightModifier, LegendIcon::Vibrotactile),
             (self.r.other_insufficient, Side::RightModifier, LegendIcon::Insufficient),
         ];
@@ -206,16 +241,17 @@ impl canvas::Program<Message> for Legend {
         state: &Self::State,
         renderer: &Renderer,
 
-        _theme: &Theme,
+        theme: &Theme,
         bounds: Rectangle,
         _cursor: mouse::Cursor,
     ) -> Vec<canvas::Geometry> {
         let mut frame = canvas::Frame::new(renderer, bounds.size());
+        let track = TrackTheme::of(theme);
 
         let space = self.space;
 
         let symbol_stroke = canvas::Stroke {
-            style: canvas::Style::Solid(LEGEND_SYMBOL_STROKE_COLOR),
+            style: canvas::Style::Solid(track.legend_symbol),
             width: 2.0,
             line_cap: canvas::LineCap::Round,
             line_join: canvas::LineJoin::Round,
@@ -223,24 +259,19 @@ impl canvas::Program<Message> for Legend {
         };
 
         // shape size
-        let ss = 10.0;
-
-        // right side
-        let rx = bounds.width - space - 2.0 * ss;
-
-        // left side
-        let lx = space + 2.0 * ss;
+        let ss = SYMBOL_SIZE;
 
         // vertical space
-        let vs = 2. * ss - 1.0;
+        let vs = ROW_HEIGHT;
 
         let center_h = bounds.width / 2.0;
 
-        let mut v = 9.0;
+        let rows = LegendRows::new();
+        let mut v = rows.ears;
 
         let droit = Text {
-            content: "DROITE".to_string(),
-            color: LEGEND_TEXT_COLOR,
+            content: tr(self.language, "DROITE").to_string(),
+            color: track.legend_text,
             size: 14.0,
             position: Point::new(space + 4.0, v),
             horizontal_alignment: Horizontal::Left,
@@ -253,8 +284,8 @@ impl canvas::Program<Message> for Legend {
 
 
         let gauche = Text {
-            content: "GAUCHE".to_string(),
-            color: LEGEND_TEXT_COLOR,
+            content: tr(self.language, "GAUCHE").to_string(),
+            color: track.legend_text,
             size: 14.0,
             position: Point::new(bounds.width - space - 4.0, v),
             horizontal_alignment: Horizontal::Right,
@@ -265,10 +296,10 @@ impl canvas::Program<Message> for Legend {
 
         frame.fill_text(gauche.clone());
 
-        v += vs * 0.95;
+        v = rows.air_title;
         let seuil_aerien = Text {
-            content: "SEUIL AÉRIEN".to_string(),
-            color: LEGEND_TITLES_COLOR,
+            content: tr(self.language, "SEUIL AÉRIEN").to_string(),
+            color: track.legend_title,
             size: 14.0,
             position: Point::new(center_h, v),
             horizontal_alignment: Horizontal::Center,
@@ -283,7 +314,7 @@ impl canvas::Program<Message> for Legend {
         frame.fill(
             &rect_path_seuil,
             canvas::fill::Fill {
-                style: canvas::Style::Solid(GRAY),
+                style: canvas::Style::Solid(track.section_background),
                 rule: canvas::fill::Rule::NonZero,
             },
         );
@@ -292,7 +323,7 @@ impl canvas::Program<Message> for Legend {
 
         let legend_text = Text {
             content: "".to_string(),
-            color: LEGEND_TEXT_COLOR,
+            color: track.legend_text,
             size: 14.0,
             horizontal_alignment: Horizontal::Center,
             vertical_alignment: Vertical::Center,
@@ -300,14 +331,14 @@ impl canvas::Program<Message> for Legend {
             ..Text::default()
         };
 
-        v += vs;
+        v = rows.air_not_masked;
         frame.fill_text(Text {
-            content: "Non masqué".to_string(),
+            content: tr(self.language, "Non masqué").to_string(),
             position: Point::new(center_h, v),
             ..legend_text
         });
 
-        let rl_pos = LegendLRPositions::default();
+        let rl_pos = LegendLRPositions::new(bounds.width);
         
 
         frame.stroke(&Shape::circle(rl_pos.l.sa_not_masked, ss), symbol_stroke.clone());
@@ -315,10 +346,10 @@ impl canvas::Program<Message> for Legend {
         
 
 
-        v += vs;
+        v = rows.air_masked;
         
         frame.fill_text(Text {
-            content: "Masqué".to_string(),
+            content: tr(self.language, "Masqué").to_string(),
             position: Point::new(center_h, v),
             ..legend_text
         });
@@ -331,9 +362,9 @@ impl canvas::Program<Message> for Legend {
         );
 
 
-        v += vs;
+        v = rows.discomfort;
         frame.fill_text(Text {
-            content: "Inconfort".to_string(),
+            content: tr(self.language, "Inconfort").to_string(),
             position: Point::new(center_h, v),
             ..legend_text
         });
@@ -342,10 +373,10 @@ impl canvas::Program<Message> for Legend {
 
 
 
-        v += vs;
+        v = rows.free_field;
         // let oy = Vector::new(ss * 0.7, -ss);
         frame.fill_text(Text {
-            content: "Champ libre".to_string(),
+            content: tr(self.language, "Champ libre").to_string(),
             position: Point::new(center_h, v),
             ..legend_text
         });
@@ -355,10 +386,10 @@ impl canvas::Program<Message> for Legend {
 
 
 
-        v += vs;
+        v = rows.hearing_aid;
         // let oy = Vector::new(ss * 0.7, -ss);
         frame.fill_text(Text {
-            content: "Avec appareil auditif".to_string(),
+            content: tr(self.language, "Avec appareil auditif").to_string(),
             position: Point::new(center_h, v),
             ..legend_text
         });
@@ -367,10 +398,10 @@ impl canvas::Program<Message> for Legend {
 
 
 
-        v += 1.0 * vs;
+        v = rows.bone_title;
         let seuil_osseux = Text {
-            content: "SEUIL OSSEUX".to_string(),
-            color: LEGEND_TITLES_COLOR,
+            content: tr(self.language, "SEUIL OSSEUX").to_string(),
+            color: track.legend_title,
             size: 14.0,
             position: Point::new(center_h, v),
             horizontal_alignment: Horizontal::Center,
@@ -384,16 +415,16 @@ impl canvas::Program<Message> for Legend {
         frame.fill(
             &rect_path_seuil,
             canvas::Fill {
-                style: canvas::Style::Solid(GRAY),
+                style: canvas::Style::Solid(track.section_background),
                 rule: canvas::fill::Rule::NonZero,
             },
         );
         frame.fill_text(seuil_osseux);
 
 
-        v += vs;
+        v = rows.bone_not_masked;
         frame.fill_text(Text {
-            content: "Non masqué".to_string(),
+            content: tr(self.language, "Non masqué").to_string(),
             position: Point::new(center_h, v),
             ..legend_text
         });
@@ -409,9 +440,9 @@ impl canvas::Program<Message> for Legend {
 
 
 
-        v += vs;
+        v = rows.bone_masked;
         frame.fill_text(Text {
-            content: "Masqué".to_string(),
+            content: tr(self.language, "Masqué").to_string(),
             position: Point::new(center_h, v),
             ..legend_text
         });
@@ -425,10 +456,10 @@ impl canvas::Program<Message> for Legend {
         );
 
 
-        v += 1.0 * vs;
+        v = rows.separator;
         let seuil_osseux = Text {
             content: " ".to_string(),
-            color: LEGEND_TEXT_COLOR,
+            color: track.legend_text,
             size: 16.0,
             position: Point::new(center_h, v),
             horizontal_alignment: Horizontal::Center,
@@ -444,68 +475,70 @@ impl canvas::Program<Message> for Legend {
         frame.fill(
             &rect_path_seuil,
             canvas::Fill {
-                style: canvas::Style::Solid(GRAY),
+                style: canvas::Style::Solid(track.section_background),
                 rule: canvas::fill::Rule::NonZero,
             },
         );
         frame.fill_text(seuil_osseux);
 
-        v += vs / 2.0;
-        let dxy = Vector::new(ss * 0.7, -ss - 4.0);
+        v = rows.no_response;
+        // offsets from the centre of the icons to the anchor of their shapes
+        let arrow = Vector::new(ss * 0.42, -ss * 1.02);
+        let vt = Vector::new(ss * 0.78, -ss * 1.25);
+        let asterisk = Vector::new(ss * 0.7, -ss);
 
         frame.fill_text(Text {
-            content: "Pas de réponse".to_string(),
+            content: tr(self.language, "Pas de réponse").to_string(),
             position: Point::new(center_h, v),
             ..legend_text
         });
         frame.stroke(
-            &Shape::bottom_left_arrow(rl_pos.l.other_no_response, ss),
+            &Shape::bottom_left_arrow(rl_pos.l.other_no_response + arrow, ss),
             symbol_stroke.clone(),
         );
         frame.stroke(
-            &Shape::bottom_right_arrow(rl_pos.r.other_no_response, ss),
+            &Shape::bottom_right_arrow(rl_pos.r.other_no_response + Vector::new(-arrow.x, arrow.y), ss),
             symbol_stroke.clone(),
         );
 
 
-        v += vs;
+        v = rows.vibrotactile;
         frame.fill_text(Text {
-            content: "Vibrotactile".to_string(),
+            content: tr(self.language, "Vibrotactile").to_string(),
             position: Point::new(center_h, v),
             ..legend_text
         });
 
         
         frame.stroke(
-            &Shape::vt(Point::new(lx - 0.0, v) - dxy, ss),
+            &Shape::vt(rl_pos.l.other_no_vibro - vt, ss),
             symbol_stroke.clone(),
         );
         frame.stroke(
-            &&Shape::vt(Point::new(rx + 0.3, v) - dxy, ss),
+            &Shape::vt(rl_pos.r.other_no_vibro - vt, ss),
             symbol_stroke.clone(),
         );
 
 
-        v += vs + 0.0;
-        let oy = Vector::new(ss * 0.7, -ss * 1.6);
+        v = rows.insufficient;
         frame.fill_text(Text {
-            content: "Surassourdissement".to_string(),
+            content: tr(self.language, "Surassourdissement").to_string(),
             position: Point::new(center_h, v),
             horizontal_alignment: Horizontal::Center,
             ..legend_text
         });
         frame.fill_text(Text {
-            content: "ou masque insuffisant".to_string(),
+            content: tr(self.language, "ou masque insuffisant").to_string(),
             position: Point::new(center_h, v + 15.0),
             horizontal_alignment: Horizontal::Center,
             ..legend_text
         });
         frame.stroke(
-            &Shape::asterisk(Point::new(lx, v) - oy, ss),
+            &Shape::asterisk(rl_pos.l.other_insufficient - asterisk, ss),
             symbol_stroke.clone(),
         );
         frame.stroke(
-            &Shape::asterisk(Point::new(rx, v) - oy, ss),
+            &Shape::asterisk(rl_pos.r.other_insufficient - asterisk, ss),
             symbol_stroke.clone(),
         );
 
@@ -513,7 +546,7 @@ impl canvas::Program<Message> for Legend {
 
         let legend_rect_size = Size::new(bounds.width, bounds.height);
         let rectangle = Rectangle::new(Point::new(0., 0.), legend_rect_size);
-        add_contour(&mut frame, rectangle, 6.0, space, 2.0, LEGEND_BORDER_COLOR);
+        add_contour(&mut frame, rectangle, 6.0, space, 2.0, track.legend_border);
 
         let b = ICON_SIZE / 2.;
         let size = Size::new(2. * b, 2. * b);
@@ -574,37 +607,48 @@ impl canvas::Program<Message> for Legend {
             }
         }
 
-        match event {
-            Event::Mouse(mouse::Event::ButtonPressed(_)) => {
-                if let Some((side, icon, pos)) = self.icon_positions.get_icon_under_cursor(cursor_position) {
-                    println!("{:?}, {:?}", side, icon);
-                    match side {
-                        Side::LeftShape => {
-                            state.left_shape = Some((icon, pos));
-                        }
-                        Side::RightShape => {
-                            state.right_shape = Some((icon, pos));
-                        }
-                        Side::LeftModifier => {
-                            state.left_modifier = Some((icon, pos));
-                        }
-                        Side::RightModifier => {
-                            state.right_modifier = Some((icon, pos));
-                        }
+        if let Event::Mouse(mouse::Event::ButtonPressed(_)) = event {
+            let icon_positions = LegendLRPositions::new(bounds.width);
+            if let Some((side, icon, pos)) = icon_positions.get_icon_under_cursor(cursor_position) {
+                match side {
+                    Side::LeftShape => {
+                        state.left_shape = Some((icon, pos));
+                    }
+                    Side::RightShape => {
+                        state.right_shape = Some((icon, pos));
+                    }
+                    Side::LeftModifier => {
+                        state.left_modifier = Some((icon, pos));
+                    }
+                    Side::RightModifier => {
+                        state.right_modifier = Some((icon, pos));
                     }
                     
                 }
+
+                // the modifiers apply to the threshold at the cursor of the audiograms
+                let modifier = match icon {
+                    LegendIcon::PasDeReponse => Some(ThresholdModifier::NoResponse),
+                    LegendIcon::Vibrotactile => Some(ThresholdModifier::Vibrotactile),
+                    LegendIcon::Insufficient => Some(ThresholdModifier::InsufficientMasking),
+                    _ => None,
+                };
+                if let Some(modifier) = modifier {
+                    return (event::Status::Captured, Some(Message::ThresholdModifierToggled(modifier)));
+                }
             }
-            _ => {}
         }
 
         return (event::Status::Ignored, None);
     }
 }
 
-pub fn draw_legend() -> Element<'static, Message> {
+pub fn draw_legend(language: Language) -> Element<'static, Message> {
     // let plotter = Plot::new(data);
-    let legend = Legend::default();
+    let legend = Legend {
+        language,
+        ..Legend::default()
+    };
     // Element::new(Plot::new(data))
     let can = Canvas::new(legend)
         .width(Length::Fixed(LEGEND_WIDTH))
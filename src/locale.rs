@@ -0,0 +1,359 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    French,
+    English,
+}
+
+pub const LANGUAGES: [Language; 2] = [Language::French, Language::English];
+
+impl std::fmt::Display for Language {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Language::French => write!(f, "Français"),
+            Language::English => write!(f, "English"),
+        }
+    }
+}
+
+/// The French text, as written in the report, and its English translation. The
+/// French text is the key of the catalogue, so that it stays readable where it is used.
+const CATALOGUE: &[(&str, &str)] = &[
+    // header
+    ("Audiologiste", "Audiologist"),
+    ("ÉVALUATION AUDIOLOGIQUE", "AUDIOLOGICAL EVALUATION"),
+    ("Date de l'évaluation : ", "Evaluation date: "),
+    ("Lieu de l'évaluation : ", "Evaluation site: "),
+    // section titles
+    ("OREILLE DROITE", "RIGHT EAR"),
+    ("OREILLE GAUCHE", "LEFT EAR"),
+    ("AUDIOMÉTRIE TONALE", "PURE-TONE AUDIOMETRY"),
+    ("AUDIOMÉTRIE VOCALE", "SPEECH AUDIOMETRY"),
+    ("IMMITANCEMÉTRIE", "IMMITTANCE"),
+    // test conditions
+    ("VALIDITÉ", "RELIABILITY"),
+    ("Bonne", "Good"),
+    ("Moyenne", "Fair"),
+    ("Nulle", "Poor"),
+    ("ÉCOUTEURS", "TRANSDUCERS"),
+    ("Intra", "Insert"),
+    ("Haut-parleurs", "Loudspeakers"),
+    ("MÉTHODE D'ÉVALUATION : \nCONDITIONNEMENT", "TEST METHOD: \nCONDITIONING"),
+    ("Jeu", "Play"),
+    ("Visuel", "Visual"),
+    ("Normes ANSI S3 en vigueur", "Current ANSI S3 standards"),
+    ("Audiomètre: ", "Audiometer: "),
+    ("Date seuils antérieurs (•) : ", "Previous thresholds (•): "),
+    ("Repos sonore inadéquat (<16h)", "Inadequate noise rest (<16h)"),
+    ("Tympanomètre: ", "Tympanometer: "),
+    // legend and audiograms
+    ("DROITE", "RIGHT"),
+    ("GAUCHE", "LEFT"),
+    ("SEUIL AÉRIEN", "AIR CONDUCTION"),
+    ("SEUIL OSSEUX", "BONE CONDUCTION"),
+    ("Non masqué", "Unmasked"),
+    ("Masqué", "Masked"),
+    ("Inconfort", "Discomfort"),
+    ("Champ libre", "Sound field"),
+    ("Avec appareil auditif", "Aided"),
+    ("Pas de réponse", "No response"),
+    ("Surassourdissement", "Overmasking"),
+    ("ou masque insuffisant", "or insufficient masking"),
+    ("CA", "AC"),
+    ("CO", "BC"),
+    ("0 ml", "0 mL"),
+    // tables
+    ("MOYENNES DES SONS PURS - dB HL", "PURE-TONE AVERAGES - dB HL"),
+    ("MSP", "PTA"),
+    ("MSP4", "PTA4"),
+    ("SEUILS VOCAUX - dB HL", "SPEECH THRESHOLDS - dB HL"),
+    ("SRP", "SRT"),
+    ("SDP", "SDT"),
+    ("N confort", "MCL"),
+    ("LANGUE", "LANGUAGE"),
+    ("Ang.", "Eng."),
+    ("VOIX", "VOICE"),
+    ("Nue", "Live"),
+    ("Enregistrée", "Recorded"),
+    ("IDENTIFICATION PAROLE", "WORD RECOGNITION"),
+    ("IDENTIFICATION PAROLE - BINAURAL", "WORD RECOGNITION - BINAURAL"),
+    ("Niveau", "Level"),
+    ("Liste", "List"),
+    ("Résultat", "Score"),
+    ("Avec lecture\nlabiale  ", "With lip\nreading  "),
+    ("GAIN FONCTIONNEL - dB", "FUNCTIONAL GAIN - dB"),
+    ("PLAGE DYNAMIQUE - dB", "DYNAMIC RANGE - dB"),
+    ("TYMPANOMÉTRIE", "TYMPANOMETRY"),
+    ("Volume", "Volume"),
+    ("Pression", "Pressure"),
+    ("Compliance", "Compliance"),
+    ("Stimulation", "Stimulus"),
+    ("RÉFLEXE STAPÉDIEN - dB", "ACOUSTIC REFLEX - dB"),
+    ("Ipsilatérale", "Ipsilateral"),
+    ("Controlatérale", "Contralateral"),
+    // conclusion
+    ("Notes : ", "Notes: "),
+    ("Voir rapport audiologique complet ci-joint.", "See the attached full audiological report."),
+    ("Évaluation globale des besoins faite.", "Global needs assessment completed."),
+    ("caractères restants", "characters left"),
+    ("caractères ne seront pas imprimés", "characters will not be printed"),
+    ("Audioprothésiste", "Hearing aid practitioner"),
+    ("Médecin de famille", "Family physician"),
+    ("Centre de réadaptation", "Rehabilitation centre"),
+    ("ORL", "ENT"),
+    // printed pages
+    ("Patient : ", "Patient: "),
+    ("Imprimé le ", "Printed on "),
+    // interface
+    ("Aperçu avant impression", "Print preview"),
+    ("Exporter PNG", "Export PNG"),
+    ("Exporter FHIR", "Export FHIR"),
+    ("Dépistage bruit", "Noise screening"),
+    ("DÉPISTAGE — BRUIT", "NOISE SCREENING"),
+    ("Seuils à 2, 3 et 4 kHz incomplets", "Thresholds at 2, 3 and 4 kHz incomplete"),
+    ("Référence (2-3-4 kHz) :", "Baseline (2-3-4 kHz):"),
+    ("Actuel (2-3-4 kHz) :", "Current (2-3-4 kHz):"),
+    ("Correction selon l'âge :", "Age correction:"),
+    ("Écart :", "Shift:"),
+    ("DTS ENREGISTRABLE", "RECORDABLE STS"),
+    ("DTS", "STS"),
+    ("Aucun DTS", "No STS"),
+    ("Audiogramme de référence du", "Baseline audiogram of"),
+    ("Aucun audiogramme de référence", "No baseline audiogram"),
+    ("Âge :", "Age:"),
+    ("Dossier :", "File:"),
+    ("Naissance :", "Born:"),
+    ("AAAA-MM-JJ", "YYYY-MM-DD"),
+    ("Homme", "Male"),
+    ("Femme", "Female"),
+    ("Correction selon l'âge", "Age correction"),
+    ("DTS : écart moyen d'au moins", "STS: average shift of at least"),
+    ("enregistrable à partir de", "recordable from"),
+    ("Définir comme référence", "Set as baseline"),
+    ("Charger la référence", "Load the baseline"),
+    // paediatric evaluation
+    ("Mode pédiatrique", "Paediatric mode"),
+    ("ÉVALUATION PÉDIATRIQUE", "PAEDIATRIC EVALUATION"),
+    ("AUDIOMÉTRIE EN CHAMP LIBRE", "SOUND-FIELD AUDIOMETRY"),
+    ("Stimulus en champ libre :", "Sound-field stimulus:"),
+    ("Sons hululés", "Warble tones"),
+    ("Bruits à bande étroite", "Narrow band noise"),
+    ("Épreuve vocale :", "Speech test:"),
+    ("Détection de la parole", "Speech detection"),
+    ("Identification de parties du corps", "Body part identification"),
+    ("Désignation d'images", "Picture pointing"),
+    ("Listes de mots pour enfants", "Children's word lists"),
+    ("Rapport destiné :", "Report for:"),
+    ("Fiabilité des réponses :", "Reliability of the responses:"),
+    ("Réponses constantes et fiables.", "Consistent and reliable responses."),
+    ("Attention fluctuante durant l'évaluation.", "Fluctuating attention during the evaluation."),
+    ("Enfant fatigué ou agité, collaboration partielle.", "Tired or restless child, partial cooperation."),
+    ("Résultats à confirmer lors d'une prochaine évaluation.", "Results to be confirmed at a later evaluation."),
+    ("Oreille droite", "Right ear"),
+    ("Oreille gauche", "Left ear"),
+    ("Champ libre, meilleure oreille :", "Sound field, better ear:"),
+    ("Audition normale", "Normal hearing"),
+    ("Perte auditive légère", "Mild hearing loss"),
+    ("Perte auditive moyenne", "Moderate hearing loss"),
+    ("Perte auditive sévère", "Severe hearing loss"),
+    ("Perte auditive profonde", "Profound hearing loss"),
+    ("Recommandations pour la classe :", "Classroom recommendations:"),
+    ("Placer l'enfant à l'avant de la classe, près de l'enseignant.", "Seat the child at the front of the class, near the teacher."),
+    ("Réduire le bruit ambiant lors des consignes.", "Reduce background noise when giving instructions."),
+    ("Vérifier la compréhension des consignes verbales.", "Check that spoken instructions are understood."),
+    ("Envisager un système d'aide à l'audition en classe.", "Consider a classroom assistive listening system."),
+    ("Prévoir un suivi en audiologie.", "Plan an audiology follow-up."),
+    // hearing handicap
+    ("Handicap auditif", "Hearing handicap"),
+    ("HANDICAP AUDITIF", "HEARING HANDICAP"),
+    ("Moyenne :", "Average:"),
+    ("Déficit monaural :", "Monaural impairment:"),
+    ("Déficit binaural :", "Binaural impairment:"),
+    ("Seuils incomplets à", "Incomplete thresholds at"),
+    ("Formule :", "Formula:"),
+    ("Fréquences :", "Frequencies:"),
+    ("Barème", "Rating schedule"),
+    ("Hautes fréquences", "High frequencies"),
+    ("Champ libre :", "Sound field:"),
+    ("Audiogramme droit", "Right audiogram"),
+    ("Audiogramme gauche", "Left audiogram"),
+    ("Graphique binaural", "Binaural chart"),
+    ("Modèle :", "Template:"),
+    ("Degrés de perte", "Degrees of loss"),
+    ("Banane de la parole", "Speech banana"),
+    ("Sons familiers", "Familiar sounds"),
+    ("Léger", "Mild"),
+    ("Moyen", "Moderate"),
+    ("Sévère", "Severe"),
+    ("Profond", "Profound"),
+    ("Feuilles", "Leaves"),
+    ("Chuchotement", "Whisper"),
+    ("Oiseaux", "Birds"),
+    ("Chien", "Dog"),
+    ("Téléphone", "Phone"),
+    ("Tondeuse", "Lawn mower"),
+    ("Avion", "Airplane"),
+    ("Interface :", "Interface:"),
+    ("Rapport :", "Report:"),
+    ("Thème :", "Theme:"),
+    ("Zoom :", "Zoom:"),
+    ("Raccourcis", "Shortcuts"),
+    ("Examinateur :", "Examiner:"),
+    ("Profils", "Profiles"),
+    ("Profils cliniciens", "Clinician profiles"),
+    ("Ajouter un profil", "Add a profile"),
+    ("Supprimer", "Remove"),
+    ("Nom", "Name"),
+    ("Titres", "Credentials"),
+    ("Profession", "Profession"),
+    ("Numéro de permis", "Licence number"),
+    ("Image de la signature", "Signature image"),
+    ("Logo de l'ordre", "Order logo"),
+    ("Destinataires", "Recipients"),
+    ("Distribuer", "Distribute"),
+    ("Autre", "Other"),
+    ("Organisme", "Organization"),
+    ("Télécopieur ou courriel", "Fax or email"),
+    ("Adresse", "Address"),
+    ("Destinataire", "Recipient"),
+    ("Objet : Évaluation audiologique —", "Subject: Audiological evaluation —"),
+    ("Nombre de pages, incluant celle-ci :", "Number of pages, including this one:"),
+    ("Expéditeur :", "Sender:"),
+    (
+        "Ce document contient des renseignements personnels et confidentiels destinés \
+         uniquement au destinataire. Si vous l'avez reçu par erreur, veuillez nous en aviser \
+         et le détruire.",
+        "This document contains personal and confidential information intended only for \
+         the recipient. If you received it in error, please notify us and destroy it.",
+    ),
+    ("Envoyer", "Send"),
+    ("Envoi du rapport", "Sending the report"),
+    ("Serveur SMTP", "SMTP server"),
+    ("Utilisateur", "User name"),
+    ("Mot de passe (pour cette session)", "Password (this session only)"),
+    ("Adresse de l'expéditeur", "Sender address"),
+    ("Passerelle de télécopie", "Fax gateway"),
+    ("Sécurité", "Security"),
+    ("Partenaire : télécopieur ou courriel", "Partner: fax or email"),
+    ("aucune adresse utilisable", "no usable address"),
+    ("Journal des envois", "Delivery log"),
+    ("envoyé", "sent"),
+    ("échec", "failed"),
+    ("Envoi en cours…", "Sending…"),
+    ("Évaluation audiologique —", "Audiological evaluation —"),
+    (
+        "Veuillez trouver ci-joint le rapport d'évaluation audiologique.",
+        "Please find attached the audiological evaluation report.",
+    ),
+    ("Finaliser", "Finalize"),
+    ("Finalisé, révision", "Finalized, revision"),
+    ("Amender", "Amend"),
+    ("Vérifier", "Verify"),
+    ("Finaliser le rapport", "Finalize the report"),
+    (
+        "Le rapport sera signé par l'examinateur et ne pourra plus être modifié sans créer une nouvelle révision. Finaliser ?",
+        "The report will be signed by the examiner and can no longer be changed without creating a new revision. Finalize?",
+    ),
+    ("Rapport finalisé, révision", "Report finalized, revision"),
+    ("signé par", "signed by"),
+    ("amende", "amends"),
+    ("Vérification de la signature", "Signature verification"),
+    ("La signature est valide.", "The signature is valid."),
+    ("Révision", "Revision"),
+    ("signée par", "signed by"),
+    ("Le rapport n'est pas finalisé.", "The report is not finalized."),
+    ("La signature du rapport est illisible.", "The signature of the report cannot be read."),
+    ("Le rapport a été modifié après sa finalisation.", "The report was changed after it was finalized."),
+    ("La signature ne correspond pas au rapport.", "The signature does not match the report."),
+    (
+        "La clé de signature n'est pas reconnue pour ce clinicien.",
+        "The signing key is not trusted for this clinician.",
+    ),
+    ("Raccourcis clavier", "Keyboard shortcuts"),
+    ("Tab / Entrée", "Tab / Enter"),
+    ("Maj + Tab", "Shift + Tab"),
+    ("Entrée", "Enter"),
+    ("Suppr", "Delete"),
+    ("Échap", "Escape"),
+    ("Ctrl + Z / Ctrl + Maj + Z", "Ctrl + Z / Ctrl + Shift + Z"),
+    ("Champ suivant des tableaux", "Next table entry"),
+    ("Champ précédent des tableaux", "Previous table entry"),
+    (
+        "Fréquence précédente ou suivante sur l'audiogramme",
+        "Previous or next frequency on the audiogram",
+    ),
+    ("Seuil plus fort ou plus faible de 5 dB", "Threshold 5 dB louder or softer"),
+    ("Saisir un seuil", "Type a threshold"),
+    ("Enregistrer le seuil à la fréquence du curseur", "Record the threshold at the cursor frequency"),
+    ("Effacer le seuil à la fréquence du curseur", "Clear the threshold at the cursor frequency"),
+    (
+        "Basculer entre conduction aérienne et osseuse, ou sans et avec appareils",
+        "Switch between air and bone conduction, or unaided and aided",
+    ),
+    ("Basculer vers les seuils d'inconfort et revenir", "Switch to the discomfort levels and back"),
+    ("Pas de réponse au seuil du curseur", "No response at the cursor threshold"),
+    ("Réponse vibrotactile au seuil du curseur", "Vibrotactile response at the cursor threshold"),
+    ("Masque insuffisant au seuil du curseur", "Insufficient masking at the cursor threshold"),
+    ("Masquer le curseur", "Hide the cursor"),
+    ("Annuler / Rétablir", "Undo / Redo"),
+    ("Enregistrer / Charger", "Save / Load"),
+    ("Exporter en PNG / Imprimer", "Export to PNG / Print"),
+    ("Zoom avant / arrière / 100 %", "Zoom in / out / 100 %"),
+    ("Afficher les raccourcis", "Show the shortcuts"),
+    ("Ajuster à une page", "Fit to page"),
+    ("Imprimer", "Print"),
+    ("Enregistrer PDF", "Save PDF"),
+    ("Fermer", "Close"),
+    ("Partenaire", "Partner"),
+    ("Succursale", "Branch"),
+    ("Session non enregistrée", "Unsaved session"),
+    (
+        "Un rapport non enregistré a été récupéré. Voulez-vous le restaurer ?",
+        "An unsaved report was recovered. Do you want to restore it?",
+    ),
+    ("Restaurer", "Restore"),
+    ("Ignorer", "Discard"),
+    ("Modifications non enregistrées", "Unsaved changes"),
+    (
+        "Les modifications non enregistrées seront perdues. Charger quand même ?",
+        "Unsaved changes will be lost. Load anyway?",
+    ),
+    ("Charger", "Load"),
+    ("Annuler", "Cancel"),
+];
+
+/// Translates a French text of the catalogue. Texts missing from the catalogue are
+/// left in French.
+/// Choice of a pick list shown in the given language, the `Display` of the choice
+/// being its French text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Translated<T> {
+    pub value: T,
+    pub language: Language,
+}
+
+impl<T: Copy> Translated<T> {
+    /// Every choice of `values`, in the given language.
+    pub fn all(values: &[T], language: Language) -> Vec<Self> {
+        values.iter().map(|&value| Translated { value, language }).collect()
+    }
+}
+
+impl<T: std::fmt::Display> std::fmt::Display for Translated<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", tr(self.language, &self.value.to_string()))
+    }
+}
+
+pub fn tr(language: Language, french: &str) -> &str {
+    match language {
+        Language::French => french,
+        Language::English => CATALOGUE
+            .iter()
+            .find(|(key, _)| *key == french)
+            .map(|(_, english)| *english)
+            .unwrap_or(french),
+    }
+}
@@ -1,25 +1,58 @@
+// warnings of the code the report maker started from, left as it was written
+#![allow(dead_code, mismatched_lifetime_syntaxes)]
+#![allow(
+    clippy::comparison_to_empty,
+    clippy::derivable_impls,
+    clippy::needless_borrow,
+    clippy::needless_range_loop,
+    clippy::needless_return,
+    clippy::useless_conversion
+)]
+
+mod clinician;
 mod config;
+mod cursor;
+mod dataset;
+mod delivery;
+mod distribution;
+mod export;
+mod fhir;
+mod field;
+mod handicap;
+mod history;
 
 mod immi_plot;
 
 mod legend;
+mod locale;
+mod notes;
+mod overlay;
+mod paediatric;
 mod partners;
 mod plot;
 mod preset;
+mod print;
+mod recovery;
+mod screening;
+mod settings;
+mod signing;
 mod tonal_tables;
-mod view;
+mod track_theme;
 mod util;
+mod view;
 
 use util::*;
 
 use view::view;
 
+use clinician::ClinicianProfile;
+use cursor::AudiogramCursor;
+use field::FocusMove;
+use plot::FreeFieldChart;
 
-
-use config::{
-    WINDOW_HEIGHT, WINDOW_WIDTH
-};
-
+use config::{PAGE_WIDTH, SCROLLBAR_WIDTH, WINDOW_HEIGHT};
+use settings::UserSettings;
+use track_theme::TrackTheme;
 
 use iced::executor;
 use iced::keyboard::{self, Modifiers};
@@ -27,117 +60,637 @@ use iced::theme::Theme;
 
 use iced::widget::canvas::event;
 
+use iced::{subscription, Application, Command, Element, Settings, Subscription};
+use iced::{time, widget, window};
 
+pub fn main() -> iced::Result {
+    env_logger::builder().format_timestamp(None).init();
 
+    let args: Vec<String> = std::env::args().collect();
 
-use iced::{window, widget};
-use iced::{
-    subscription, Application, Command, Element,
-    Settings, Subscription,
-};
-
-
-
+    // `--export-csv <output> <reports or folders>...` flattens saved reports and exits
+    if let [_, flag, output, inputs @ ..] = &args[..] {
+        if flag == "--export-csv" {
+            match dataset::export_csv(inputs, output) {
+                Ok(count) => {
+                    println!("{} reports exported to {}", count, output);
+                    std::process::exit(0);
+                }
+                Err(e) => {
+                    println!("Failed to export reports: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+    }
 
+    // `--import-fhir <bundle> <report>` reads back a bundle exported as FHIR and exits
+    if let [_, flag, filename, output] = &args[..] {
+        if flag == "--import-fhir" {
+            match fhir::import_fhir(filename, output) {
+                Ok(_) => {
+                    println!("{} imported to {}", filename, output);
+                    std::process::exit(0);
+                }
+                Err(e) => {
+                    println!("Failed to import {}: {}", filename, e);
+                    std::process::exit(1);
+                }
+            }
+        }
+    }
 
-pub fn main() -> iced::Result {
-    env_logger::builder().format_timestamp(None).init();
+    // `--verify <report file>` checks the signature of a finalized report and exits
+    if let [_, flag, filename] = &args[..] {
+        if flag == "--verify" {
+            match signing::verify_file(filename, &UserSettings::load().trusted_keys) {
+                Ok(signature) => {
+                    println!(
+                        "{}: revision {} signed by {} on {} ({})",
+                        filename, signature.revision, signature.signed_by, signature.signed_at, signature.hash
+                    );
+                    std::process::exit(0);
+                }
+                Err(e) => {
+                    println!("{}: {}", filename, e);
+                    std::process::exit(1);
+                }
+            }
+        }
+    }
 
     let default_font = config::DEFAULT_FONT;
 
     AudioRox::run(Settings {
         antialiasing: true,
 
-
         default_font,
 
         window: window::Settings {
             position: window::Position::Centered,
-            size: (WINDOW_WIDTH, WINDOW_HEIGHT),
+            size: ((PAGE_WIDTH + SCROLLBAR_WIDTH) as u32, WINDOW_HEIGHT),
             ..window::Settings::default()
         },
         ..Settings::default()
     })
 }
 
-
-
-
-
-
 impl Application for AudioRox {
     type Message = Message;
     type Theme = Theme;
     type Executor = executor::Default;
     type Flags = ();
 
-    
-
     fn new(_flags: ()) -> (Self, Command<Message>) {
-        (
-            Self {
-                speed: 5,
-                ..Self::default()
-            },
-            
-            load_fonts(),
-        )
+        let mut audiorox = Self {
+            speed: 5,
+            settings: UserSettings::load(),
+            window_width: PAGE_WIDTH + SCROLLBAR_WIDTH,
+            report_path: REPORT_FILE.to_string(),
+            ..Self::default()
+        };
+        audiorox.saved_snapshot = audiorox.snapshot();
+
+        if let Some(session) = recovery::read_journal() {
+            audiorox.recovered_session = Some(session);
+            audiorox.succursale_overlay_menu = Modals::RestoreSession;
+        }
+
+        (audiorox, load_fonts())
     }
 
     fn title(&self) -> String {
-        String::from("audiometry")
+        if self.is_dirty() {
+            String::from("audiometry *")
+        } else {
+            String::from("audiometry")
+        }
     }
 
     fn update(&mut self, message: Message) -> Command<Message> {
+        match message {
+            Message::Undo => {
+                if let Some(snapshot) = self.history.undo(self.snapshot()) {
+                    self.restore_history(&snapshot);
+                }
+                Command::none()
+            }
+            Message::Redo => {
+                if let Some(snapshot) = self.history.redo(self.snapshot()) {
+                    self.restore_history(&snapshot);
+                }
+                Command::none()
+            }
+            Message::FinalizeReport => {
+                self.succursale_overlay_menu = Modals::None;
+                if let Err(e) = self.finalize() {
+                    println!("Failed to finalize report: {}", e);
+                }
+                if self.is_finalized() {
+                    // the signed report is saved and can no longer be undone
+                    self.history = Default::default();
+                    match self.save_to_file(&self.report_path) {
+                        Ok(_) => self.mark_saved(),
+                        Err(e) => println!("Failed to save finalized report: {}", e),
+                    }
+                }
+                Command::none()
+            }
+            message if !message.edits_report() => self.apply(message),
+            message => {
+                let text_field = message.text_field();
+                let before = self.snapshot();
+                let signature = self.signature.clone();
+
+                let command = self.apply(message);
+
+                // a finalized report is read-only until amended
+                if signature.is_some() && self.signature == signature && self.snapshot() != before {
+                    if let Err(e) = self.restore_snapshot(&before) {
+                        eprintln!("Failed to restore report: {}", e);
+                    }
+                } else if self.snapshot() != before {
+                    self.history.record(before, text_field);
+                    // an edit can also bring the report back to its saved state
+                    self.dirty = self.snapshot() != self.saved_snapshot;
+                }
+                command
+            }
+        }
+    }
+
+    fn subscription(&self) -> Subscription<Message> {
+        let autosave = time::every(recovery::AUTOSAVE_INTERVAL).map(|_| Message::Autosave);
+
+        // undo and redo also apply while typing, the text inputs capturing every key
+        let shortcuts = subscription::events_with(|event, status| match (event, status) {
+            (
+                iced::event::Event::Keyboard(keyboard::Event::KeyPressed {
+                    key_code: keyboard::KeyCode::Z,
+                    modifiers,
+                    ..
+                }),
+                _,
+            ) if modifiers == Modifiers::CTRL => Some(Message::Undo),
+            (
+                iced::event::Event::Keyboard(keyboard::Event::KeyPressed {
+                    key_code: keyboard::KeyCode::Z,
+                    modifiers,
+                    ..
+                }),
+                _,
+            ) if modifiers == Modifiers::CTRL | Modifiers::SHIFT => Some(Message::Redo),
+            (
+                iced::event::Event::Keyboard(keyboard::Event::KeyPressed {
+                    key_code: keyboard::KeyCode::S,
+                    modifiers: Modifiers::CTRL,
+                    ..
+                }),
+                event::Status::Ignored,
+            ) => Some(Message::SaveFile),
+            (
+                iced::event::Event::Keyboard(keyboard::Event::KeyPressed {
+                    key_code: keyboard::KeyCode::L,
+                    modifiers: Modifiers::CTRL,
+                    ..
+                }),
+                event::Status::Ignored,
+            ) => Some(Message::LoadFile),
+            (
+                iced::event::Event::Keyboard(keyboard::Event::KeyPressed {
+                    key_code: keyboard::KeyCode::E,
+                    modifiers: Modifiers::CTRL,
+                    ..
+                }),
+                event::Status::Ignored,
+            ) => Some(Message::ExportPng),
+            (
+                iced::event::Event::Keyboard(keyboard::Event::KeyPressed {
+                    key_code: keyboard::KeyCode::P,
+                    modifiers: Modifiers::CTRL,
+                    ..
+                }),
+                event::Status::Ignored,
+            ) => Some(Message::ShowPrintPreview),
+            (
+                iced::event::Event::Keyboard(keyboard::Event::KeyPressed {
+                    key_code: keyboard::KeyCode::Equals | keyboard::KeyCode::Plus | keyboard::KeyCode::NumpadAdd,
+                    modifiers,
+                }),
+                _,
+            ) if modifiers.control() => Some(Message::ZoomIn),
+            (
+                iced::event::Event::Keyboard(keyboard::Event::KeyPressed {
+                    key_code: keyboard::KeyCode::Minus | keyboard::KeyCode::NumpadSubtract,
+                    modifiers,
+                }),
+                _,
+            ) if modifiers.control() => Some(Message::ZoomOut),
+            (
+                iced::event::Event::Keyboard(keyboard::Event::KeyPressed {
+                    key_code: keyboard::KeyCode::Key0 | keyboard::KeyCode::Numpad0,
+                    modifiers,
+                }),
+                _,
+            ) if modifiers.control() => Some(Message::ZoomReset),
+            (
+                iced::event::Event::Keyboard(keyboard::Event::KeyPressed {
+                    key_code: keyboard::KeyCode::Tab,
+                    modifiers,
+                }),
+                _,
+            ) if modifiers.is_empty() => {
+                Some(Message::MoveFocus(FocusMove::Next))
+            }
+            (
+                iced::event::Event::Keyboard(keyboard::Event::KeyPressed {
+                    key_code: keyboard::KeyCode::Tab,
+                    modifiers,
+                }),
+                _,
+            ) if modifiers == Modifiers::SHIFT => {
+                Some(Message::MoveFocus(FocusMove::Previous))
+            }
+            (
+                iced::event::Event::Keyboard(keyboard::Event::KeyPressed {
+                    key_code: keyboard::KeyCode::F1,
+                    ..
+                }),
+                _,
+            ) => Some(Message::ShowShortcuts),
+            (
+                iced::event::Event::Keyboard(keyboard::Event::KeyPressed {
+                    key_code: keyboard::KeyCode::Escape,
+                    ..
+                }),
+                event::Status::Ignored,
+            ) => Some(Message::Escape),
+            (
+                iced::event::Event::Keyboard(keyboard::Event::KeyPressed {
+                    key_code: key_code @ (keyboard::KeyCode::Left
+                    | keyboard::KeyCode::Right
+                    | keyboard::KeyCode::Up
+                    | keyboard::KeyCode::Down
+                    | keyboard::KeyCode::Enter
+                    | keyboard::KeyCode::NumpadEnter
+                    | keyboard::KeyCode::Backspace
+                    | keyboard::KeyCode::Delete
+                    | keyboard::KeyCode::B
+                    | keyboard::KeyCode::N
+                    | keyboard::KeyCode::V
+                    | keyboard::KeyCode::M
+                    | keyboard::KeyCode::U
+                    | keyboard::KeyCode::Minus
+                    | keyboard::KeyCode::NumpadSubtract
+                    | keyboard::KeyCode::Key0
+                    | keyboard::KeyCode::Key1
+                    | keyboard::KeyCode::Key2
+                    | keyboard::KeyCode::Key3
+                    | keyboard::KeyCode::Key4
+                    | keyboard::KeyCode::Key5
+                    | keyboard::KeyCode::Key6
+                    | keyboard::KeyCode::Key7
+                    | keyboard::KeyCode::Key8
+                    | keyboard::KeyCode::Key9
+                    | keyboard::KeyCode::Numpad0
+                    | keyboard::KeyCode::Numpad1
+                    | keyboard::KeyCode::Numpad2
+                    | keyboard::KeyCode::Numpad3
+                    | keyboard::KeyCode::Numpad4
+                    | keyboard::KeyCode::Numpad5
+                    | keyboard::KeyCode::Numpad6
+                    | keyboard::KeyCode::Numpad7
+                    | keyboard::KeyCode::Numpad8
+                    | keyboard::KeyCode::Numpad9),
+                    modifiers,
+                }),
+                event::Status::Ignored,
+            ) if modifiers.is_empty() => Some(Message::AudiogramKey(key_code)),
+            (iced::event::Event::Window(window::Event::Resized { width, .. }), _) => {
+                Some(Message::WindowResized(width))
+            }
+            _ => None,
+        });
+
+        Subscription::batch([shortcuts, autosave])
+    }
+
+    fn view(&self) -> Element<'_, Message> {
+        view(self)
+    }
+
+    fn theme(&self) -> Theme {
+        TrackTheme::new(self.settings.theme).theme()
+    }
+
+    /// Scales the page to the width of the window, whatever the scale of the screen,
+    /// then applies the zoom.
+    fn scale_factor(&self) -> f64 {
+        let fit = self.window_width / (PAGE_WIDTH + SCROLLBAR_WIDTH);
+        fit as f64 * self.settings.zoom
+    }
+}
+
+impl AudioRox {
+    fn save_settings(&self) {
+        if let Err(e) = self.settings.save() {
+            println!("Failed to save settings: {}", e);
+        }
+    }
+
+    fn restore_history(&mut self, snapshot: &str) {
+        if let Err(e) = self.restore_snapshot(snapshot) {
+            eprintln!("Failed to restore report: {}", e);
+        }
+        // undoing every change since the last save leaves nothing to save
+        self.dirty = self.snapshot() != self.saved_snapshot;
+        if self.print_preview.is_some() {
+            self.refresh_print_preview();
+        }
+    }
+
+    fn load_report(&mut self) {
+        match self.load_from_file(&self.report_path) {
+            Ok(data) => {
+                println!("Data loaded successfully");
+                self.replace_report(data);
+                // the changes of the previous report cannot be undone in this one
+                self.history = Default::default();
+                self.mark_saved();
+            }
+            Err(e) => println!("Failed to load data: {}", e),
+        }
+    }
+
+    fn apply(&mut self, message: Message) -> Command<Message> {
         match message {
             Message::FontLoaded(_) => (),
             Message::LegendModifierSelected(_) => {}
             Message::LegendShapeSelected(_) => {}
+            Message::Undo | Message::Redo => (),
 
             Message::LoadFile => {
-                // Perform your save operation here
-                // println!("S key is pressed");
-                println!("{}", serde_json::to_string(&self).unwrap());
-                match self.load_from_file("data.json") {
-                    Ok(data) => {
-                        println!("Data saved successfully");
-                        *self = data;
+                if self.is_dirty() {
+                    self.succursale_overlay_menu = Modals::ConfirmLoad;
+                } else {
+                    self.load_report();
+                }
+            }
+            Message::ConfirmLoad => {
+                self.succursale_overlay_menu = Modals::None;
+                self.load_report();
+            }
+            Message::CancelLoad => self.succursale_overlay_menu = Modals::None,
+
+            Message::Autosave => self.autosave(),
+            Message::RestoreSession => {
+                self.succursale_overlay_menu = Modals::None;
+                if let Some(session) = self.recovered_session.take() {
+                    if let Err(e) = self.restore_snapshot(&session) {
+                        println!("Failed to restore session: {}", e);
                     }
-                    Err(e) => println!("Failed to save data: {}", e),
                 }
-                // ...
+            }
+            Message::DiscardSession => {
+                self.succursale_overlay_menu = Modals::None;
+                self.recovered_session = None;
+                recovery::remove_journal();
+            }
+
+            Message::SaveFile => match self.save_to_file(&self.report_path) {
+                Ok(_) => {
+                    println!("Data saved successfully");
+                    self.mark_saved();
+                }
+                Err(e) => println!("Failed to save data: {}", e),
+            },
+
+            Message::ExportPng => {
+                let filename = export::default_export_name(self, "png");
+                match export::export_png(self, &filename, self.export_dpi) {
+                    Ok(_) => println!("Report exported to {}", filename),
+                    Err(e) => println!("Failed to export report: {}", e),
+                }
+            }
+            Message::ExportDpiChanged(dpi) => self.export_dpi = dpi,
+            Message::ExportFhir => {
+                let filename = export::default_export_name(self, "fhir.json");
+                match fhir::export_fhir(self, &filename) {
+                    Ok(_) => println!("Report exported to {}", filename),
+                    Err(e) => println!("Failed to export report: {}", e),
+                }
+            }
+            Message::ExportPdf => {
+                let filename = export::default_export_name(self, "pdf");
+                match print::export_pdf(self, &filename) {
+                    Ok(_) => println!("Report exported to {}", filename),
+                    Err(e) => println!("Failed to export report: {}", e),
+                }
             }
 
-            Message::SaveFile => {
-                // Perform your save operation here
-                // println!("S key is pressed");
-                println!("{}", serde_json::to_string(&self).unwrap());
-                match self.save_to_file("data.json") {
-                    Ok(_) => println!("Data loaded successfully"),
-                    Err(e) => println!("Failed to load data: {}", e),
+            Message::ShowPrintPreview => self.refresh_print_preview(),
+            Message::ClosePrintPreview => self.print_preview = None,
+            Message::PrintReport => match print::print_report(self) {
+                Ok(_) => println!("Report sent to the printer"),
+                Err(e) => println!("Failed to print report: {}", e),
+            },
+            Message::PaperSizeChanged(paper) => {
+                self.page_setup.paper = paper;
+                self.refresh_print_preview();
+            }
+            Message::MarginChanged(margin) => {
+                self.page_setup.margin = margin;
+                self.refresh_print_preview();
+            }
+            Message::FitToPageChanged(value) => {
+                self.page_setup.fit_to_page = value;
+                self.refresh_print_preview();
+            }
+            Message::PatientNameChanged(value) => {
+                self.patient_name = value;
+                if self.print_preview.is_some() {
+                    self.refresh_print_preview();
                 }
-                // ...
-            }
-            
-            Message::CCPatientChanged(value) => self.cc.patient = value,
-            Message::CCAudioProChanged(value) => self.cc.audioprothesiste = value,
-            Message::CCFamilyDocChanged(value) => self.cc.family_doctor = value,
-            Message::CCORLChanged(value) => self.cc.orl = value,
-            Message::CCOtherChanged(value) => self.cc.other = value,
-            Message::CCReadapt(value) => self.cc.readapt = value,
-            
-
-            Message::PartnerChanged(value) =>  {
+            }
+            Message::ReportLanguageChanged(language) => self.report_language = language,
+            Message::UiLanguageChanged(language) => {
+                self.settings.ui_language = language;
+                self.save_settings();
+            }
+            Message::ThemeChanged(theme) => {
+                self.settings.theme = theme;
+                self.save_settings();
+            }
+            Message::ZoomIn => {
+                self.settings.zoom_in();
+                self.save_settings();
+            }
+            Message::ZoomOut => {
+                self.settings.zoom_out();
+                self.save_settings();
+            }
+            Message::ZoomReset => {
+                self.settings.zoom = 1.0;
+                self.save_settings();
+            }
+            // iced divides the size of the window by the scale factor of the screen and by
+            // ours, so the width is scaled back to only depend on the screen
+            Message::WindowResized(width) => self.window_width = width as f32 * self.scale_factor() as f32,
+
+            Message::MoveFocus(step) => {
+                self.audiogram_cursor = None;
+                return field::move_focus(step);
+            }
+            Message::FocusFound(focused, step) => return field::focus_from(focused, step),
+            Message::AudiogramCursorPlaced(ear, frequency, level) => {
+                let curve = self.audiogram_cursor.as_ref().map(|cursor| cursor.curve.on(ear));
+                let mut cursor = AudiogramCursor::new(ear, frequency, level);
+                cursor.curve = curve.unwrap_or(cursor.curve);
+                self.audiogram_cursor = Some(cursor);
+            }
+            Message::AudiogramKey(key_code) => self.audiogram_key(key_code),
+            Message::ThresholdModifierToggled(modifier) => self.toggle_modifier(modifier),
+            Message::Escape => match self.succursale_overlay_menu {
+                Modals::Shortcuts => self.succursale_overlay_menu = Modals::None,
+                _ => self.audiogram_cursor = None,
+            },
+            Message::ShowShortcuts => self.succursale_overlay_menu = Modals::Shortcuts,
+            Message::HideShortcuts => self.succursale_overlay_menu = Modals::None,
+
+            Message::CCChanged(category, value) => self.cc.recipient_mut(category).selected = value,
+            Message::RecipientEdited(category, field, value) => {
+                self.cc.recipient_mut(category).set(field, value)
+            }
+            Message::ShowRecipients => self.succursale_overlay_menu = Modals::Recipients,
+            Message::HideRecipients => self.succursale_overlay_menu = Modals::None,
+            Message::DistributeReport => match distribution::distribute(self, &distribution::cc_addressees(self)) {
+                Ok(files) if files.is_empty() => println!("No recipient is checked"),
+                Ok(files) => println!("Report distributed to {}", files.join(", ")),
+                Err(e) => println!("Failed to distribute report: {}", e),
+            },
+            Message::ShowDelivery => {
+                self.delivery_log = delivery::read_log(&self.report_path, &self.patient_name);
+                self.succursale_overlay_menu = Modals::Delivery;
+            }
+            Message::HideDelivery => {
+                self.succursale_overlay_menu = Modals::None;
+                self.save_settings();
+            }
+            Message::DeliverySettingChanged(field, value) => self.settings.delivery.set(field, value),
+            Message::SmtpSecurityChanged(security) => self.settings.delivery.security = security,
+            Message::PartnerContactChanged(contact) => {
+                self.settings.delivery.set_partner_contact(self.partner, contact)
+            }
+            Message::DeliverReport => {
+                self.save_settings();
+                match delivery::prepare(self) {
+                    Ok(job) => {
+                        self.delivering = true;
+                        // the server may take a while to answer, the window stays responsive
+                        return Command::perform(delivery::send_in_background(job), Message::DeliveryFinished);
+                    }
+                    Err(e) => println!("Failed to prepare the copies: {}", e),
+                }
+            }
+            Message::DeliveryFinished(records) => {
+                self.delivering = false;
+                if let Err(e) = delivery::append_log(&self.report_path, &records) {
+                    println!("Failed to write delivery log: {}", e);
+                }
+                self.delivery_log.extend(records);
+            }
+            Message::NoteChanged(index, value) => {
+                // typing past the end of the line goes on with the next line
+                let typing_at_end = value.starts_with(self.notes.line(index));
+                if self.notes.set_line(index, value) && typing_at_end {
+                    return notes::focus_line(index + 1);
+                }
+            }
+            Message::NoteSubmitted(index) => return notes::focus_line(index + 1),
+            Message::StandardSentenceChanged(sentence, checked) => {
+                self.notes.set_sentence(sentence, checked)
+            }
+
+            Message::ExaminerChanged(profile) => self.examiner = profile,
+            Message::ConfirmFinalize => self.succursale_overlay_menu = Modals::ConfirmFinalize,
+            Message::FinalizeReport => (),
+            Message::CancelFinalize => self.succursale_overlay_menu = Modals::None,
+            Message::AmendReport => self.amend(),
+            Message::VerifyReport => {
+                self.verification = Some(self.verify());
+                self.succursale_overlay_menu = Modals::Verification;
+            }
+            Message::HideVerification => self.succursale_overlay_menu = Modals::None,
+            Message::ScreeningChanged(enabled) => self.screening.enabled = enabled,
+            Message::ScreeningAgeChanged(age) => self.screening.age = age,
+            Message::ScreeningFileNumberChanged(number) => self.screening.file_number = number,
+            Message::ScreeningBirthDateChanged(date) => self.screening.birth_date = date,
+            Message::ScreeningSexChanged(sex) => self.screening.sex = sex,
+            Message::AgeCorrectionChanged(value) => self.screening.age_correction = value,
+            Message::SaveBaseline => {
+                if let Err(e) = self.save_baseline() {
+                    println!("Failed to save baseline: {}", e);
+                }
+            }
+            Message::LoadBaseline => {
+                if let Err(e) = self.load_baseline() {
+                    println!("Failed to load baseline: {}", e);
+                }
+            }
+            Message::PaediatricChanged(enabled) => {
+                self.paediatric.enabled = enabled;
+                if enabled {
+                    self.free_field.chart = FreeFieldChart::Binaural;
+                }
+            }
+            Message::StimulusChanged(stimulus) => self.paediatric.stimulus = stimulus,
+            Message::SpeechTestChanged(test) => self.paediatric.speech_test = test,
+            Message::ReliabilityNoteChanged(note, checked) => self.paediatric.set_note(note, checked),
+            Message::AudienceChanged(audience) => self.paediatric.audience = audience,
+            Message::SchoolRecommendationChanged(recommendation, checked) => {
+                self.paediatric.set_recommendation(recommendation, checked)
+            }
+            Message::HandicapChanged(enabled) => self.handicap.enabled = enabled,
+            Message::FormulaChanged(formula) => self.handicap.formula = formula,
+            Message::ShowClinicians => self.succursale_overlay_menu = Modals::Clinicians,
+            Message::HideClinicians => {
+                self.succursale_overlay_menu = Modals::None;
+                self.save_settings();
+            }
+            Message::ClinicianAdded => {
+                self.settings.clinicians.push(ClinicianProfile::blank());
+                self.save_settings();
+            }
+            Message::ClinicianRemoved(index) => {
+                if index < self.settings.clinicians.len() {
+                    self.settings.clinicians.remove(index);
+                    self.save_settings();
+                }
+            }
+            Message::ClinicianEdited(index, field, value) => {
+                if let Some(profile) = self.settings.clinicians.get_mut(index) {
+                    // the report of the examiner follows the edits of their profile
+                    let is_examiner = *profile == self.examiner;
+                    profile.set(field, value);
+                    if is_examiner {
+                        self.examiner = profile.clone();
+                    }
+                }
+            }
+
+            Message::PartnerChanged(value) => {
                 self.partner = value;
                 self.succursale_overlay_menu = Modals::Succursale;
             }
 
-            Message::SuccursaleChanged(value) =>  {
+            Message::SuccursaleChanged(value) => {
                 self.partner = value;
-                println!("succursale changed : {:?}", self.partner );
+                println!("succursale changed : {:?}", self.partner);
                 self.succursale_overlay_menu = Modals::None;
             }
 
+            Message::TemplateChanged(choice) => self.template = choice.name(),
+
             Message::ShowParnerChoices => {
                 // self.show_partner_choices = true;
                 self.succursale_overlay_menu = Modals::Partner;
@@ -156,6 +709,11 @@ impl Application for AudioRox {
             }
 
             Message::AdequateRestPeriodChanged(value) => self.adequate_rest_period = value,
+            Message::ExtendedFrequenciesChanged(value) => self.extended_frequencies = value,
+            Message::FreeFieldChartChanged(chart) => self.free_field.chart = chart,
+            Message::DegreeOfLossChanged(value) => self.overlays.degree_of_loss = value,
+            Message::SpeechBananaChanged(value) => self.overlays.speech_banana = value,
+            Message::FamiliarSoundsChanged(value) => self.overlays.familiar_sounds = value,
             Message::AnteriorThresholdDateChanged(value) => self.anterior_threshold_date = value,
             Message::AudiometerNameChanged(value) => self.audiometer_name = value,
             Message::TympanometerNameChanged(value) => self.tympanometer_name = value,
@@ -168,120 +726,20 @@ impl Application for AudioRox {
                 self.transductor = new_transductor;
             }
             Message::MethodChanged(new_method) => self.method = new_method,
-            // Message::SuccursaleChanged(new_succursale) => self.succursale = new_succursale,
-            Message::MSPRightChanged(value) => self.tonal_table_right.msp = value,
-            Message::MSP4RightChanged(value) => self.tonal_table_right.msp4 = value,
-            Message::FLCHRightChanged(value) => self.tonal_table_right.fletcher = value,
-
-            Message::MSPLeftChanged(value) => self.tonal_table_left.msp = value,
-            Message::MSP4LeftChanged(value) => self.tonal_table_left.msp4 = value,
-            Message::FLCHLeftChanged(value) => self.tonal_table_left.fletcher = value,
-
-            Message::SDPRightChanged(value) => self.vocal_table_right.sdp = value,
-            Message::SRPRightChanged(value) => self.vocal_table_right.srp = value,
-
-            // Message::ListRightChanged(value) => self.vocal_table_right.list = value,
-            Message::SDPLeftChanged(value) => self.vocal_table_left.sdp = value,
-            Message::SRPLeftChanged(value) => self.vocal_table_left.srp = value,
-
-            // Message::ListLeftChanged(value) => self.vocal_table_left.list = value,
-            Message::SDPFreeChanged(value) => self.vocal_table_binaural.sdp = value,
-            Message::SRPFreeChanged(value) => self.vocal_table_binaural.srp = value,
-
-            Message::MiscRightChanged(value) => self.vocal_table_right.misc = value,
-            Message::MiscLeftChanged(value) => self.vocal_table_left.misc = value,
-            Message::MiscBinChanged(value) => self.vocal_table_binaural.misc = value,
+            Message::FieldChanged(path, value) => {
+                self.audiogram_cursor = None;
+                match self.field_mut(path) {
+                    Some(field) => *field = value,
+                    None => println!("No field at {}", path.key()),
+                }
+            }
 
-            // Message::MiscRightChanged(new_misc) => self.vocal_misc_right = new_misc,
-            // Message::MiscLeftChanged(new_misc) => self.vocal_misc_left = new_misc,
-            // Message::MiscBinChanged(new_misc) => self.vocal_misc_bin = new_misc,
             Message::VocalLangChanged(value) => self.vocal_lang = value,
             Message::IsRecordedChanged(value) => self.is_recorded = value,
 
-            Message::IdLanRes1LeftChanged(value) => self.id_lang_left.result1 = value,
-            Message::IdLanRes2LeftChanged(value) => self.id_lang_left.result2 = value,
-            Message::IdLanLev1LeftChanged(value) => self.id_lang_left.level1 = value,
-            Message::IdLanLev2LeftChanged(value) => self.id_lang_left.level2 = value,
-            Message::IdLanList1LeftChanged(value) => self.id_lang_left.list1 = value,
-            Message::IdLanList2LeftChanged(value) => self.id_lang_left.list2 = value,
-
-            Message::IdLanRes1RightChanged(value) => self.id_lang_right.result1 = value,
-            Message::IdLanRes2RightChanged(value) => self.id_lang_right.result2 = value,
-            Message::IdLanLev1RightChanged(value) => self.id_lang_right.level1 = value,
-            Message::IdLanLev2RightChanged(value) => self.id_lang_right.level2 = value,
-            Message::IdLanList1RightChanged(value) => self.id_lang_right.list1 = value,
-            Message::IdLanList2RightChanged(value) => self.id_lang_right.list2 = value,
-
-            Message::IdLanRes1BinChanged(value) => self.id_lang_bin.result1 = value,
-            Message::IdLanRes2BinChanged(value) => self.id_lang_bin.result2 = value,
-            Message::IdLanLev1BinChanged(value) => self.id_lang_bin.level1 = value,
-            Message::IdLanLev2BinChanged(value) => self.id_lang_bin.level2 = value,
-            Message::IdLanList1BinChanged(value) => self.id_lang_bin.list1 = value,
-            Message::IdLanList2BinChanged(value) => self.id_lang_bin.list2 = value,
-
-            Message::TympaVolumeLeftChanged(value) => self.tympa_left.volume = value,
-            Message::TympaVolumeRightChanged(value) => self.tympa_right.volume = value,
-
-            Message::TympaPressureLeftChanged(value) => self.tympa_left.pressure = value,
-            Message::TympaPressureRightChanged(value) => self.tympa_right.pressure = value,
-
-            Message::TympaComplianceLeftChanged(value) => self.tympa_left.compliance = value,
-            Message::TympaComplianceRightChanged(value) => self.tympa_right.compliance = value,
-
-            Message::StapIpsi500LeftChanged(new) => self.stap_left.ipsi.khz_500 = new,
-            Message::StapIpsi1000LeftChanged(new) => self.stap_left.ipsi.khz_1000 = new,
-            Message::StapIpsi2000LeftChanged(new) => self.stap_left.ipsi.khz_2000 = new,
-            Message::StapIpsi4000LeftChanged(new) => self.stap_left.ipsi.khz_4000 = new,
-
-            Message::StapControl500LeftChanged(new) => self.stap_left.control.khz_500 = new,
-            Message::StapControl1000LeftChanged(new) => self.stap_left.control.khz_1000 = new,
-            Message::StapControl2000LeftChanged(new) => self.stap_left.control.khz_2000 = new,
-            Message::StapControl4000LeftChanged(new) => self.stap_left.control.khz_4000 = new,
-
-            Message::StapIpsi500RightChanged(new) => self.stap_right.ipsi.khz_500 = new,
-            Message::StapIpsi1000RightChanged(new) => self.stap_right.ipsi.khz_1000 = new,
-            Message::StapIpsi2000RightChanged(new) => self.stap_right.ipsi.khz_2000 = new,
-            Message::StapIpsi4000RightChanged(new) => self.stap_right.ipsi.khz_4000 = new,
-
-            Message::StapControl500RightChanged(new) => self.stap_right.control.khz_500 = new,
-            Message::StapControl1000RightChanged(new) => self.stap_right.control.khz_1000 = new,
-            Message::StapControl2000RightChanged(new) => self.stap_right.control.khz_2000 = new,
-            Message::StapControl4000RightChanged(new) => self.stap_right.control.khz_4000 = new,
-
             Message::None => {} // _ => {}
         }
 
         Command::none()
     }
-
-    fn subscription(&self) -> Subscription<Message> {
-        subscription::events_with(|event, status| match (event, status) {
-            (
-                iced::event::Event::Keyboard(keyboard::Event::KeyPressed {
-                    key_code: keyboard::KeyCode::S,
-                    modifiers: Modifiers::CTRL,
-                    ..
-                }),
-                event::Status::Ignored,
-            ) => Some(Message::SaveFile),
-            (
-                iced::event::Event::Keyboard(keyboard::Event::KeyPressed {
-                    key_code: keyboard::KeyCode::L,
-                    modifiers: Modifiers::CTRL,
-                    ..
-                }),
-                event::Status::Ignored,
-            ) => Some(Message::LoadFile),
-            _ => None,
-        })
-    }
-
-    fn view(&self)  ->  Element<Message> {
-        view(self)
-    }
-
-    fn theme(&self) -> Theme {
-        Theme::Light
-    }
 }
-
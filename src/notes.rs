@@ -0,0 +1,195 @@
+use iced::widget::text_input;
+use iced::Command;
+
+use serde::{Deserialize, Serialize};
+
+use crate::util::Message;
+
+/// Lines of the notes, as many as the ruled lines of the printed page.
+pub const NOTE_LINES: usize = 3;
+/// Characters fitting on a printed line of the notes.
+pub const NOTE_LINE_CHARS: usize = 56;
+
+/// Standard sentences printed below the notes when checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StandardSentence {
+    FullReportAttached,
+    NeedsAssessed,
+}
+
+pub const STANDARD_SENTENCES: [StandardSentence; 2] = [
+    StandardSentence::FullReportAttached,
+    StandardSentence::NeedsAssessed,
+];
+
+impl StandardSentence {
+    /// French text of the sentence, translated with `tr`.
+    pub fn text(&self) -> &'static str {
+        match self {
+            StandardSentence::FullReportAttached => "Voir rapport audiologique complet ci-joint.",
+            StandardSentence::NeedsAssessed => "Évaluation globale des besoins faite.",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Notes {
+    pub lines: Vec<String>,
+    /// Checked standard sentences, in the order of `STANDARD_SENTENCES`.
+    pub sentences: Vec<StandardSentence>,
+}
+
+impl Default for Notes {
+    fn default() -> Self {
+        Self {
+            lines: vec![String::new(); NOTE_LINES],
+            sentences: STANDARD_SENTENCES.to_vec(),
+        }
+    }
+}
+
+impl Notes {
+    pub fn line(&self, index: usize) -> &str {
+        self.lines.get(index).map(String::as_str).unwrap_or_default()
+    }
+
+    /// Line as printed: the last line stops where the printed area ends.
+    pub fn printed_line(&self, index: usize) -> &str {
+        let line = self.line(index);
+        if index + 1 == NOTE_LINES {
+            if let Some((kept, _)) = split_at_fit(line) {
+                return kept;
+            }
+        }
+        line
+    }
+
+    /// Characters typed past the end of the printed area, kept in the last line so that
+    /// nothing is lost, but not printed.
+    pub fn overflow(&self) -> usize {
+        split_at_fit(self.line(NOTE_LINES - 1)).map_or(0, |(_, overflow)| overflow.chars().count())
+    }
+
+    /// Replaces a line. What goes past the end of the line flows word by word onto the
+    /// next lines, and what does not fit in the printed area stays at the end of the
+    /// last line. Returns whether text flowed onto the next line.
+    pub fn set_line(&mut self, index: usize, value: String) -> bool {
+        self.lines.resize(NOTE_LINES, String::new());
+        if index >= NOTE_LINES {
+            return false;
+        }
+        self.lines[index] = value.replace(['\n', '\r'], " ");
+
+        let mut flowed = false;
+        for i in index..NOTE_LINES - 1 {
+            let Some((kept, overflow)) = split_line(&self.lines[i]) else {
+                break;
+            };
+            self.lines[i] = kept;
+
+            let next = &self.lines[i + 1];
+            self.lines[i + 1] = if next.is_empty() {
+                overflow
+            } else {
+                format!("{} {}", overflow, next)
+            };
+            flowed |= i == index;
+        }
+        flowed
+    }
+
+    /// Characters left before the printed area is full.
+    pub fn remaining(&self) -> usize {
+        let used: usize = self.lines.iter().map(|line| line.chars().count()).sum();
+        (NOTE_LINES * NOTE_LINE_CHARS).saturating_sub(used)
+    }
+
+    pub fn has_sentence(&self, sentence: StandardSentence) -> bool {
+        self.sentences.contains(&sentence)
+    }
+
+    pub fn set_sentence(&mut self, sentence: StandardSentence, checked: bool) {
+        self.sentences.retain(|s| *s != sentence);
+        if checked {
+            self.sentences.push(sentence);
+            self.sentences.sort_by_key(|s| STANDARD_SENTENCES.iter().position(|x| x == s));
+        }
+    }
+}
+
+/// Splits a line too long for the printed area at the last space that fits, or in the
+/// middle of a word longer than the line.
+fn split_at_fit(line: &str) -> Option<(&str, &str)> {
+    let (cut, after) = line.char_indices().nth(NOTE_LINE_CHARS)?;
+    let space = if after == ' ' { Some(cut) } else { line[..cut].rfind(' ') };
+    let (kept, overflow) = match space {
+        Some(space) if space > 0 => (&line[..space], &line[space + 1..]),
+        _ => (&line[..cut], &line[cut..]),
+    };
+    Some((kept.trim_end(), overflow.trim_start()))
+}
+
+fn split_line(line: &str) -> Option<(String, String)> {
+    split_at_fit(line).map(|(kept, overflow)| (kept.to_string(), overflow.to_string()))
+}
+
+pub fn input_id(index: usize) -> text_input::Id {
+    text_input::Id::new(format!("notes.{}", index))
+}
+
+/// Moves the typing on to a line, at the end of its text.
+pub fn focus_line(index: usize) -> Command<Message> {
+    if index >= NOTE_LINES {
+        return Command::none();
+    }
+    Command::batch([
+        text_input::focus(input_id(index)),
+        text_input::move_cursor_to_end(input_id(index)),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_that_fit_are_not_split() {
+        assert_eq!(split_line(""), None);
+        assert_eq!(split_line(&"a".repeat(NOTE_LINE_CHARS)), None);
+    }
+
+    #[test]
+    fn long_lines_are_split_between_words() {
+        let line = format!("{} suite", "mot ".repeat(14).trim_end());
+        let (kept, overflow) = split_line(&line).unwrap();
+        assert_eq!(kept, "mot ".repeat(14).trim_end());
+        assert_eq!(overflow, "suite");
+
+        let line = format!("{} suite", "mot ".repeat(15).trim_end());
+        let (kept, overflow) = split_line(&line).unwrap();
+        assert_eq!(kept, "mot ".repeat(14).trim_end());
+        assert_eq!(overflow, "mot suite");
+
+        // a space right after the last character that fits
+        let line = format!("{} suite", "a".repeat(NOTE_LINE_CHARS));
+        assert_eq!(split_line(&line), Some(("a".repeat(NOTE_LINE_CHARS), String::from("suite"))));
+    }
+
+    #[test]
+    fn words_longer_than_a_line_are_cut() {
+        let line = "é".repeat(NOTE_LINE_CHARS + 4);
+        assert_eq!(split_line(&line), Some(("é".repeat(NOTE_LINE_CHARS), "é".repeat(4))));
+    }
+
+    #[test]
+    fn text_flows_onto_the_next_lines() {
+        let mut notes = Notes::default();
+        notes.set_line(1, String::from("suite"));
+        assert!(notes.set_line(0, format!("{} fin", "mot ".repeat(14).trim_end())));
+        assert_eq!(notes.line(0), "mot ".repeat(14).trim_end());
+        assert_eq!(notes.line(1), "fin suite");
+        assert_eq!(notes.overflow(), 0);
+        assert!(!notes.set_line(2, String::from("court")));
+    }
+}
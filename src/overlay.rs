@@ -0,0 +1,149 @@
+use iced::alignment::{Horizontal, Vertical};
+use iced::widget::canvas::{self, Path, Text};
+use iced::{Point, Size};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{self, DEGREE_OF_LOSS_COLORS, SPEECH_BANANA_COLOR};
+use crate::locale::{tr, Language};
+use crate::plot::{level_y, FrequencyAxis};
+use crate::track_theme::TrackTheme;
+
+/// Layers drawn under the thresholds of the audiograms.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Overlays {
+    pub degree_of_loss: bool,
+    pub speech_banana: bool,
+    pub familiar_sounds: bool,
+}
+
+/// Degree of hearing loss of the BIAP classification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DegreeOfLoss {
+    Normal,
+    Mild,
+    Moderate,
+    Severe,
+    Profound,
+}
+
+impl DegreeOfLoss {
+    /// French name drawn on the overlay, translated with `tr`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            DegreeOfLoss::Normal => "Normal",
+            DegreeOfLoss::Mild => "Léger",
+            DegreeOfLoss::Moderate => "Moyen",
+            DegreeOfLoss::Severe => "Sévère",
+            DegreeOfLoss::Profound => "Profond",
+        }
+    }
+}
+
+/// Degrees of hearing loss with their lower and upper levels in dB HL.
+const DEGREES_OF_LOSS: [(DegreeOfLoss, f32, f32); 5] = [
+    (DegreeOfLoss::Normal, -10.0, 20.0),
+    (DegreeOfLoss::Mild, 20.0, 40.0),
+    (DegreeOfLoss::Moderate, 40.0, 70.0),
+    (DegreeOfLoss::Severe, 70.0, 90.0),
+    (DegreeOfLoss::Profound, 90.0, 120.0),
+];
+
+/// Outline of the area where the sounds of conversational speech fall, going along
+/// the soft sounds and back along the loud ones.
+const SPEECH_BANANA: [(u32, f32); 12] = [
+    (250, 25.0),
+    (500, 20.0),
+    (1000, 18.0),
+    (2000, 20.0),
+    (4000, 25.0),
+    (6000, 30.0),
+    (6000, 45.0),
+    (4000, 52.0),
+    (2000, 58.0),
+    (1000, 60.0),
+    (500, 58.0),
+    (250, 50.0),
+];
+
+/// Everyday sounds placed at their main frequency and usual level.
+const FAMILIAR_SOUNDS: [(&str, u32, f32); 8] = [
+    ("Feuilles", 4000, 10.0),
+    ("Chuchotement", 2000, 30.0),
+    ("Oiseaux", 6000, 40.0),
+    ("Piano", 250, 65.0),
+    ("Chien", 500, 80.0),
+    ("Téléphone", 2000, 85.0),
+    ("Tondeuse", 250, 95.0),
+    ("Avion", 1000, 115.0),
+];
+
+/// Degree of loss of an average hearing level, as shown on the overlay.
+pub fn degree_of_loss(level: f32) -> DegreeOfLoss {
+    DEGREES_OF_LOSS
+        .iter()
+        .find(|(_, _, to)| level < *to)
+        .map_or(DegreeOfLoss::Profound, |(degree, _, _)| *degree)
+}
+
+pub fn draw_overlays(
+    frame: &mut canvas::Frame,
+    axis: &FrequencyAxis,
+    overlays: Overlays,
+    track: &TrackTheme,
+    language: Language,
+) {
+    let label = Text {
+        color: track.overlay_label,
+        size: 12.0,
+        font: config::DEFAULT_FONT,
+        vertical_alignment: Vertical::Center,
+        ..Text::default()
+    };
+
+    if overlays.degree_of_loss {
+        for ((degree, from, to), color) in DEGREES_OF_LOSS.iter().zip(DEGREE_OF_LOSS_COLORS) {
+            let top = level_y(*from);
+            let bottom = level_y(*to);
+
+            frame.fill_rectangle(
+                Point::new(axis.first_x, top),
+                Size::new(axis.last_x - axis.first_x, bottom - top),
+                color,
+            );
+            frame.fill_text(Text {
+                content: tr(language, degree.name()).to_string(),
+                position: Point::new(axis.last_x - 4.0, (top + bottom) / 2.0),
+                horizontal_alignment: Horizontal::Right,
+                ..label.clone()
+            });
+        }
+    }
+
+    if overlays.speech_banana {
+        let banana = Path::new(|p| {
+            for (i, (frequency, level)) in SPEECH_BANANA.iter().enumerate() {
+                let point = Point::new(axis.x(*frequency), level_y(*level));
+                if i == 0 {
+                    p.move_to(point);
+                } else {
+                    p.line_to(point);
+                }
+            }
+            p.close();
+        });
+        frame.fill(&banana, SPEECH_BANANA_COLOR);
+    }
+
+    if overlays.familiar_sounds {
+        for (name, frequency, level) in FAMILIAR_SOUNDS.iter() {
+            frame.fill_text(Text {
+                content: tr(language, name).to_string(),
+                position: Point::new(axis.x(*frequency), level_y(*level)),
+                horizontal_alignment: Horizontal::Center,
+                ..label.clone()
+            });
+        }
+    }
+}
@@ -0,0 +1,262 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::overlay::{degree_of_loss, DegreeOfLoss};
+use crate::plot::Thresholds;
+use crate::util::{AudioRox, Transductor};
+
+/// Frequencies averaged for the degree of loss explained to the parents, after the BIAP.
+pub const AVERAGE_FREQUENCIES: [u32; 4] = [500, 1000, 2000, 4000];
+
+/// Stimulus of the sound-field thresholds, which are not specific to an ear.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Stimulus {
+    #[default]
+    WarbleTone,
+    NarrowBandNoise,
+}
+
+pub const STIMULI: [Stimulus; 2] = [Stimulus::WarbleTone, Stimulus::NarrowBandNoise];
+
+impl fmt::Display for Stimulus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Stimulus::WarbleTone => "Sons hululés",
+                Stimulus::NarrowBandNoise => "Bruits à bande étroite",
+            }
+        )
+    }
+}
+
+/// Speech test suited to the age of the child.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SpeechTest {
+    #[default]
+    None,
+    SpeechDetection,
+    BodyParts,
+    PicturePointing,
+    ChildrenWordLists,
+}
+
+pub const SPEECH_TESTS: [SpeechTest; 5] = [
+    SpeechTest::None,
+    SpeechTest::SpeechDetection,
+    SpeechTest::BodyParts,
+    SpeechTest::PicturePointing,
+    SpeechTest::ChildrenWordLists,
+];
+
+impl fmt::Display for SpeechTest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                SpeechTest::None => "Aucune",
+                SpeechTest::SpeechDetection => "Détection de la parole",
+                SpeechTest::BodyParts => "Identification de parties du corps",
+                SpeechTest::PicturePointing => "Désignation d'images",
+                SpeechTest::ChildrenWordLists => "Listes de mots pour enfants",
+            }
+        )
+    }
+}
+
+/// Remarks on how much the responses of the child can be relied on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReliabilityNote {
+    Consistent,
+    FluctuatingAttention,
+    PartialCooperation,
+    ToConfirm,
+}
+
+pub const RELIABILITY_NOTES: [ReliabilityNote; 4] = [
+    ReliabilityNote::Consistent,
+    ReliabilityNote::FluctuatingAttention,
+    ReliabilityNote::PartialCooperation,
+    ReliabilityNote::ToConfirm,
+];
+
+impl ReliabilityNote {
+    /// French text of the note, translated with `tr`.
+    pub fn text(&self) -> &'static str {
+        match self {
+            ReliabilityNote::Consistent => "Réponses constantes et fiables.",
+            ReliabilityNote::FluctuatingAttention => "Attention fluctuante durant l'évaluation.",
+            ReliabilityNote::PartialCooperation => "Enfant fatigué ou agité, collaboration partielle.",
+            ReliabilityNote::ToConfirm => "Résultats à confirmer lors d'une prochaine évaluation.",
+        }
+    }
+}
+
+/// Who the report is written for.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Audience {
+    /// The full report, for the referring physician and the other clinicians.
+    #[default]
+    Clinical,
+    /// A shorter report, in plain words, with recommendations for the class.
+    ParentsAndSchool,
+}
+
+pub const AUDIENCES: [Audience; 2] = [Audience::Clinical, Audience::ParentsAndSchool];
+
+impl fmt::Display for Audience {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Audience::Clinical => "Clinique",
+                Audience::ParentsAndSchool => "Parents et école",
+            }
+        )
+    }
+}
+
+/// Recommendations for the class, in the report for the parents and the school.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SchoolRecommendation {
+    FrontSeating,
+    QuietInstructions,
+    CheckUnderstanding,
+    AssistiveListening,
+    FollowUp,
+}
+
+pub const SCHOOL_RECOMMENDATIONS: [SchoolRecommendation; 5] = [
+    SchoolRecommendation::FrontSeating,
+    SchoolRecommendation::QuietInstructions,
+    SchoolRecommendation::CheckUnderstanding,
+    SchoolRecommendation::AssistiveListening,
+    SchoolRecommendation::FollowUp,
+];
+
+impl SchoolRecommendation {
+    /// French text of the recommendation, translated with `tr`.
+    pub fn text(&self) -> &'static str {
+        match self {
+            SchoolRecommendation::FrontSeating => "Placer l'enfant à l'avant de la classe, près de l'enseignant.",
+            SchoolRecommendation::QuietInstructions => "Réduire le bruit ambiant lors des consignes.",
+            SchoolRecommendation::CheckUnderstanding => "Vérifier la compréhension des consignes verbales.",
+            SchoolRecommendation::AssistiveListening => "Envisager un système d'aide à l'audition en classe.",
+            SchoolRecommendation::FollowUp => "Prévoir un suivi en audiologie.",
+        }
+    }
+}
+
+/// Evaluation of a child, shown in a box of the report when enabled. The sound-field
+/// thresholds are entered on the binaural chart.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Paediatric {
+    pub enabled: bool,
+    /// Stimulus of the next sound-field thresholds entered.
+    pub stimulus: Stimulus,
+    /// Stimulus each sound-field threshold was obtained with, by frequency.
+    pub stimuli: BTreeMap<u32, Stimulus>,
+    pub speech_test: SpeechTest,
+    /// Checked notes, in the order of `RELIABILITY_NOTES`.
+    pub reliability: Vec<ReliabilityNote>,
+    pub audience: Audience,
+    /// Checked recommendations, in the order of `SCHOOL_RECOMMENDATIONS`.
+    pub recommendations: Vec<SchoolRecommendation>,
+}
+
+impl Paediatric {
+    pub fn has_note(&self, note: ReliabilityNote) -> bool {
+        self.reliability.contains(&note)
+    }
+
+    pub fn set_note(&mut self, note: ReliabilityNote, checked: bool) {
+        self.reliability.retain(|n| *n != note);
+        if checked {
+            self.reliability.push(note);
+            self.reliability.sort_by_key(|n| RELIABILITY_NOTES.iter().position(|x| x == n));
+        }
+    }
+
+    pub fn has_recommendation(&self, recommendation: SchoolRecommendation) -> bool {
+        self.recommendations.contains(&recommendation)
+    }
+
+    pub fn set_recommendation(&mut self, recommendation: SchoolRecommendation, checked: bool) {
+        self.recommendations.retain(|r| *r != recommendation);
+        if checked {
+            self.recommendations.push(recommendation);
+            self.recommendations
+                .sort_by_key(|r| SCHOOL_RECOMMENDATIONS.iter().position(|x| x == r));
+        }
+    }
+
+    /// Frequencies of the sound-field thresholds obtained with each stimulus, in the
+    /// order of `STIMULI`. Reports saved before the stimulus was recorded by frequency
+    /// have the stimulus of the evaluation alone.
+    pub fn frequencies_by_stimulus(&self) -> Vec<(Stimulus, Vec<u32>)> {
+        if self.stimuli.is_empty() {
+            return vec![(self.stimulus, vec![])];
+        }
+        STIMULI
+            .iter()
+            .map(|stimulus| {
+                let frequencies = self
+                    .stimuli
+                    .iter()
+                    .filter(|(_, s)| *s == stimulus)
+                    .map(|(frequency, _)| *frequency)
+                    .collect::<Vec<_>>();
+                (*stimulus, frequencies)
+            })
+            .filter(|(_, frequencies)| !frequencies.is_empty())
+            .collect()
+    }
+
+    /// Whether the report is the short one for the parents and the school.
+    pub fn for_parents(&self) -> bool {
+        self.enabled && self.audience == Audience::ParentsAndSchool
+    }
+}
+
+/// Average over `AVERAGE_FREQUENCIES`, None unless a response was obtained at each.
+pub fn average(thresholds: &Thresholds) -> Option<f32> {
+    let levels: Option<Vec<f32>> = AVERAGE_FREQUENCIES
+        .iter()
+        .map(|frequency| {
+            thresholds
+                .get(frequency)
+                .filter(|threshold| !threshold.no_response)
+                .map(|threshold| threshold.level)
+        })
+        .collect();
+    levels.map(|levels| levels.iter().sum::<f32>() / levels.len() as f32)
+}
+
+/// Hearing of the thresholds in plain words, translated with `tr`. None while the
+/// average cannot be computed.
+pub fn plain_hearing(thresholds: &Thresholds) -> Option<&'static str> {
+    Some(match degree_of_loss(average(thresholds)?) {
+        DegreeOfLoss::Normal => "Audition normale",
+        DegreeOfLoss::Mild => "Perte auditive légère",
+        DegreeOfLoss::Moderate => "Perte auditive moyenne",
+        DegreeOfLoss::Severe => "Perte auditive sévère",
+        DegreeOfLoss::Profound => "Perte auditive profonde",
+    })
+}
+
+impl AudioRox {
+    /// Whether there are results by ear, obtained with inserts or headphones. Without
+    /// them, the paediatric report only shows the sound-field chart.
+    pub fn ear_specific(&self) -> bool {
+        matches!(self.transductor, Transductor::Intra | Transductor::Supra)
+            || [&self.audiogram_right, &self.audiogram_left]
+                .iter()
+                .any(|audiogram| !audiogram.air.is_empty() || !audiogram.bone.is_empty())
+    }
+}
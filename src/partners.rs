@@ -11,6 +11,7 @@ use iced::theme;
 use super::Message;
 
 use crate::config::{CustomButtonStyle, TEXT_LINE_VSPACE};
+use crate::locale::{tr, Language};
 
 use serde::{Deserialize, Serialize};
 
@@ -123,6 +124,16 @@ pub fn get_all_partners(partner: &PartnerAndSuccursale) -> Element<Message> {
     column![harmonie, bois, prevost, aures].spacing(2.0).into()
 }
 
+pub fn get_partner_name(partner: &PartnerAndSuccursale) -> &'static str {
+    match partner {
+        PartnerAndSuccursale::Harmonie(_) => "Harmonie",
+        PartnerAndSuccursale::Bois(_) => "Bois",
+        PartnerAndSuccursale::Prevost(_) => "Prevost",
+        PartnerAndSuccursale::Autres(_) => "Autres",
+        PartnerAndSuccursale::None => "Rapport",
+    }
+}
+
 pub fn get_chosen_succursale(partner: &PartnerAndSuccursale) -> (String, String) {
 
 
@@ -174,8 +185,190 @@ pub fn get_chosen_succursale(partner: &PartnerAndSuccursale) -> (String, String)
 }
 
 
+/// Sections of the report, in the order they can be laid out by a template.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReportSection {
+    Header,
+    TonalAudiometry,
+    /// Comparison to the baseline of a worker exposed to noise, in screening mode only.
+    NoiseScreening,
+    /// Stimulus, speech test and reliability of the evaluation of a child, in
+    /// paediatric mode only.
+    Paediatric,
+    VocalAudiometry,
+    SpeechIdentification,
+    Immittance,
+    /// Percentage of hearing loss computed from the thresholds, when enabled.
+    HearingHandicap,
+    /// Notes, CC, logo of the order and signature.
+    Conclusion,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReportFooter {
+    #[default]
+    None,
+    /// The name and address of the chosen clinic.
+    Succursale,
+}
+
+/// Layout of a report: which sections appear and in what order, the logo of the
+/// header and the footer. Kept in the user settings, where new ones can be added.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReportTemplate {
+    /// Name of the template, shown in the template selector.
+    pub name: String,
+    pub sections: Vec<ReportSection>,
+    /// Logo shown next to the name of the audiologist.
+    pub header_logo: Option<String>,
+    pub footer: ReportFooter,
+}
+
+impl Default for ReportTemplate {
+    fn default() -> Self {
+        Self {
+            name: String::from(STANDARD_TEMPLATE),
+            sections: ALL_SECTIONS.to_vec(),
+            header_logo: None,
+            footer: ReportFooter::None,
+        }
+    }
+}
+
+pub const ALL_SECTIONS: [ReportSection; 9] = [
+    ReportSection::Header,
+    ReportSection::TonalAudiometry,
+    ReportSection::NoiseScreening,
+    ReportSection::Paediatric,
+    ReportSection::VocalAudiometry,
+    ReportSection::SpeechIdentification,
+    ReportSection::Immittance,
+    ReportSection::HearingHandicap,
+    ReportSection::Conclusion,
+];
+
+/// Sections of the report for the parents and the school of a child, without the
+/// tables meant for clinicians.
+pub const PARENTS_SECTIONS: [ReportSection; 4] = [
+    ReportSection::Header,
+    ReportSection::TonalAudiometry,
+    ReportSection::Paediatric,
+    ReportSection::Conclusion,
+];
+
+/// Template used when neither the report nor its partner has one.
+pub const STANDARD_TEMPLATE: &str = "Standard";
+pub const PARTNER_TEMPLATE: &str = "Partenaire";
+
+/// Template of the reports of a partner, by the name given by `get_partner_name`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartnerTemplate {
+    pub partner: String,
+    pub template: String,
+}
+
+/// Report templates of the user settings, and the one of each partner.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TemplateSettings {
+    pub templates: Vec<ReportTemplate>,
+    pub partners: Vec<PartnerTemplate>,
+}
+
+impl Default for TemplateSettings {
+    fn default() -> Self {
+        let partner_template = |partner: &str| PartnerTemplate {
+            partner: partner.to_string(),
+            template: PARTNER_TEMPLATE.to_string(),
+        };
+        Self {
+            templates: vec![
+                ReportTemplate::default(),
+                ReportTemplate {
+                    name: String::from(PARTNER_TEMPLATE),
+                    header_logo: Some(String::from("images/logo.PNG")),
+                    footer: ReportFooter::Succursale,
+                    ..ReportTemplate::default()
+                },
+            ],
+            partners: vec![
+                partner_template("Harmonie"),
+                partner_template("Bois"),
+                partner_template("Prevost"),
+            ],
+        }
+    }
+}
+
+impl TemplateSettings {
+    fn find(&self, name: &str) -> Option<&ReportTemplate> {
+        self.templates.iter().find(|template| template.name == name)
+    }
+
+    /// Template of a report: the one chosen for the report, else the one of its
+    /// partner, else the standard one. Names that are no longer in the settings are
+    /// skipped.
+    pub fn for_report(&self, chosen: Option<&str>, partner: &PartnerAndSuccursale) -> ReportTemplate {
+        let partner_name = get_partner_name(partner);
+        let of_partner = self
+            .partners
+            .iter()
+            .find(|p| p.partner == partner_name)
+            .map(|p| p.template.as_str());
+
+        [chosen, of_partner, Some(STANDARD_TEMPLATE)]
+            .into_iter()
+            .flatten()
+            .find_map(|name| self.find(name))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Choices of the template selector: the template of the partner, then each one.
+    pub fn choices(&self) -> Vec<TemplateChoice> {
+        std::iter::once(TemplateChoice::Partner)
+            .chain(self.templates.iter().map(|template| TemplateChoice::Named(template.name.clone())))
+            .collect()
+    }
+}
+
+/// Template chosen for a report in the selector.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateChoice {
+    /// The template of the partner of the report.
+    Partner,
+    Named(String),
+}
+
+impl TemplateChoice {
+    pub fn of(chosen: &Option<String>) -> Self {
+        match chosen {
+            Some(name) => TemplateChoice::Named(name.clone()),
+            None => TemplateChoice::Partner,
+        }
+    }
+
+    pub fn name(self) -> Option<String> {
+        match self {
+            TemplateChoice::Partner => None,
+            TemplateChoice::Named(name) => Some(name),
+        }
+    }
+}
+
+impl std::fmt::Display for TemplateChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TemplateChoice::Partner => write!(f, "Selon le partenaire"),
+            TemplateChoice::Named(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+
 
-pub fn make_succursale_element(partner: &PartnerAndSuccursale) -> Container<'_,Message> {
+pub fn make_succursale_element(partner: &PartnerAndSuccursale, lang: Language) -> Container<'_,Message> {
     let ( clinic, succursale) = get_chosen_succursale(partner);
     let mut suc_element: Element<'_,Message> = text(succursale).to_owned().size(14).into();
 
@@ -199,7 +392,7 @@ pub fn make_succursale_element(partner: &PartnerAndSuccursale) -> Container<'_,M
 
 
     let location = container(column![
-        button(text(&("Lieu de l'évaluation : ".to_owned() + &clinic)).size(18.))
+        button(text(&(tr(lang, "Lieu de l'évaluation : ").to_owned() + &clinic)).size(18.))
             .on_press(Message::ShowParnerChoices)
             .padding(0.)
             .style(theme::Button::Custom(Box::new(CustomButtonStyle))),
@@ -642,3 +835,20 @@ pub mod modal {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn template_of_the_partner_unless_one_is_chosen() {
+        let templates = TemplateSettings::default();
+        let bois = PartnerAndSuccursale::Bois(Bois::Levy);
+
+        assert_eq!(templates.for_report(None, &bois).name, PARTNER_TEMPLATE);
+        assert_eq!(templates.for_report(None, &PartnerAndSuccursale::None).name, STANDARD_TEMPLATE);
+        assert_eq!(templates.for_report(Some(STANDARD_TEMPLATE), &bois).name, STANDARD_TEMPLATE);
+        // a template removed from the settings falls back on the one of the partner
+        assert_eq!(templates.for_report(Some("Supprimé"), &bois).name, PARTNER_TEMPLATE);
+    }
+}
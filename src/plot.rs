@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 
@@ -10,33 +12,188 @@ use iced::widget::canvas;
 
 use iced::widget::canvas::path::{Arc, Builder};
 use iced::widget::canvas::{Cache, Canvas,  Path, Text};
+use iced::widget::canvas::event::{self, Event};
 
 use iced::{mouse, Color, Renderer, Element, Length, Point, Rectangle, Size, Vector, Theme};
 
 use crate::config::{
-    self, CORNER_RADIUS, LEGEND_WIDTH, PLOT_CANVAS_HEIGHT, PLOT_CANVAS_WIDTH, PLOT_CA_CO_Y_SPACE,
+    self, CORNER_RADIUS, PLOT_CANVAS_HEIGHT, PLOT_CANVAS_WIDTH, PLOT_CA_CO_Y_SPACE,
     PLOT_DASH, PLOT_LEGEMD_SPACE, PLOT_SHAPE_SIZE, PLOT_SHAPE_STROKE, PLOT_SPACE,
-    PLOT_TICK_LABEL_SPACE, PLOT_TICK_SIZE, PLOT_X_AXIS, PLOT_X_OFFSET_START,
-    PLOT_Y_AXIS, PLOT_Y_OFFSET_START, SPACE, 
-    WINDOW_WIDTH,
+    PLOT_TICK_LABEL_SPACE, PLOT_TICK_SIZE, PLOT_EXTENDED, PLOT_INTER_OCTAVES, PLOT_OCTAVES,
+    PLOT_X_OFFSET_START, PLOT_Y_AXIS, PLOT_Y_OFFSET_START, SPACE, 
 };
+use crate::cursor::{frequencies, AudiogramCursor, CursorCurve};
+use crate::locale::{tr, Language};
+use crate::overlay::{draw_overlays, Overlays};
+use crate::track_theme::TrackTheme;
 use crate::Message;
 
-const NUM_X_TICKS: usize = 7;
+const NUM_X_TICKS: usize = PLOT_OCTAVES.len();
 const NUM_Y_TICKS: usize = 14;
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum EarSide {
     Right,
     Left,
     Free,
 }
-#[derive( Serialize, Deserialize)]
-enum Conduction {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Conduction {
     Bone,
     Air,
 }
 
+/// A threshold in dB HL and the modifiers drawn next to its symbol.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Threshold {
+    pub level: f32,
+    /// No response at the maximum output of the audiometer, `level` being that maximum.
+    pub no_response: bool,
+    /// The stimulus was felt rather than heard.
+    pub vibrotactile: bool,
+    /// Overmasking or insufficient masking.
+    pub insufficient_masking: bool,
+}
+
+/// Modifier of a threshold, toggled at the cursor with a key or from the legend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThresholdModifier {
+    NoResponse,
+    Vibrotactile,
+    InsufficientMasking,
+}
+
+pub const THRESHOLD_MODIFIERS: [ThresholdModifier; 3] = [
+    ThresholdModifier::NoResponse,
+    ThresholdModifier::Vibrotactile,
+    ThresholdModifier::InsufficientMasking,
+];
+
+impl ThresholdModifier {
+    /// Name of the modifier in the exports.
+    pub fn key(&self) -> &'static str {
+        match self {
+            ThresholdModifier::NoResponse => "no_response",
+            ThresholdModifier::Vibrotactile => "vibrotactile",
+            ThresholdModifier::InsufficientMasking => "insufficient_masking",
+        }
+    }
+}
+
+impl Threshold {
+    pub fn has(&self, modifier: ThresholdModifier) -> bool {
+        match modifier {
+            ThresholdModifier::NoResponse => self.no_response,
+            ThresholdModifier::Vibrotactile => self.vibrotactile,
+            ThresholdModifier::InsufficientMasking => self.insufficient_masking,
+        }
+    }
+
+    pub fn toggle(&mut self, modifier: ThresholdModifier) {
+        let flag = match modifier {
+            ThresholdModifier::NoResponse => &mut self.no_response,
+            ThresholdModifier::Vibrotactile => &mut self.vibrotactile,
+            ThresholdModifier::InsufficientMasking => &mut self.insufficient_masking,
+        };
+        *flag = !*flag;
+    }
+}
+
+/// Thresholds of one curve of the audiogram by frequency in Hz.
+pub type Thresholds = BTreeMap<u32, Threshold>;
+
+/// Thresholds measured for one ear.
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Audiogram {
+    pub air: Thresholds,
+    pub bone: Thresholds,
+    /// Uncomfortable loudness levels.
+    pub discomfort: Thresholds,
+}
+
+impl Audiogram {
+    pub fn thresholds(&self, conduction: Conduction) -> &Thresholds {
+        match conduction {
+            Conduction::Air => &self.air,
+            Conduction::Bone => &self.bone,
+        }
+    }
+
+    pub fn thresholds_mut(&mut self, conduction: Conduction) -> &mut Thresholds {
+        match conduction {
+            Conduction::Air => &mut self.air,
+            Conduction::Bone => &mut self.bone,
+        }
+    }
+
+    /// Range between the air conduction threshold and the discomfort level, in dB by
+    /// frequency, where both were obtained.
+    pub fn dynamic_range(&self) -> Vec<(u32, f32)> {
+        self.discomfort
+            .iter()
+            .filter_map(|(frequency, discomfort)| {
+                let threshold = self.air.get(frequency)?;
+                let has_response = !threshold.no_response && !discomfort.no_response;
+                has_response.then_some((*frequency, discomfort.level - threshold.level))
+            })
+            .collect()
+    }
+}
+
+/// Chart on which the free-field thresholds are drawn.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FreeFieldChart {
+    #[default]
+    Right,
+    Left,
+    Binaural,
+}
+
+pub const FREE_FIELD_CHARTS: [FreeFieldChart; 3] = [
+    FreeFieldChart::Right,
+    FreeFieldChart::Left,
+    FreeFieldChart::Binaural,
+];
+
+impl std::fmt::Display for FreeFieldChart {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FreeFieldChart::Right => write!(f, "Audiogramme droit"),
+            FreeFieldChart::Left => write!(f, "Audiogramme gauche"),
+            FreeFieldChart::Binaural => write!(f, "Graphique binaural"),
+        }
+    }
+}
+
+/// Thresholds measured in free field, without and with hearing aids.
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FreeField {
+    pub unaided: Thresholds,
+    pub aided: Thresholds,
+    pub chart: FreeFieldChart,
+}
+
+impl FreeField {
+    pub fn is_empty(&self) -> bool {
+        self.unaided.is_empty() && self.aided.is_empty()
+    }
+
+    /// Improvement brought by the hearing aids, in dB by frequency, where both
+    /// thresholds were obtained.
+    pub fn functional_gain(&self) -> Vec<(u32, f32)> {
+        self.unaided
+            .iter()
+            .filter_map(|(frequency, unaided)| {
+                let aided = self.aided.get(frequency)?;
+                let has_response = !unaided.no_response && !aided.no_response;
+                has_response.then_some((*frequency, unaided.level - aided.level))
+            })
+            .collect()
+    }
+}
 
 #[derive(Default, Serialize, Deserialize)]
 pub struct PlotInfo {
@@ -48,101 +205,211 @@ pub struct PlotInfo {
 
 
 pub struct Plot {
-    data: Vec<f32>,
+    audiogram: Audiogram,
+    free_field: Option<FreeField>,
     ear_side: EarSide,
-    shape: Shape,
+    extended: bool,
+    overlays: Overlays,
+    cursor: Option<AudiogramCursor>,
     space: f32,
-    top_left: Point,
+    language: Language,
 
-    _size: Size,
     _corner_radius: f32,
     _plot_cache: Cache,
 }
 
 impl Default for Plot {
     fn default() -> Self {
-        let data = vec![];
         Self {
-            data,
+            audiogram: Audiogram::default(),
+            free_field: None,
             ear_side: EarSide::Right,
-            shape: Shape::None,
+            extended: false,
+            overlays: Overlays::default(),
+            cursor: None,
             space: SPACE,
+            language: Language::default(),
             _corner_radius: CORNER_RADIUS,
             _plot_cache: Cache::default(),
-            top_left: Point::new(0.0, 0.0),
-            _size: Size::new(0.0, 0.0),
         }
     }
 }
 
-impl Plot {
-    pub fn new(data: Vec<f32>, shape: Shape, ear_side: EarSide) -> Self {
-        let space = PLOT_SPACE;
-        // let space = 10.;
+/// Horizontal placement of the frequencies, on a log scale starting at 125 Hz.
+pub struct FrequencyAxis {
+    pub first_x: f32,
+    pub last_x: f32,
+    octave_width: f32,
+}
 
-        let mut first_x = PLOT_X_OFFSET_START + space;
-        let plot_width = (PLOT_X_AXIS.len()) as f32 * PLOT_TICK_SIZE * 2.0;
-        let mut last_x = first_x + plot_width - PLOT_TICK_SIZE * 0.6;
+impl FrequencyAxis {
+    /// Fits the axis in a canvas of the given width, the tick labels of the levels
+    /// being on the outer side of each ear.
+    fn new(ear_side: EarSide, extended: bool, width: f32) -> Self {
+        let (first_x, last_x) = if let EarSide::Left = ear_side {
+            let first_x = PLOT_LEGEMD_SPACE - 6.0;
+            (first_x, width - PLOT_X_OFFSET_START - PLOT_SPACE - PLOT_TICK_SIZE * 0.6)
+        } else {
+            (PLOT_X_OFFSET_START + PLOT_SPACE, width - PLOT_LEGEMD_SPACE)
+        };
 
-        let y_size = (PLOT_Y_AXIS.len() - 1) as f32 * PLOT_TICK_SIZE;
+        // the extended frequencies squeeze the octaves, keeping clear of the "Hz" label
+        let octave_width = if extended {
+            let last_frequency = PLOT_EXTENDED[PLOT_EXTENDED.len() - 1];
+            (last_x - first_x - PLOT_TICK_SIZE * 1.6) / octaves(last_frequency)
+        } else {
+            PLOT_TICK_SIZE * 2.0
+        };
 
-        if let EarSide::Left = ear_side {
-            first_x = space;
-            last_x = first_x + plot_width - PLOT_TICK_SIZE;
+        Self {
+            first_x,
+            last_x,
+            octave_width,
         }
+    }
 
-        let top_left = Point::new(first_x, PLOT_Y_OFFSET_START);
-        let size = Size::new(last_x - first_x, y_size);
+    pub fn x(&self, frequency: u32) -> f32 {
+        self.first_x + self.octave_width * octaves(frequency)
+    }
+}
 
+/// Number of octaves above the first frequency of the axis.
+fn octaves(frequency: u32) -> f32 {
+    (frequency as f32 / PLOT_OCTAVES[0] as f32).log2()
+}
+
+pub fn level_y(level: f32) -> f32 {
+    (level - PLOT_Y_AXIS[0] as f32) / 10.0 * PLOT_TICK_SIZE + PLOT_Y_OFFSET_START + PLOT_SPACE
+}
+
+fn y_level(y: f32) -> f32 {
+    (y - PLOT_Y_OFFSET_START - PLOT_SPACE) / PLOT_TICK_SIZE * 10.0 + PLOT_Y_AXIS[0] as f32
+}
+
+impl Plot {
+    pub fn new(
+        audiogram: Audiogram,
+        free_field: Option<FreeField>,
+        ear_side: EarSide,
+        extended: bool,
+        overlays: Overlays,
+        cursor: Option<AudiogramCursor>,
+        language: Language,
+    ) -> Self {
         Plot {
-            data,
-            shape,
+            audiogram,
+            free_field,
             ear_side,
-            top_left,
-            _size: size,
-            space,
+            extended,
+            overlays,
+            cursor,
+            space: PLOT_SPACE,
+            language,
             ..Default::default()
         }
     }
 
-    fn get_conduction(&self) -> Conduction {
-        match self.shape {
-            Shape::LeftBracket | Shape::RightBracket | Shape::Greater | Shape::Less => {
-                Conduction::Bone
-            }
-            _ => Conduction::Air,
+    /// Symbols of the air and bone conduction thresholds of the ear.
+    fn symbols(&self) -> (Shape, Shape) {
+        match self.ear_side {
+            EarSide::Right => (Shape::Circle, Shape::Less),
+            EarSide::Left => (Shape::X, Shape::Greater),
+            EarSide::Free => (Shape::None, Shape::None),
         }
     }
 
-    fn plot_data(&self, frame: &mut canvas::Frame) {
-        // let space = self.space;
-        // let x_offset = PLOT_X_OFFSET_START + space;
-        // let y_offset = PLOT_Y_OFFSET_START + space;
+    /// Thresholds drawn on the chart with their symbol, air conduction first.
+    fn curves(&self) -> Vec<(&Thresholds, Shape)> {
+        let (air, bone) = self.symbols();
+        let mut curves = vec![
+            (&self.audiogram.air, air),
+            (&self.audiogram.bone, bone),
+            (&self.audiogram.discomfort, Shape::U),
+        ];
+
+        if let Some(free_field) = &self.free_field {
+            curves.push((&free_field.unaided, Shape::S));
+            curves.push((&free_field.aided, Shape::A));
+        }
+        curves
+    }
 
-        // let plot_width = frame.width() - PLOT_X_OFFSET_START * 2.0;
-        // let plot_height = frame.height() - y_offset * 2.0;
+    fn is_on_axis(&self, frequency: u32) -> bool {
+        let last_octave = PLOT_OCTAVES[PLOT_OCTAVES.len() - 1];
+        frequency >= PLOT_OCTAVES[0] && (self.extended || frequency <= last_octave)
+    }
+
+    /// Two thresholds are not connected when a frequency that is always tested lies
+    /// between them without a threshold. Inter-octaves are optional and never break
+    /// the line.
+    fn is_gap(&self, thresholds: &Thresholds, from: u32, to: u32) -> bool {
+        let extended: &[u32] = if self.extended { &PLOT_EXTENDED } else { &[] };
 
-        let x_unit = PLOT_TICK_SIZE; //plot_width / NUM_X_TICKS as f32;
-        let y_unit = PLOT_TICK_SIZE; //plot_height / 12.0;
+        PLOT_OCTAVES
+            .iter()
+            .chain(extended)
+            .any(|f| from < *f && *f < to && !thresholds.contains_key(f))
+    }
+
+    /// Glyphs drawn next to the symbol of the threshold, as in the legend.
+    fn modifiers(&self, threshold: &Threshold) -> Vec<Shape> {
+        let no_response = match self.ear_side {
+            EarSide::Left => Shape::RightArrow,
+            _ => Shape::LeftArrow,
+        };
 
-        let line_dash = if let Conduction::Bone = self.get_conduction() {
+        [
+            (threshold.no_response, no_response),
+            (threshold.vibrotactile, Shape::VT),
+            (threshold.insufficient_masking, Shape::Asterisk),
+        ]
+        .into_iter()
+        .filter_map(|(is_set, shape)| is_set.then_some(shape))
+        .collect()
+    }
+
+    fn plot_data(
+        &self,
+        frame: &mut canvas::Frame,
+        axis: &FrequencyAxis,
+        thresholds: &Thresholds,
+        shape: Shape,
+        color: Color,
+    ) {
+        let stroke = canvas::Stroke {
+            style: canvas::Style::Solid(color),
+            ..PLOT_SHAPE_STROKE
+        };
+        let line_dash = if let Conduction::Bone = shape.conduction() {
             PLOT_DASH
         } else {
             canvas::LineDash::default()
         };
 
-        let mut builder = Builder::new();
-
-        // Draw lines between the points
-        for i in 0..(self.data.len() - 1) {
-            let x1 = 2.0 * (i + 1) as f32 * x_unit + self.top_left.x;
-            let y1 = self.data[i] / 10.0 * y_unit + self.top_left.y;
+        let points: Vec<(u32, &Threshold, Point)> = thresholds
+            .iter()
+            .filter(|(frequency, _)| self.is_on_axis(**frequency))
+            .map(|(frequency, threshold)| {
+                let point = Point::new(axis.x(*frequency), level_y(threshold.level));
+                (*frequency, threshold, point)
+            })
+            .collect();
 
-            let x2 = 2.0 * (i + 2) as f32 * x_unit + self.top_left.x;
-            let y2 = self.data[i + 1] / 10.0 * y_unit + self.top_left.y;
+        let mut builder = Builder::new();
 
-            let point1 = Point::new(x1, y1);
-            let point2 = Point::new(x2, y2);
+        // Draw lines between the points, a point without response is not a threshold
+        // and is never connected
+        for pair in points.windows(2) {
+            let (from, threshold1, point1) = pair[0];
+            let (to, threshold2, point2) = pair[1];
+
+            if !shape.is_connected()
+                || threshold1.no_response
+                || threshold2.no_response
+                || self.is_gap(thresholds, from, to)
+            {
+                continue;
+            }
 
             builder.move_to(point1);
             builder.line_to(point2);
@@ -153,20 +420,89 @@ impl Plot {
             &builder.build(),
             canvas::Stroke {
                 line_dash,
-                ..PLOT_SHAPE_STROKE
+                ..stroke.clone()
             },
         );
 
         // Draw points
-        for (i, value) in self.data.iter().enumerate() {
-            let x = 2.0 * (i + 1) as f32 * x_unit + self.top_left.x;
-            let y = value / 10.0 * y_unit + self.top_left.y;
+        for (_, threshold, point) in points.iter() {
             frame.stroke(
-                &self.shape.draw_shape(Point::new(x, y), PLOT_SHAPE_SIZE),
-                PLOT_SHAPE_STROKE,
+                &shape.draw_shape(*point, PLOT_SHAPE_SIZE),
+                stroke.clone(),
             );
+
+            for modifier in self.modifiers(threshold) {
+                frame.stroke(
+                    &modifier.draw_shape(*point, PLOT_SHAPE_SIZE),
+                    stroke.clone(),
+                );
+            }
         }
     }
+
+    /// Cross-hair on the frequency and level of the cursor, with the symbol of the
+    /// threshold it enters and the level being typed.
+    fn draw_cursor(
+        &self,
+        frame: &mut canvas::Frame,
+        axis: &FrequencyAxis,
+        cursor: &AudiogramCursor,
+        color: Color,
+    ) {
+        let point = Point::new(axis.x(cursor.frequency), level_y(cursor.level));
+        let top = level_y(PLOT_Y_AXIS[0] as f32);
+        let bottom = level_y(PLOT_Y_AXIS[PLOT_Y_AXIS.len() - 1] as f32);
+
+        frame.stroke(
+            &Path::new(|p| {
+                p.move_to(Point::new(point.x, top));
+                p.line_to(Point::new(point.x, bottom));
+                p.move_to(Point::new(axis.first_x, point.y));
+                p.line_to(Point::new(axis.last_x, point.y));
+            }),
+            canvas::Stroke {
+                style: canvas::Style::Solid(color),
+                width: 1.0,
+                line_dash: canvas::LineDash {
+                    segments: &[2., 3.],
+                    offset: 0,
+                },
+                ..canvas::Stroke::default()
+            },
+        );
+
+        let (air, bone) = self.symbols();
+        let shape = match cursor.curve {
+            CursorCurve::Air => air,
+            CursorCurve::Bone => bone,
+            CursorCurve::Discomfort => Shape::U,
+            CursorCurve::Unaided => Shape::S,
+            CursorCurve::Aided => Shape::A,
+        };
+        frame.stroke(
+            &shape.draw_shape(point, PLOT_SHAPE_SIZE),
+            canvas::Stroke {
+                style: canvas::Style::Solid(color),
+                width: 2.0,
+                ..PLOT_SHAPE_STROKE
+            },
+        );
+
+        let label = if cursor.typed.is_empty() {
+            format!("{} dB", cursor.level)
+        } else {
+            format!("{}_", cursor.typed)
+        };
+        frame.fill_text(Text {
+            content: label,
+            color,
+            size: 14.0,
+            font: config::DEFAULT_FONT,
+            position: point + Vector::new(PLOT_SHAPE_SIZE + 3.0, -PLOT_SHAPE_SIZE - 3.0),
+            vertical_alignment: Vertical::Bottom,
+            ..Text::default()
+        });
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -183,15 +519,52 @@ impl Default for Interaction {
 impl canvas::Program<Message> for Plot {
     type State = Interaction;
 
+    /// A click on a chart places the keyboard cursor on the nearest frequency and level.
+    fn update(
+        &self,
+        _state: &mut Self::State,
+        event: Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> (event::Status, Option<Message>) {
+        let Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) = event else {
+            return (event::Status::Ignored, None);
+        };
+        let Some(position) = cursor.position_in(bounds) else {
+            return (event::Status::Ignored, None);
+        };
+
+        let axis = FrequencyAxis::new(self.ear_side, self.extended, bounds.width);
+        let level = y_level(position.y);
+        let on_chart = (axis.first_x..=axis.last_x).contains(&position.x)
+            && (PLOT_Y_AXIS[0] as f32 - 5.0..=PLOT_Y_AXIS[PLOT_Y_AXIS.len() - 1] as f32 + 5.0)
+                .contains(&level);
+        if !on_chart {
+            return (event::Status::Ignored, None);
+        }
+
+        let distance = |frequency: &u32| (axis.x(*frequency) - position.x).abs();
+        let frequency = frequencies(self.extended)
+            .into_iter()
+            .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+            .unwrap_or(PLOT_OCTAVES[0]);
+
+        (
+            event::Status::Captured,
+            Some(Message::AudiogramCursorPlaced(self.ear_side, frequency, level)),
+        )
+    }
+
     fn draw(
         &self,
         _state: &Self::State,
         renderer: &Renderer,
-        _theme: &Theme,
+        theme: &Theme,
         bounds: Rectangle,
         _cursor: mouse::Cursor,
     ) -> Vec<canvas::Geometry> {
         let mut frame = canvas::Frame::new(renderer, bounds.size());
+        let track = TrackTheme::of(theme);
 
 
         
@@ -203,17 +576,12 @@ impl canvas::Program<Message> for Plot {
         // let y_offset1 = PLOT_Y_OFFSET_END;
 
         let y_axis = [-10, 0, 10, 20, 30, 40, 50, 60, 70, 80, 90, 100, 110, 120];
-        let x_axis = [125, 250, 500, 1000, 2000, 4000, 8000];
 
-        let x_offset = PLOT_X_OFFSET_START;
+        let axis = FrequencyAxis::new(self.ear_side, self.extended, bounds.width);
+        draw_overlays(&mut frame, &axis, self.overlays, track, self.language);
+        // width of the cells of the CA CO table, which follow the frequencies
+        let half_octave = axis.octave_width / 2.0;
 
-        // if let EarSide::Right = self.ear_side {
-        //     PLOT_X_OFFSET_START
-        // } else {
-        //     PLOT_X_OFFSET_START
-        // };
-
-        let plot_width = (x_axis.len()) as f32 * (PLOT_TICK_SIZE * 2.0 - 1.);
         let plot_height = PLOT_Y_OFFSET_START + space + (y_axis.len() - 1) as f32 * PLOT_TICK_SIZE;
 
         let y_unit = PLOT_TICK_SIZE;
@@ -223,7 +591,7 @@ impl canvas::Program<Message> for Plot {
 
 
         let legend_text = Text {
-            color: config::AXIS_LABEL_COLOR,
+            color: track.axis_label,
             size: 16.0,
             font: config::DEFAULT_FONT,
             ..Text::default()
@@ -232,8 +600,8 @@ impl canvas::Program<Message> for Plot {
         let mut y: f32;
 
 
-        let mut first_x = x_offset + space;
-        let mut last_x = (WINDOW_WIDTH as f32 - LEGEND_WIDTH) / 2.0 - PLOT_LEGEMD_SPACE * 2. - 5.0;
+        let first_x = axis.first_x;
+        let last_x = axis.last_x;
 
 
 
@@ -252,9 +620,6 @@ impl canvas::Program<Message> for Plot {
         let mut ca_label_x = first_x - PLOT_TICK_LABEL_SPACE;
 
         if let EarSide::Left = self.ear_side {
-            first_x = PLOT_LEGEMD_SPACE - 6.0;
-            last_x = first_x + plot_width - x_unit * 0.6;
-
             y_tick_x_pos = last_x + PLOT_TICK_LABEL_SPACE;
             y0_tick_x_pos = y_tick_x_pos;
             y_tick_h_align = Horizontal::Left;
@@ -293,7 +658,7 @@ impl canvas::Program<Message> for Plot {
                     }),
                     // y_stroke.clone(),
                     canvas::Stroke {
-                        style: canvas::Style::Solid(Color::from_rgb(0.5, 0.5, 0.5)),
+                        style: canvas::Style::Solid(track.threshold_20),
                         width: 2.0,
                         line_cap: canvas::LineCap::Round,
                         line_join: canvas::LineJoin::Round,
@@ -309,7 +674,7 @@ impl canvas::Program<Message> for Plot {
                     }),
                     // y_stroke.clone(),
                     canvas::Stroke {
-                        style: canvas::Style::Solid(config::GRID_COLOR),
+                        style: canvas::Style::Solid(track.grid),
                         width: 1.0,
                         line_cap: canvas::LineCap::Round,
                         line_join: canvas::LineJoin::Round,
@@ -343,15 +708,15 @@ impl canvas::Program<Message> for Plot {
 
 
         let x_stroke = canvas::Stroke {
-            style: canvas::Style::Solid(config::GRID_COLOR),
+            style: canvas::Style::Solid(track.grid),
             width: 1.0,
             line_cap: canvas::LineCap::Round,
             line_join: canvas::LineJoin::Round,
             ..canvas::Stroke::default()
         };
 
-        for x_usize in 0..7 {
-            let x = x_unit * 2.0 * x_usize as f32 + first_x;
+        for x_tick in PLOT_OCTAVES.iter() {
+            let x = axis.x(*x_tick);
 
             frame.stroke(
                 &Path::new(|p| {
@@ -360,7 +725,7 @@ impl canvas::Program<Message> for Plot {
                 }),
                 x_stroke.clone(),
             );
-            let content = format!("{}", x_axis[x_usize] as f32 / 1.0);
+            let content = format!("{}", *x_tick as f32 / 1.0);
 
 
             frame.fill_text(Text {
@@ -382,8 +747,9 @@ impl canvas::Program<Message> for Plot {
         );
 
         // pointillé
-        for x_usize in 2..NUM_X_TICKS - 1 {
-            let x = x_unit * 2.0 * (x_usize as f32 + 0.5) + first_x;
+        let extended: &[u32] = if self.extended { &PLOT_EXTENDED } else { &[] };
+        for x_tick in PLOT_INTER_OCTAVES.iter().chain(extended) {
+            let x = axis.x(*x_tick);
 
             frame.stroke(
                 &Path::new(|p| {
@@ -391,7 +757,7 @@ impl canvas::Program<Message> for Plot {
                     p.line_to(Point::new(x, plot_height));
                 }),
                 canvas::Stroke {
-                    style: canvas::Style::Solid(config::GRID_COLOR),
+                    style: canvas::Style::Solid(track.grid),
                     width: 1.0,
                     line_cap: canvas::LineCap::Round,
                     line_join: canvas::LineJoin::Round,
@@ -403,14 +769,40 @@ impl canvas::Program<Message> for Plot {
             )
         }
 
+        // the extended frequencies are too close to each other and to 8000 to all be
+        // labelled, only the end of the range is
+        if let Some(x_tick) = extended.last() {
+            frame.fill_text(Text {
+                content: format!("{}k", x_tick / 1000),
+                horizontal_alignment: Horizontal::Center,
+                vertical_alignment: Vertical::Bottom,
+                position: Point::new(axis.x(*x_tick), space - PLOT_TICK_LABEL_SPACE + PLOT_Y_OFFSET_START),
+                size: 13.0,
+                ..legend_text
+            });
+        }
+
+        for (thresholds, shape) in self.curves() {
+            self.plot_data(&mut frame, &axis, thresholds, shape, track.curve);
+        }
+
+        if let Some(cursor) = &self.cursor {
+            self.draw_cursor(&mut frame, &axis, cursor, track.palette.primary);
+        }
+
+        // the binaural chart only holds free-field thresholds, without air and bone conduction
+        if let EarSide::Free = self.ear_side {
+            return vec![frame.into_geometry()];
+        }
+
         //////////////////////////////// bottom CA CO table //////////////////////////////////////
 
 
-        let max_x = x_unit * ((2 * NUM_X_TICKS) as f32 + 0.5 - 2.0);
+        let max_x = half_octave * ((2 * NUM_X_TICKS) as f32 + 0.5 - 2.0);
         let size = Size::new(max_x, PLOT_TICK_SIZE * 2.0);
 
         let caco_stroke = canvas::Stroke {
-            style: canvas::Style::Solid(config::GRID_COLOR),
+            style: canvas::Style::Solid(track.grid),
             width: 1.0,
             line_cap: canvas::LineCap::Round,
             line_join: canvas::LineJoin::Round,
@@ -432,7 +824,7 @@ impl canvas::Program<Message> for Plot {
         );
 
         for x in 1..(2 * NUM_X_TICKS - 1) {
-            let x = x_unit * (x as f32 + 0.5) + ca_upper_left.x;
+            let x = half_octave * (x as f32 + 0.5) + ca_upper_left.x;
 
             frame.stroke(
                 &Path::new(|p| {
@@ -440,7 +832,7 @@ impl canvas::Program<Message> for Plot {
                     p.line_to(Point::new(x, ca_upper_left.y + size.height));
                 }),
                 canvas::Stroke {
-                    style: canvas::Style::Solid(config::GRID_COLOR),
+                    style: canvas::Style::Solid(track.grid),
                     width: 1.0,
                     line_cap: canvas::LineCap::Round,
                     line_join: canvas::LineJoin::Round,
@@ -450,31 +842,31 @@ impl canvas::Program<Message> for Plot {
         }
         frame.fill(
             &Path::new(|p| {
-                p.rectangle(ca_upper_left, Size::new(x_unit * 1.5, size.height));
+                p.rectangle(ca_upper_left, Size::new(half_octave * 1.5, size.height));
             }),
-            config::GRAY,
+            track.section_background,
         );
         frame.fill(
             &Path::new(|p| {
                 p.rectangle(
-                    ca_upper_left + Vector::new(x_unit * 2.5, 0.0),
-                    Size::new(x_unit, size.height),
+                    ca_upper_left + Vector::new(half_octave * 2.5, 0.0),
+                    Size::new(half_octave, size.height),
                 );
             }),
-            config::GRAY,
+            track.section_background,
         );
 
         frame.fill(
             &Path::new(|p| {
                 p.rectangle(
-                    ca_upper_left + Vector::new(x_unit * 10.5, size.height / 2.0),
-                    Size::new(x_unit * 2.0, size.height / 2.0),
+                    ca_upper_left + Vector::new(half_octave * 10.5, size.height / 2.0),
+                    Size::new(half_octave * 2.0, size.height / 2.0),
                 );
             }),
-            config::GRAY,
+            track.section_background,
         );
         frame.fill_text(Text {
-            content: "CA".to_string(),
+            content: tr(self.language, "CA").to_string(),
             horizontal_alignment: ca_h_align,
             vertical_alignment: Vertical::Center,
             position: Point::new(ca_label_x, y1 + size.height / 4.0),
@@ -482,7 +874,7 @@ impl canvas::Program<Message> for Plot {
         });
 
         frame.fill_text(Text {
-            content: "CO".to_string(),
+            content: tr(self.language, "CO").to_string(),
             horizontal_alignment: ca_h_align,
             vertical_alignment: Vertical::Center,
             position: Point::new(ca_label_x, y1 + 3.0 * size.height / 4.0),
@@ -491,8 +883,6 @@ impl canvas::Program<Message> for Plot {
 
         //////////////////////////////// bottom CA CO table //////////////////////////////////////
 
-        self.plot_data(&mut frame);
-
         vec![frame.into_geometry()]
     }
 }
@@ -628,8 +1018,24 @@ pub fn add_contour(
     );
 }
 
-pub fn plot<'a>(data: Vec<f32>, shape: Shape, ear_side: EarSide) -> Element<'a, Message> {
-    let plotter = Plot::new(data, shape, ear_side);
+pub fn plot<'a>(
+    audiogram: &Audiogram,
+    free_field: Option<&FreeField>,
+    ear_side: EarSide,
+    extended: bool,
+    overlays: Overlays,
+    cursor: Option<&AudiogramCursor>,
+    language: Language,
+) -> Element<'a, Message> {
+    let plotter = Plot::new(
+        audiogram.clone(),
+        free_field.cloned(),
+        ear_side,
+        extended,
+        overlays,
+        cursor.filter(|cursor| cursor.ear == ear_side).cloned(),
+        language,
+    );
     // plotter.plot_data()
     // Element::new(Plot::new(data))
     let can = Canvas::new(plotter)
@@ -657,9 +1063,26 @@ pub enum Shape {
     LeftArrow,
     LeftBracket,
     RightBracket,
+    Asterisk,
+    S,
+    A,
 }
 
 impl Shape {
+    fn conduction(&self) -> Conduction {
+        match self {
+            Shape::LeftBracket | Shape::RightBracket | Shape::Greater | Shape::Less => {
+                Conduction::Bone
+            }
+            _ => Conduction::Air,
+        }
+    }
+
+    /// Discomfort levels are not thresholds, their points stay apart.
+    fn is_connected(&self) -> bool {
+        !matches!(self, Shape::U)
+    }
+
     // method that takes a string and returns the
     fn draw_shape(&self, position: Point, size: f32) -> Path {
         match self {
@@ -675,6 +1098,9 @@ impl Shape {
             Shape::LeftArrow => Shape::bottom_left_arrow(position, size),
             Shape::LeftBracket => Shape::left_bracket(position, size),
             Shape::RightBracket => Shape::right_bracket(position, size),
+            Shape::Asterisk => Shape::asterisk(position, size),
+            Shape::S => Shape::s(position, size),
+            Shape::A => Shape::a(position, size),
 
             _ => Path::new(|_| {}),
         }
@@ -0,0 +1,346 @@
+use std::io::Write;
+
+use iced::alignment::Horizontal;
+use iced::widget::image::Handle;
+use iced::widget::{column, container, horizontal_space, image as image_widget, row, text, vertical_space};
+use iced::{ContentFit, Length};
+
+use crate::export::{self, ExportDpi, SCREEN_DPI};
+use crate::locale::tr;
+use crate::partners::get_chosen_succursale;
+use crate::signing::EXPORT_METADATA_KEY;
+use crate::util::{AudioRox, Message};
+
+/// Resolution of the document sent to the printer.
+pub const PRINT_DPI: ExportDpi = ExportDpi(300);
+
+/// Command used to queue the generated document. Any command accepting the same
+/// arguments as `lp` can be used, e.g. a script standing in for the spooler.
+pub const PRINT_COMMAND_VAR: &str = "AUDIOROX_PRINT_COMMAND";
+pub const DEFAULT_PRINT_COMMAND: &str = "lp";
+
+const HEADER_HEIGHT: f32 = 28.0;
+const FOOTER_HEIGHT: f32 = 22.0;
+const HEADER_TEXT_SIZE: f32 = 11.0;
+
+pub const PAPER_SIZES: [PaperSize; 2] = [PaperSize::Letter, PaperSize::A4];
+pub const MARGINS: [Margin; 4] = [Margin(5), Margin(10), Margin(13), Margin(20)];
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaperSize {
+    #[default]
+    Letter,
+    A4,
+}
+
+impl PaperSize {
+    /// Width and height in inches.
+    pub fn size_inches(&self) -> (f32, f32) {
+        match self {
+            PaperSize::Letter => (8.5, 11.0),
+            PaperSize::A4 => (210.0 / 25.4, 297.0 / 25.4),
+        }
+    }
+
+    pub fn cups_media(&self) -> &'static str {
+        match self {
+            PaperSize::Letter => "Letter",
+            PaperSize::A4 => "A4",
+        }
+    }
+}
+
+impl std::fmt::Display for PaperSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PaperSize::Letter => write!(f, "Lettre (8,5 × 11 po)"),
+            PaperSize::A4 => write!(f, "A4 (210 × 297 mm)"),
+        }
+    }
+}
+
+/// Page margin in millimeters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Margin(pub u32);
+
+impl Default for Margin {
+    fn default() -> Self {
+        Margin(13)
+    }
+}
+
+impl std::fmt::Display for Margin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Marges {} mm", self.0)
+    }
+}
+
+impl Margin {
+    pub fn inches(&self) -> f32 {
+        self.0 as f32 / 25.4
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PageSetup {
+    pub paper: PaperSize,
+    pub margin: Margin,
+    /// Shrinks the report to a single page instead of splitting it.
+    pub fit_to_page: bool,
+}
+
+impl Default for PageSetup {
+    fn default() -> Self {
+        Self {
+            paper: PaperSize::default(),
+            margin: Margin::default(),
+            fit_to_page: true,
+        }
+    }
+}
+
+/// Pages rendered for the print preview.
+#[derive(Debug, Clone)]
+pub struct PrintPreview {
+    pub pages: Vec<Handle>,
+}
+
+impl PrintPreview {
+    pub fn new(audiorox: &AudioRox) -> Result<Self, Box<dyn std::error::Error>> {
+        let pages = render_pages(audiorox, ExportDpi(SCREEN_DPI))?
+            .into_iter()
+            .map(|page| Handle::from_pixels(page.width(), page.height(), page.into_raw()))
+            .collect();
+
+        Ok(Self { pages })
+    }
+}
+
+/// Renders the report, scales it to the printable width of the page and splits it
+/// into pages, each with a header (clinic, patient) and a footer (page numbers).
+pub fn render_pages(
+    audiorox: &AudioRox,
+    dpi: ExportDpi,
+) -> Result<Vec<image::RgbaImage>, Box<dyn std::error::Error>> {
+    let setup = audiorox.page_setup;
+    let lang = audiorox.report_language;
+    let report = export::render_report(audiorox, dpi)?;
+
+    // logical units, as in the on-screen layout
+    let (paper_width, paper_height) = setup.paper.size_inches();
+    let page_width = paper_width * SCREEN_DPI as f32;
+    let page_height = paper_height * SCREEN_DPI as f32;
+    let margin = setup.margin.inches() * SCREEN_DPI as f32;
+
+    let body_width = page_width - 2.0 * margin;
+    let body_height = page_height - 2.0 * margin - HEADER_HEIGHT - FOOTER_HEIGHT;
+
+    // report pixels per logical unit of the page
+    let report_width = report.width() as f32 / dpi.scale_factor() as f32;
+    let report_height = report.height() as f32 / dpi.scale_factor() as f32;
+    let mut scale = body_width / report_width;
+    if setup.fit_to_page {
+        scale = scale.min(body_height / report_height);
+    }
+
+    let slice_height = ((body_height / scale) * dpi.scale_factor() as f32).floor() as u32;
+    let page_count = ((report.height() as f32) / slice_height as f32).ceil().max(1.0) as usize;
+
+    let (clinic, succursale) = get_chosen_succursale(&audiorox.partner);
+    let date = chrono::Local::now().format("%d-%m-%Y");
+
+    let mut pages = Vec::with_capacity(page_count);
+    for page_index in 0..page_count {
+        let y = page_index as u32 * slice_height;
+        let height = slice_height.min(report.height() - y);
+        let slice = image::imageops::crop_imm(&report, 0, y, report.width(), height).to_image();
+
+        let slice_width = report_width * scale;
+        let slice_logical_height = height as f32 / dpi.scale_factor() as f32 * scale;
+
+        let header = row![
+            column![
+                text(&clinic).size(HEADER_TEXT_SIZE),
+                text(&succursale).size(HEADER_TEXT_SIZE * 0.9),
+            ],
+            horizontal_space(Length::Fill),
+            text(format!("{}{}", tr(lang, "Patient : "), audiorox.patient_name)).size(HEADER_TEXT_SIZE),
+        ]
+        .height(Length::Fixed(HEADER_HEIGHT));
+
+        let footer = row![
+            text(format!("{}{}", tr(lang, "Imprimé le "), date)).size(HEADER_TEXT_SIZE),
+            horizontal_space(Length::Fill),
+            text(format!("Page {} / {}", page_index + 1, page_count)).size(HEADER_TEXT_SIZE),
+        ]
+        .height(Length::Fixed(FOOTER_HEIGHT));
+
+        let body = container(
+            image_widget(Handle::from_pixels(slice.width(), slice.height(), slice.into_raw()))
+                .content_fit(ContentFit::Fill)
+                .width(Length::Fixed(slice_width))
+                .height(Length::Fixed(slice_logical_height)),
+        )
+        .width(Length::Fill)
+        .height(Length::Fixed(body_height))
+        .align_x(Horizontal::Center);
+
+        let page = container(column![header, body, vertical_space(Length::Fill), footer])
+            .padding(margin)
+            .width(Length::Fixed(page_width))
+            .height(Length::Fixed(page_height));
+
+        pages.push(export::rasterize(page.into(), page_width, dpi)?);
+    }
+
+    Ok(pages)
+}
+
+/// Writes the pages as a PDF with one full-page image per page. The signature of a
+/// finalized report goes in the document information.
+pub fn write_pdf(
+    pages: &[image::RgbaImage],
+    paper: PaperSize,
+    signature: Option<&str>,
+    filename: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (paper_width, paper_height) = paper.size_inches();
+    let (width_pt, height_pt) = (paper_width * 72.0, paper_height * 72.0);
+
+    let mut pdf: Vec<u8> = b"%PDF-1.4\n".to_vec();
+    let mut offsets = vec![];
+
+    let page_ids: Vec<usize> = (0..pages.len()).map(|i| 3 + 3 * i).collect();
+    let kids = page_ids
+        .iter()
+        .map(|id| format!("{} 0 R", id))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    offsets.push(pdf.len());
+    write!(pdf, "1 0 obj\n<< /Type /Catalog /Pages 2 0 R >>\nendobj\n")?;
+    offsets.push(pdf.len());
+    write!(
+        pdf,
+        "2 0 obj\n<< /Type /Pages /Kids [{}] /Count {} >>\nendobj\n",
+        kids,
+        pages.len()
+    )?;
+
+    for (page, page_id) in pages.iter().zip(page_ids) {
+        let (content_id, image_id) = (page_id + 1, page_id + 2);
+
+        offsets.push(pdf.len());
+        write!(
+            pdf,
+            "{} 0 obj\n<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.2} {:.2}] \
+             /Resources << /XObject << /Im0 {} 0 R >> >> /Contents {} 0 R >>\nendobj\n",
+            page_id, width_pt, height_pt, image_id, content_id
+        )?;
+
+        let content = format!("q {:.2} 0 0 {:.2} 0 0 cm /Im0 Do Q", width_pt, height_pt);
+        offsets.push(pdf.len());
+        write!(
+            pdf,
+            "{} 0 obj\n<< /Length {} >>\nstream\n{}\nendstream\nendobj\n",
+            content_id,
+            content.len() + 1,
+            content
+        )?;
+
+        let rgb = image::DynamicImage::ImageRgba8(page.clone()).into_rgb8();
+        let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(rgb.as_raw())?;
+        let data = encoder.finish()?;
+
+        offsets.push(pdf.len());
+        write!(
+            pdf,
+            "{} 0 obj\n<< /Type /XObject /Subtype /Image /Width {} /Height {} \
+             /ColorSpace /DeviceRGB /BitsPerComponent 8 /Filter /FlateDecode /Length {} >>\nstream\n",
+            image_id,
+            page.width(),
+            page.height(),
+            data.len()
+        )?;
+        pdf.extend_from_slice(&data);
+        write!(pdf, "\nendstream\nendobj\n")?;
+    }
+
+    let mut info = String::new();
+    if let Some(signature) = signature {
+        let info_id = offsets.len() + 1;
+        offsets.push(pdf.len());
+        write!(
+            pdf,
+            "{} 0 obj\n<< /{} {} >>\nendobj\n",
+            info_id,
+            EXPORT_METADATA_KEY,
+            pdf_text(signature)
+        )?;
+        info = format!(" /Info {} 0 R", info_id);
+    }
+
+    let xref = pdf.len();
+    write!(pdf, "xref\n0 {}\n0000000000 65535 f \n", offsets.len() + 1)?;
+    for offset in offsets.iter() {
+        writeln!(pdf, "{:010} 00000 n ", offset)?;
+    }
+    write!(
+        pdf,
+        "trailer\n<< /Size {} /Root 1 0 R{} >>\nstartxref\n{}\n%%EOF\n",
+        offsets.len() + 1,
+        info,
+        xref
+    )?;
+
+    std::fs::write(filename, pdf)?;
+    Ok(())
+}
+
+/// Text string of the document information, in UTF-16 for the accents.
+fn pdf_text(text: &str) -> String {
+    let utf16: String = text.encode_utf16().map(|unit| format!("{:04X}", unit)).collect();
+    format!("<FEFF{}>", utf16)
+}
+
+pub fn export_pdf(audiorox: &AudioRox, filename: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let pages = render_pages(audiorox, PRINT_DPI)?;
+    let signature = audiorox.export_signature();
+    write_pdf(&pages, audiorox.page_setup.paper, signature.as_deref(), filename)
+}
+
+/// Generates the document in the temporary directory and hands it to the print queue.
+pub fn print_report(audiorox: &AudioRox) -> Result<(), Box<dyn std::error::Error>> {
+    let path = std::env::temp_dir().join(export::default_export_name(audiorox, "pdf"));
+    let filename = path.to_str().ok_or("Invalid temporary path")?;
+    export_pdf(audiorox, filename)?;
+
+    let command = std::env::var(PRINT_COMMAND_VAR).unwrap_or(DEFAULT_PRINT_COMMAND.to_string());
+    let output = std::process::Command::new(&command)
+        .arg("-o")
+        .arg(format!("media={}", audiorox.page_setup.paper.cups_media()))
+        .arg(filename)
+        .output()?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "{} failed: {}",
+            command,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Stacks the pages of the print preview.
+pub fn preview_pages(preview: &PrintPreview) -> iced::Element<'_, Message> {
+    let mut pages = column![].spacing(15).align_items(iced::Alignment::Center);
+    for page in preview.pages.iter() {
+        pages = pages.push(container(image_widget(page.clone())).padding(1));
+    }
+
+    pages.into()
+}
@@ -0,0 +1,32 @@
+use std::path::Path;
+use std::time::Duration;
+
+/// Journal holding the last autosave of a report that was not saved yet.
+pub const RECOVERY_FILE: &str = "recovery.json";
+
+pub const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Writes to a temporary file first, so that a crash while writing never leaves a
+/// truncated file behind.
+pub fn write_atomically(filename: &str, contents: &str) -> std::io::Result<()> {
+    let temporary = format!("{}.tmp", filename);
+    std::fs::write(&temporary, contents)?;
+    std::fs::rename(&temporary, filename)
+}
+
+pub fn write_journal(snapshot: &str) -> std::io::Result<()> {
+    write_atomically(RECOVERY_FILE, snapshot)
+}
+
+/// The report left by a session that ended before being saved, if any.
+pub fn read_journal() -> Option<String> {
+    std::fs::read_to_string(RECOVERY_FILE).ok()
+}
+
+pub fn remove_journal() {
+    if Path::new(RECOVERY_FILE).exists() {
+        if let Err(e) = std::fs::remove_file(RECOVERY_FILE) {
+            println!("Failed to remove recovery journal: {}", e);
+        }
+    }
+}
@@ -0,0 +1,287 @@
+use serde::{Deserialize, Serialize};
+
+use crate::plot::{EarSide, Thresholds};
+use crate::recovery::write_atomically;
+use crate::util::AudioRox;
+
+/// Baseline audiograms of the workers, one file per worker.
+pub const BASELINES_DIR: &str = "baselines";
+
+/// Frequencies averaged for the standard threshold shift.
+pub const STS_FREQUENCIES: [u32; 3] = [2000, 3000, 4000];
+/// Change of the average from the baseline that is a standard threshold shift, in dB.
+pub const STS_SHIFT: f32 = 10.0;
+/// Average hearing level from which a standard threshold shift is recordable, in dB HL.
+pub const RECORDABLE_LEVEL: f32 = 25.0;
+
+/// Age corrections at 2, 3 and 4 kHz in dB, from 20 years or less to 60 years or more,
+/// of appendix F of OSHA 29 CFR 1910.95.
+const AGE_CORRECTIONS_MALE: [[u8; 3]; 41] = [
+    [3, 4, 5], [3, 4, 5], [3, 4, 5], [3, 4, 6], [3, 5, 6], [3, 5, 7], [4, 5, 7], [4, 6, 7],
+    [4, 6, 8], [4, 6, 8], [4, 6, 9], [4, 7, 9], [5, 7, 10], [5, 7, 10], [5, 8, 11], [5, 8, 11],
+    [5, 9, 12], [6, 9, 12], [6, 9, 13], [6, 10, 14], [6, 10, 14], [6, 10, 14], [7, 11, 16],
+    [7, 12, 16], [7, 12, 17], [7, 13, 18], [8, 13, 19], [8, 14, 19], [8, 14, 20], [9, 15, 21],
+    [9, 16, 22], [9, 16, 23], [10, 17, 24], [10, 18, 25], [10, 18, 26], [11, 19, 27],
+    [11, 20, 28], [11, 21, 29], [12, 22, 31], [12, 22, 32], [13, 23, 33],
+];
+const AGE_CORRECTIONS_FEMALE: [[u8; 3]; 41] = [
+    [4, 3, 3], [4, 4, 3], [4, 4, 4], [5, 4, 4], [5, 4, 4], [5, 4, 4], [5, 5, 4], [5, 5, 5],
+    [5, 5, 5], [5, 5, 5], [6, 5, 5], [6, 6, 5], [6, 6, 6], [6, 6, 6], [6, 6, 6], [6, 7, 7],
+    [7, 7, 7], [7, 7, 7], [7, 7, 7], [7, 8, 8], [7, 8, 8], [8, 8, 8], [8, 9, 9], [8, 9, 9],
+    [8, 9, 9], [8, 10, 10], [9, 10, 10], [9, 10, 11], [9, 11, 11], [9, 11, 11], [10, 11, 12],
+    [10, 12, 12], [10, 12, 13], [10, 13, 13], [11, 13, 14], [11, 14, 14], [11, 14, 15],
+    [11, 15, 15], [12, 15, 16], [12, 16, 16], [12, 16, 17],
+];
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Sex {
+    #[default]
+    Male,
+    Female,
+}
+
+impl Sex {
+    /// Age correction averaged over `STS_FREQUENCIES`, in dB.
+    fn age_correction(&self, age: u32) -> f32 {
+        let table = match self {
+            Sex::Male => &AGE_CORRECTIONS_MALE,
+            Sex::Female => &AGE_CORRECTIONS_FEMALE,
+        };
+        let row = table[(age.clamp(20, 60) - 20) as usize];
+        row.iter().map(|correction| *correction as f32).sum::<f32>() / row.len() as f32
+    }
+}
+
+/// Audiograms the next screenings of a worker are compared to.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Baseline {
+    pub date: String,
+    /// Age of the worker at the time of the baseline, for the age correction.
+    pub age: String,
+    pub right: Thresholds,
+    pub left: Thresholds,
+}
+
+/// Screening of a worker exposed to noise, shown in a box of the report when enabled.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NoiseScreening {
+    pub enabled: bool,
+    /// Copy of the baseline the report is compared to.
+    pub baseline: Option<Baseline>,
+    /// Number of the file of the worker, which with the date of birth identifies the
+    /// baseline of the worker whatever the spelling of the name.
+    pub file_number: String,
+    pub birth_date: String,
+    pub age: String,
+    pub sex: Sex,
+    /// Subtracts from the shift the part expected from aging since the baseline.
+    pub age_correction: bool,
+}
+
+/// Comparison of an ear to its baseline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThresholdShift {
+    /// Averages over `STS_FREQUENCIES`, in dB HL.
+    pub baseline: f32,
+    pub current: f32,
+    /// Age correction subtracted from the shift, None when not applied.
+    pub age_correction: Option<f32>,
+    pub shift: f32,
+    /// Standard threshold shift.
+    pub sts: bool,
+    /// Standard threshold shift with an average hearing level reaching `RECORDABLE_LEVEL`.
+    pub recordable: bool,
+}
+
+/// Average over `STS_FREQUENCIES`, None unless the threshold of each was obtained.
+pub fn sts_average(thresholds: &Thresholds) -> Option<f32> {
+    let levels: Option<Vec<f32>> = STS_FREQUENCIES
+        .iter()
+        .map(|frequency| thresholds.get(frequency).map(|threshold| threshold.level))
+        .collect();
+    levels.map(|levels| levels.iter().sum::<f32>() / levels.len() as f32)
+}
+
+fn parse_age(age: &str) -> Option<u32> {
+    age.trim().parse().ok()
+}
+
+impl NoiseScreening {
+    /// Compares the air conduction thresholds of an ear to those of the baseline. None
+    /// without a baseline, or while a threshold of the average is missing.
+    pub fn shift(&self, ear: EarSide, current: &Thresholds) -> Option<ThresholdShift> {
+        let baseline = self.baseline.as_ref()?;
+        let baseline = match ear {
+            EarSide::Right => &baseline.right,
+            EarSide::Left => &baseline.left,
+            EarSide::Free => return None,
+        };
+        let (baseline_average, current_average) = (sts_average(baseline)?, sts_average(current)?);
+
+        let age_correction = self
+            .age_correction
+            .then(|| {
+                let baseline_age = parse_age(&self.baseline.as_ref()?.age)?;
+                let age = parse_age(&self.age)?;
+                Some(self.sex.age_correction(age) - self.sex.age_correction(baseline_age))
+            })
+            .flatten();
+
+        let shift = current_average - baseline_average - age_correction.unwrap_or(0.0);
+        let sts = shift >= STS_SHIFT;
+        Some(ThresholdShift {
+            baseline: baseline_average,
+            current: current_average,
+            age_correction,
+            shift,
+            sts,
+            recordable: sts && current_average >= RECORDABLE_LEVEL,
+        })
+    }
+}
+
+impl NoiseScreening {
+    /// File of the baseline of the worker, named after the file number and the date of
+    /// birth. Two workers of the same name do not share a baseline.
+    fn baseline_file(&self) -> Result<String, Box<dyn std::error::Error>> {
+        let key = |entry: &str| -> String {
+            entry
+                .trim()
+                .chars()
+                .map(|c| if c.is_alphanumeric() { c.to_lowercase().next().unwrap_or(c) } else { '_' })
+                .collect()
+        };
+        let (file_number, birth_date) = (key(&self.file_number), key(&self.birth_date));
+        if file_number.is_empty() || birth_date.is_empty() {
+            return Err("The worker has no file number or date of birth".into());
+        }
+        Ok(format!("{}/{}_{}.json", BASELINES_DIR, file_number, birth_date))
+    }
+}
+
+impl AudioRox {
+    /// Makes the current audiograms the baseline of the worker, for this report and
+    /// the next screenings.
+    pub fn save_baseline(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let filename = self.screening.baseline_file()?;
+        let baseline = Baseline {
+            date: chrono::Local::now().format("%d-%m-%Y").to_string(),
+            age: self.screening.age.clone(),
+            right: self.audiogram_right.air.clone(),
+            left: self.audiogram_left.air.clone(),
+        };
+
+        std::fs::create_dir_all(BASELINES_DIR)?;
+        write_atomically(&filename, &serde_json::to_string(&baseline)?)?;
+        self.screening.baseline = Some(baseline);
+        Ok(())
+    }
+
+    /// Copies the stored baseline of the worker into the report.
+    pub fn load_baseline(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let contents = std::fs::read_to_string(self.screening.baseline_file()?)?;
+        self.screening.baseline = Some(serde_json::from_str(&contents)?);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plot::Threshold;
+
+    fn thresholds(levels: [f32; 3]) -> Thresholds {
+        STS_FREQUENCIES
+            .into_iter()
+            .zip(levels)
+            .map(|(frequency, level)| (frequency, Threshold { level, ..Threshold::default() }))
+            .collect()
+    }
+
+    /// The example of appendix F: a man whose baseline was taken at 27, tested again
+    /// at 32, with 0, 0 and 5 dB HL then 10, 10 and 25 dB HL at 2, 3 and 4 kHz in the
+    /// right ear.
+    fn appendix_f(age_correction: bool) -> NoiseScreening {
+        NoiseScreening {
+            baseline: Some(Baseline {
+                age: String::from("27"),
+                right: thresholds([0.0, 0.0, 5.0]),
+                ..Baseline::default()
+            }),
+            age: String::from("32"),
+            sex: Sex::Male,
+            age_correction,
+            ..NoiseScreening::default()
+        }
+    }
+
+    fn assert_close(value: f32, expected: f32) {
+        assert!((value - expected).abs() < 0.01, "{} is not {}", value, expected);
+    }
+
+    #[test]
+    fn age_corrections_of_appendix_f() {
+        // 3 dB at 4 kHz from 27 to 32, as worked out in the appendix
+        assert_eq!(AGE_CORRECTIONS_MALE[32 - 20], [5, 7, 10]);
+        assert_eq!(AGE_CORRECTIONS_MALE[27 - 20], [4, 6, 7]);
+        assert_close(Sex::Male.age_correction(32) - Sex::Male.age_correction(27), 5.0 / 3.0);
+        // the tables stop at 20 and 60 years
+        assert_eq!(Sex::Female.age_correction(16), Sex::Female.age_correction(20));
+        assert_eq!(Sex::Female.age_correction(75), Sex::Female.age_correction(60));
+    }
+
+    #[test]
+    fn shift_of_appendix_f() {
+        let current = thresholds([10.0, 10.0, 25.0]);
+
+        let shift = appendix_f(false).shift(EarSide::Right, &current).unwrap();
+        assert_close(shift.baseline, 5.0 / 3.0);
+        assert_close(shift.current, 15.0);
+        assert_eq!(shift.age_correction, None);
+        assert_close(shift.shift, 40.0 / 3.0);
+        assert!(shift.sts);
+        assert!(!shift.recordable);
+
+        let shift = appendix_f(true).shift(EarSide::Right, &current).unwrap();
+        assert_close(shift.age_correction.unwrap(), 5.0 / 3.0);
+        assert_close(shift.shift, 35.0 / 3.0);
+        assert!(shift.sts);
+        assert!(!shift.recordable, "15 dB HL is below the recordable level");
+    }
+
+    #[test]
+    fn recordable_shift() {
+        let shift = appendix_f(true).shift(EarSide::Right, &thresholds([25.0, 30.0, 40.0])).unwrap();
+        assert!(shift.sts);
+        assert!(shift.recordable);
+    }
+
+    #[test]
+    fn no_shift_without_the_thresholds() {
+        let screening = appendix_f(true);
+        let mut current = thresholds([10.0, 10.0, 25.0]);
+        assert_eq!(screening.shift(EarSide::Left, &current), None);
+        assert_eq!(screening.shift(EarSide::Free, &current), None);
+        current.remove(&3000);
+        assert_eq!(screening.shift(EarSide::Right, &current), None);
+        assert_eq!(NoiseScreening::default().shift(EarSide::Right, &thresholds([0.0; 3])), None);
+    }
+
+    #[test]
+    fn baseline_of_the_worker() {
+        let screening = |file_number: &str, birth_date: &str| NoiseScreening {
+            file_number: file_number.to_string(),
+            birth_date: birth_date.to_string(),
+            ..NoiseScreening::default()
+        };
+        assert_eq!(screening(" A-1024 ", "1990-04-12").baseline_file().unwrap(), "baselines/a_1024_1990_04_12.json");
+        assert_ne!(
+            screening("1024", "1990-04-12").baseline_file().unwrap(),
+            screening("1024", "1985-11-03").baseline_file().unwrap()
+        );
+        assert!(screening("", "1990-04-12").baseline_file().is_err());
+        assert!(screening("1024", " ").baseline_file().is_err());
+    }
+}
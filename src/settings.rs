@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+
+use crate::clinician::ClinicianProfile;
+use crate::delivery::DeliverySettings;
+use crate::handicap::Bareme;
+use crate::locale::Language;
+use crate::partners::TemplateSettings;
+use crate::recovery::write_atomically;
+use crate::signing::TrustedKey;
+use crate::track_theme::ThemeVariant;
+
+/// Preferences of the user, kept from one session to the next whatever the report.
+pub const SETTINGS_FILE: &str = "settings.json";
+
+/// Zoom levels of the page, on top of its scaling to the width of the window.
+pub const ZOOM_LEVELS: [f64; 11] = [0.5, 0.67, 0.75, 0.8, 0.9, 1.0, 1.1, 1.25, 1.5, 1.75, 2.0];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UserSettings {
+    pub theme: ThemeVariant,
+    /// Language of the interface, whatever the language of the report.
+    pub ui_language: Language,
+    pub zoom: f64,
+    /// Audiologists of the team, to choose the examiner of a report from.
+    pub clinicians: Vec<ClinicianProfile>,
+    /// Mail server the copies of the reports are sent through.
+    pub delivery: DeliverySettings,
+    /// Barème of the percentages of hearing loss for compensation claims.
+    pub bareme: Bareme,
+    /// Layouts of the reports, and the one of each partner.
+    pub templates: TemplateSettings,
+    /// Keys the signatures of the finalized reports are verified with.
+    pub trusted_keys: Vec<TrustedKey>,
+}
+
+impl Default for UserSettings {
+    fn default() -> Self {
+        Self {
+            theme: ThemeVariant::default(),
+            ui_language: Language::default(),
+            zoom: 1.0,
+            clinicians: vec![ClinicianProfile::default()],
+            delivery: DeliverySettings::default(),
+            bareme: Bareme::default(),
+            templates: TemplateSettings::default(),
+            trusted_keys: vec![],
+        }
+    }
+}
+
+impl UserSettings {
+    pub fn zoom_in(&mut self) {
+        if let Some(zoom) = ZOOM_LEVELS.into_iter().find(|zoom| *zoom > self.zoom) {
+            self.zoom = zoom;
+        }
+    }
+
+    pub fn zoom_out(&mut self) {
+        if let Some(zoom) = ZOOM_LEVELS.into_iter().rev().find(|zoom| *zoom < self.zoom) {
+            self.zoom = zoom;
+        }
+    }
+
+    /// The saved settings, or the defaults on the first launch.
+    pub fn load() -> Self {
+        let Ok(contents) = std::fs::read_to_string(SETTINGS_FILE) else {
+            return Self::default();
+        };
+        serde_json::from_str(&contents).unwrap_or_else(|e| {
+            println!("Failed to read settings: {}", e);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        write_atomically(SETTINGS_FILE, &serde_json::to_string(self).unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ui_language_is_saved() {
+        let settings = UserSettings { ui_language: Language::English, ..Default::default() };
+        let saved = serde_json::to_string(&settings).unwrap();
+        let loaded: UserSettings = serde_json::from_str(&saved).unwrap();
+        assert_eq!(loaded.ui_language, Language::English);
+    }
+}
@@ -0,0 +1,310 @@
+use std::fmt;
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use rand_core::OsRng;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::clinician::ClinicianProfile;
+use crate::recovery::write_atomically;
+use crate::util::AudioRox;
+
+/// Local signing keys of the clinicians, one file per profile.
+pub const KEYS_DIR: &str = "keys";
+/// Copies of the finalized reports, kept when they are amended.
+pub const FINALIZED_DIR: &str = "finalized";
+/// Key of the signature in the metadata of the exported documents.
+pub const EXPORT_METADATA_KEY: &str = "AudioRoxSignature";
+
+/// Signature of a finalized report. The hash and the signature cover the report as
+/// saved, including the other fields of the signature.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReportSignature {
+    pub revision: u32,
+    /// Hash of the revision amended by this one.
+    pub amends: Option<String>,
+    pub signed_by: String,
+    pub signed_at: String,
+    pub public_key: String,
+    /// SHA-256 of the signed content, in hexadecimal.
+    pub hash: String,
+    /// Ed25519 signature of the signed content, in hexadecimal.
+    pub signature: String,
+}
+
+/// Finalized revision that a draft amends, until the draft is finalized in turn.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RevisionLink {
+    pub revision: u32,
+    pub hash: String,
+}
+
+/// Public key whose signatures are trusted. The key of a clinician is pinned in the user
+/// settings when they finalize their first report on this computer; the keys of the
+/// colleagues are added to the settings by hand.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrustedKey {
+    /// Name and licence, as in the signatures.
+    pub signed_by: String,
+    pub public_key: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerifyError {
+    Unsigned,
+    Malformed,
+    Modified,
+    InvalidSignature,
+    /// The signature is valid, but made with a key that is not trusted for the signer.
+    UntrustedKey,
+}
+
+impl VerifyError {
+    /// French description, translated with `tr`.
+    pub fn message(&self) -> &'static str {
+        match self {
+            VerifyError::Unsigned => "Le rapport n'est pas finalisé.",
+            VerifyError::Malformed => "La signature du rapport est illisible.",
+            VerifyError::Modified => "Le rapport a été modifié après sa finalisation.",
+            VerifyError::InvalidSignature => "La signature ne correspond pas au rapport.",
+            VerifyError::UntrustedKey => "La clé de signature n'est pas reconnue pour ce clinicien.",
+        }
+    }
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+/// Content covered by the signature: the report as saved, keys sorted, without the
+/// hash and the signature themselves.
+fn signed_content(mut report: Value) -> Option<String> {
+    let signature = report.get_mut("signature")?.as_object_mut()?;
+    signature.remove("hash");
+    signature.remove("signature");
+    serde_json::to_string(&report).ok()
+}
+
+fn key_file(profile: &ClinicianProfile) -> String {
+    let name: String = format!("{} {}", profile.name, profile.licence)
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+    format!("{}/{}.key", KEYS_DIR, name)
+}
+
+/// The key of the clinician, created on their first finalized report.
+fn signing_key(profile: &ClinicianProfile) -> Result<SigningKey, Box<dyn std::error::Error>> {
+    let filename = key_file(profile);
+    if let Ok(contents) = std::fs::read_to_string(&filename) {
+        let bytes: [u8; 32] = hex::decode(contents.trim())?
+            .try_into()
+            .map_err(|_| format!("Invalid signing key in {}", filename))?;
+        return Ok(SigningKey::from_bytes(&bytes));
+    }
+
+    let key = SigningKey::generate(&mut OsRng);
+    std::fs::create_dir_all(KEYS_DIR)?;
+    write_atomically(&filename, &hex::encode(key.to_bytes()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&filename, std::fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(key)
+}
+
+/// Checks a report as saved against its signature, and the key of the signature against
+/// the trusted keys: the key embedded in the report proves nothing by itself.
+pub fn verify_value(report: Value, trusted: &[TrustedKey]) -> Result<ReportSignature, VerifyError> {
+    let signature: Option<ReportSignature> = match report.get("signature") {
+        Some(value) => serde_json::from_value(value.clone()).map_err(|_| VerifyError::Malformed)?,
+        None => None,
+    };
+    let signature = signature.ok_or(VerifyError::Unsigned)?;
+    let content = signed_content(report).ok_or(VerifyError::Malformed)?;
+
+    if hex::encode(Sha256::digest(content.as_bytes())) != signature.hash {
+        return Err(VerifyError::Modified);
+    }
+
+    let public_key: [u8; 32] = hex::decode(&signature.public_key)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(VerifyError::Malformed)?;
+    let bytes: [u8; 64] = hex::decode(&signature.signature)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(VerifyError::Malformed)?;
+    let public_key = VerifyingKey::from_bytes(&public_key).map_err(|_| VerifyError::Malformed)?;
+
+    public_key
+        .verify(content.as_bytes(), &Signature::from_bytes(&bytes))
+        .map_err(|_| VerifyError::InvalidSignature)?;
+
+    let is_trusted = trusted
+        .iter()
+        .any(|key| key.signed_by == signature.signed_by && key.public_key == signature.public_key);
+    if !is_trusted {
+        return Err(VerifyError::UntrustedKey);
+    }
+    Ok(signature)
+}
+
+/// Verifies a saved report file from the command line, for `--verify <file>`.
+pub fn verify_file(filename: &str, trusted: &[TrustedKey]) -> Result<ReportSignature, Box<dyn std::error::Error>> {
+    let report: Value = serde_json::from_str(&std::fs::read_to_string(filename)?)?;
+    Ok(verify_value(report, trusted)?)
+}
+
+impl AudioRox {
+    pub fn is_finalized(&self) -> bool {
+        self.signature.is_some()
+    }
+
+    /// Signs the report with the key of the examiner, which locks it, and keeps a copy
+    /// of the signed revision. The key is pinned in the settings the first time, and
+    /// must match the pinned one afterwards.
+    pub fn finalize(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let key = signing_key(&self.examiner)?;
+        let signed_by = format!("{} {}", self.examiner.name, self.examiner.licence).trim().to_string();
+        let public_key = hex::encode(key.verifying_key().to_bytes());
+
+        match self.settings.trusted_keys.iter().find(|key| key.signed_by == signed_by) {
+            Some(pinned) if pinned.public_key != public_key => {
+                return Err(format!("The signing key of {} is not the one pinned in the settings", signed_by).into());
+            }
+            Some(_) => (),
+            None => {
+                self.settings.trusted_keys.push(TrustedKey {
+                    signed_by: signed_by.clone(),
+                    public_key: public_key.clone(),
+                });
+                self.settings.save()?;
+            }
+        }
+
+        let signature = ReportSignature {
+            revision: self.amends.as_ref().map_or(1, |link| link.revision + 1),
+            amends: self.amends.as_ref().map(|link| link.hash.clone()),
+            signed_by,
+            signed_at: chrono::Local::now().to_rfc3339(),
+            public_key,
+            hash: String::new(),
+            signature: String::new(),
+        };
+
+        // the report stays a draft, with its link to the amended revision, unless the
+        // signed copy could be written
+        let (previous, amends) = (self.signature.take(), self.amends.take());
+        let signed = self.sign(&key, signature);
+        if signed.is_err() {
+            self.signature = previous;
+            self.amends = amends;
+        }
+        signed
+    }
+
+    /// Hashes and signs the report with the signature given, and writes the signed copy.
+    fn sign(&mut self, key: &SigningKey, mut signature: ReportSignature) -> Result<(), Box<dyn std::error::Error>> {
+        self.signature = Some(signature.clone());
+        let content = signed_content(self.saved_value()?).ok_or("Invalid report")?;
+        signature.hash = hex::encode(Sha256::digest(content.as_bytes()));
+        signature.signature = hex::encode(key.sign(content.as_bytes()).to_bytes());
+        let hash = signature.hash.clone();
+        self.signature = Some(signature);
+
+        std::fs::create_dir_all(FINALIZED_DIR)?;
+        write_atomically(&format!("{}/{}.json", FINALIZED_DIR, &hash[..16]), &self.snapshot())?;
+        Ok(())
+    }
+
+    /// Unlocks the report as a draft of the next revision.
+    pub fn amend(&mut self) {
+        if let Some(signature) = self.signature.take() {
+            self.amends = Some(RevisionLink {
+                revision: signature.revision,
+                hash: signature.hash,
+            });
+        }
+    }
+
+    pub fn verify(&self) -> Result<ReportSignature, VerifyError> {
+        let report = self.saved_value().map_err(|_| VerifyError::Malformed)?;
+        verify_value(report, &self.settings.trusted_keys)
+    }
+
+    /// The report parsed back from its saved form, so that the numbers are written
+    /// the same way whether the report comes from memory or from a file.
+    fn saved_value(&self) -> serde_json::Result<Value> {
+        serde_json::from_str(&self.snapshot())
+    }
+
+    /// The signature as embedded in the metadata of the exported documents.
+    pub fn export_signature(&self) -> Option<String> {
+        self.signature.as_ref().and_then(|signature| serde_json::to_string(signature).ok())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A report signed with `key` the way `finalize` does, without the key files.
+    fn signed_report(key: &SigningKey) -> Value {
+        let mut report = AudioRox {
+            patient_name: String::from("Tremblay"),
+            signature: Some(ReportSignature {
+                revision: 1,
+                amends: None,
+                signed_by: String::from("Roxanne Bolduc OOAQ #4182"),
+                signed_at: String::from("2024-05-01T10:00:00-04:00"),
+                public_key: hex::encode(key.verifying_key().to_bytes()),
+                hash: String::new(),
+                signature: String::new(),
+            }),
+            ..AudioRox::default()
+        };
+        let content = signed_content(report.saved_value().unwrap()).unwrap();
+        if let Some(signature) = &mut report.signature {
+            signature.hash = hex::encode(Sha256::digest(content.as_bytes()));
+            signature.signature = hex::encode(key.sign(content.as_bytes()).to_bytes());
+        }
+        report.saved_value().unwrap()
+    }
+
+    fn trusted(key: &SigningKey) -> TrustedKey {
+        TrustedKey {
+            signed_by: String::from("Roxanne Bolduc OOAQ #4182"),
+            public_key: hex::encode(key.verifying_key().to_bytes()),
+        }
+    }
+
+    #[test]
+    fn signature_of_a_trusted_key() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let signature = verify_value(signed_report(&key), &[trusted(&key)]).unwrap();
+        assert_eq!(signature.revision, 1);
+    }
+
+    #[test]
+    fn valid_signature_of_an_unknown_key() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let other = SigningKey::from_bytes(&[8; 32]);
+        assert_eq!(verify_value(signed_report(&key), &[]), Err(VerifyError::UntrustedKey));
+        assert_eq!(verify_value(signed_report(&key), &[trusted(&other)]), Err(VerifyError::UntrustedKey));
+    }
+
+    #[test]
+    fn report_changed_after_signing() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let mut report = signed_report(&key);
+        report["patient_name"] = Value::from("Gagnon");
+        assert_eq!(verify_value(report, &[trusted(&key)]), Err(VerifyError::Modified));
+    }
+}
@@ -1,13 +1,16 @@
 use crate::plot::EarSide;
 
 use super::config::{
-    GRAY, RADIO_SIZE, RADIO_SPACING, RADIO_TEXT_SIZE,
-    TABLE_BORDER_COLOR, TABLE_ENTRY_SIZE, TABLE_ENTRY_TITLE_SIZE, 
-  TABLE_TITLE_SIZE, TABLE_TITLE_TEXT_COLOR, TEXT_INPUT_HEIGHT,
+    RADIO_SIZE, RADIO_SPACING, RADIO_TEXT_SIZE,
+    TABLE_ENTRY_SIZE, TABLE_ENTRY_TITLE_SIZE, 
+  TABLE_TITLE_SIZE, TEXT_INPUT_HEIGHT,
     TONAL_TABLE_COL_WIDTH, TYMPA_TABLE_COL_WIDTH, VOCAL_TABLE_CONTENT_HEIGHT,
 };
 
 use super::{AudioRox, IdLang, Message, Stap, Tympa};
+use crate::field::{self, field_input, FieldPath, Section};
+use crate::locale::{tr, Language};
+use crate::track_theme::TrackTheme;
 
 use iced::alignment::{Horizontal, Vertical};
 // use iced_native::widget::Container;
@@ -16,7 +19,7 @@ use iced::theme::{self, Theme};
 
 
 use iced::widget::{
-    column, container, container::Appearance, horizontal_space, radio, row, text, text_input,
+    column, container, container::Appearance, horizontal_space, radio, row, text,
     vertical_space, Column, Row,
 };
 
@@ -61,13 +64,13 @@ pub struct TableContainerCustomStyle;
 impl container::StyleSheet for TableContainerCustomStyle {
     type Style = Theme;
 
-    fn appearance(&self, _style: &Self::Style) -> Appearance {
+    fn appearance(&self, style: &Self::Style) -> Appearance {
         container::Appearance {
             text_color: None,
             background: None,
             border_radius: 0.0.into(),
             border_width: 1.0,
-            border_color: TABLE_BORDER_COLOR,
+            border_color: TrackTheme::of(style).table_border,
         }
     }
 }
@@ -77,13 +80,13 @@ pub struct ResultContainerCustomStyle;
 impl container::StyleSheet for ResultContainerCustomStyle {
     type Style = Theme;
 
-    fn appearance(&self, _style: &Self::Style) -> Appearance {
+    fn appearance(&self, style: &Self::Style) -> Appearance {
         container::Appearance {
             text_color: None,
             background: None,
             border_radius: 2.0.into(),
             border_width: 1.2,
-            border_color: TABLE_BORDER_COLOR,
+            border_color: TrackTheme::of(style).table_border,
         }
     }
 }
@@ -93,126 +96,67 @@ pub struct TableTitleCustomStyle;
 impl container::StyleSheet for TableTitleCustomStyle {
     type Style = Theme;
 
-    fn appearance(&self, _style: &Self::Style) -> Appearance {
+    fn appearance(&self, style: &Self::Style) -> Appearance {
+        let track = TrackTheme::of(style);
         container::Appearance {
-            text_color: None, //Some(Color::from_rgb(0.05, 0.05, 0.02)),
-            background: Some(GRAY.into()),
+            text_color: Some(track.table_title_text),
+            background: Some(track.section_background.into()),
             // background: None,
             border_radius: 0.0.into(),
             border_width: 1.0,
-            border_color: TABLE_BORDER_COLOR,
+            border_color: track.table_border,
         }
     }
 }
 
-pub fn get_message_fn(s: &str, side: EarSide) -> impl Fn(String) -> Message {
-    match (s, side) {
-        ("MSP", EarSide::Right) => Message::MSPRightChanged,
-        ("MSP4", EarSide::Right) => Message::MSP4RightChanged,
-        ("FLCH", EarSide::Right) => Message::FLCHRightChanged,
-
-        ("SDP", EarSide::Right) => Message::SDPRightChanged,
-        ("SRP", EarSide::Right) => Message::SRPRightChanged,
-
-        ("N confort", EarSide::Right) => Message::MiscRightChanged,
-
-        ("MSP", EarSide::Left) => Message::MSPLeftChanged,
-        ("MSP4", EarSide::Left) => Message::MSP4LeftChanged,
-        ("FLCH", EarSide::Left) => Message::FLCHLeftChanged,
-
-        ("SRP", EarSide::Left) => Message::SRPLeftChanged,
-        ("SDP", EarSide::Left) => Message::SDPLeftChanged,
-        ("N confort", EarSide::Left) => Message::MiscLeftChanged,
-
-        ("SDP", EarSide::Free) => Message::SDPFreeChanged,
-        ("SRP", EarSide::Free) => Message::SRPFreeChanged,
-        ("N confort", EarSide::Free) => Message::MiscBinChanged,
-
-        ("IdParoleRes1", EarSide::Left) => Message::IdLanRes1LeftChanged,
-        ("IdParoleRes2", EarSide::Left) => Message::IdLanRes2LeftChanged,
-        ("IdParoleLev1", EarSide::Left) => Message::IdLanLev1LeftChanged,
-        ("IdParoleLev2", EarSide::Left) => Message::IdLanLev2LeftChanged,
-        ("IdParoleList1", EarSide::Left) => Message::IdLanList1LeftChanged,
-        ("IdParoleList2", EarSide::Left) => Message::IdLanList2LeftChanged,
-
-        ("IdParoleRes1", EarSide::Right) => Message::IdLanRes1RightChanged,
-        ("IdParoleRes2", EarSide::Right) => Message::IdLanRes2RightChanged,
-        ("IdParoleLev1", EarSide::Right) => Message::IdLanLev1RightChanged,
-        ("IdParoleLev2", EarSide::Right) => Message::IdLanLev2RightChanged,
-        ("IdParoleList1", EarSide::Right) => Message::IdLanList1RightChanged,
-        ("IdParoleList2", EarSide::Right) => Message::IdLanList2RightChanged,
-
-        ("IdParoleRes1", EarSide::Free) => Message::IdLanRes1BinChanged,
-        ("IdParoleRes2", EarSide::Free) => Message::IdLanRes2BinChanged,
-        ("IdParoleLev1", EarSide::Free) => Message::IdLanLev1BinChanged,
-        ("IdParoleLev2", EarSide::Free) => Message::IdLanLev2BinChanged,
-        ("IdParoleList1", EarSide::Free) => Message::IdLanList1BinChanged,
-        ("IdParoleList2", EarSide::Free) => Message::IdLanList2BinChanged,
-
-        ("TympaVolume", EarSide::Left) => Message::TympaVolumeLeftChanged,
-        ("TympaVolume", EarSide::Right) => Message::TympaVolumeRightChanged,
-
-        ("TympaPressure", EarSide::Left) => Message::TympaPressureLeftChanged,
-        ("TympaPressure", EarSide::Right) => Message::TympaPressureRightChanged,
-
-        ("TympaCompliance", EarSide::Left) => Message::TympaComplianceLeftChanged,
-        ("TympaCompliance", EarSide::Right) => Message::TympaComplianceRightChanged,
-
-        ("StapIpsi500", EarSide::Left) => Message::StapIpsi500LeftChanged,
-        ("StapIpsi1000", EarSide::Left) => Message::StapIpsi1000LeftChanged,
-        ("StapIpsi2000", EarSide::Left) => Message::StapIpsi2000LeftChanged,
-        ("StapIpsi4000", EarSide::Left) => Message::StapIpsi4000LeftChanged,
-
-        ("StapIpsi500", EarSide::Right) => Message::StapIpsi500RightChanged,
-        ("StapIpsi1000", EarSide::Right) => Message::StapIpsi1000RightChanged,
-        ("StapIpsi2000", EarSide::Right) => Message::StapIpsi2000RightChanged,
-        ("StapIpsi4000", EarSide::Right) => Message::StapIpsi4000RightChanged,
-
-        ("StapControl500", EarSide::Left) => Message::StapControl500LeftChanged,
-        ("StapControl1000", EarSide::Left) => Message::StapControl1000LeftChanged,
-        ("StapControl2000", EarSide::Left) => Message::StapControl2000LeftChanged,
-        ("StapControl4000", EarSide::Left) => Message::StapControl4000LeftChanged,
-
-        ("StapControl500", EarSide::Right) => Message::StapControl500RightChanged,
-        ("StapControl1000", EarSide::Right) => Message::StapControl1000RightChanged,
-        ("StapControl2000", EarSide::Right) => Message::StapControl2000RightChanged,
-        ("StapControl4000", EarSide::Right) => Message::StapControl4000RightChanged,
-
-        _ => panic!("Not a valid Table message: {}", s),
-    }
-}
-
 pub fn make_tonal_tables(audio_rox: &AudioRox) -> (Element<Message>, Element<Message>) {
-    let tonal_table_columns_left = [
-        ("MSP", &audio_rox.tonal_table_left.msp),
-        ("MSP4", &audio_rox.tonal_table_left.msp4),
-        ("FLCH", &audio_rox.tonal_table_left.fletcher),
+    let lang = audio_rox.report_language;
+
+    let tonal_table_columns = [
+        ("MSP", field::Column::Msp),
+        ("MSP4", field::Column::Msp4),
+        ("FLCH", field::Column::Fletcher),
     ];
 
     let tonal_table_left = make_one_tonal_table(
+        audio_rox,
         EarSide::Left,
         // "Moyennes tonales oreille gauche (dB HL)",
-        "MOYENNES DES SONS PURS - dB HL",
-        &tonal_table_columns_left,
+        tr(lang, "MOYENNES DES SONS PURS - dB HL"),
+        &tonal_table_columns,
+        lang,
     );
 
-    let tonal_table_columns_right = [
-        ("MSP", &audio_rox.tonal_table_right.msp),
-        ("MSP4", &audio_rox.tonal_table_right.msp4),
-        ("FLCH", &audio_rox.tonal_table_right.fletcher),
-        // ("N Confor\nparole", &audio_rox),
-    ];
-
     let tonal_table_right = make_one_tonal_table(
+        audio_rox,
         EarSide::Right,
         // "Moyennes tonales oreille droite (dB HL)",
-        "MOYENNES DES SONS PURS - dB HL",
-        &tonal_table_columns_right,
+        tr(lang, "MOYENNES DES SONS PURS - dB HL"),
+        &tonal_table_columns,
+        lang,
     );
 
     (tonal_table_right, tonal_table_left)
 }
 
+/// Values in dB computed from the audiograms, one column per frequency.
+pub fn make_frequency_table(table_name: &str, values: Vec<(u32, f32)>) -> Element<'static, Message> {
+    let mut table = Row::new().spacing(12).align_items(Alignment::Center);
+
+    for (frequency, value) in values {
+        table = table.push(
+            column![
+                text(frequency).size(TABLE_ENTRY_TITLE_SIZE),
+                text(format!("{:.0}", value)).size(TABLE_ENTRY_SIZE),
+            ]
+            .spacing(4)
+            .align_items(Alignment::Center),
+        );
+    }
+
+    put_in_table(table_name, table.into())
+}
+
 pub fn seuils_vocaux_tables(
     audio_rox: &AudioRox,
 ) -> (
@@ -221,38 +165,37 @@ pub fn seuils_vocaux_tables(
     Element<Message>,
     Element<Message>,
 ) {
-    // vocal tables
-    let vocal_input_table_columns_left = [
-        ("SRP", &audio_rox.vocal_table_left.srp),
-        ("SDP", &audio_rox.vocal_table_left.sdp),
-        ("N confort", &audio_rox.vocal_table_left.misc),
-        // ("Liste", &audio_rox.vocal_table_left.list),
-    ];
+    let lang = audio_rox.report_language;
 
-    let vocal_input_table_columns_right = [
-        ("SRP", &audio_rox.vocal_table_right.srp),
-        ("SDP", &audio_rox.vocal_table_right.sdp),
-        ("N confort", &audio_rox.vocal_table_right.misc),
+    // vocal tables
+    let vocal_input_table_columns = [
+        ("SRP", field::Column::Srp),
+        ("SDP", field::Column::Sdp),
+        ("N confort", field::Column::Comfort),
     ];
 
     let tonal_table_left = make_one_vocal_table(
+        audio_rox,
         EarSide::Left,
         // "Moyennes tonales oreille gauche (dB HL)",
-        "SEUILS VOCAUX - dB HL",
-        &vocal_input_table_columns_left,
+        tr(lang, "SEUILS VOCAUX - dB HL"),
+        &vocal_input_table_columns,
+        lang,
     );
 
     let tonal_table_right = make_one_vocal_table(
+        audio_rox,
         EarSide::Right,
         // "Moyennes tonales oreille droite (dB HL)",
-        "SEUILS VOCAUX - dB HL",
-        &vocal_input_table_columns_right,
+        tr(lang, "SEUILS VOCAUX - dB HL"),
+        &vocal_input_table_columns,
+        lang,
     );
 
     let vspacing = 3;
     // a column of two checkboxes for "FR" and "ANG"
     let vocal_lang = put_in_table(
-        "LANGUE",
+        tr(lang, "LANGUE"),
         column![
             // container(text("LANGUE ").size(RADIO_TITLE_SIZE)),
             // add_table_name("LANGUE"),
@@ -268,7 +211,7 @@ pub fn seuils_vocaux_tables(
             .text_size(RADIO_TEXT_SIZE),
             // horizontal_space(2.),
             radio(
-                "Ang.",
+                tr(lang, "Ang."),
                 Lang::English,
                 Some(audio_rox.vocal_lang),
                 Message::VocalLangChanged
@@ -282,12 +225,12 @@ pub fn seuils_vocaux_tables(
     );
 
     let voice = put_in_table(
-        "VOIX",
+        tr(lang, "VOIX"),
         column![
             // text("VOIX: ").size(RADIO_TITLE_SIZE),
             // horizontal_space(5.),
             radio(
-                "Nue",
+                tr(lang, "Nue"),
                 IsRecorded::No,
                 Some(audio_rox.is_recorded),
                 Message::IsRecordedChanged
@@ -298,7 +241,7 @@ pub fn seuils_vocaux_tables(
             // horizontal_space(2.),
             // vertical_space(2.),
             radio(
-                "Enregistrée",
+                tr(lang, "Enregistrée"),
                 IsRecorded::Yes,
                 Some(audio_rox.is_recorded),
                 Message::IsRecordedChanged
@@ -322,50 +265,57 @@ pub fn seuils_vocaux_tables(
 pub fn identification_language(
     audio_rox: &AudioRox,
 ) -> (Element<Message>, Element<Message>, Element<Message>) {
+    let lang = audio_rox.report_language;
+
     let id_table_left = make_one_id_language_table(
         EarSide::Left,
-        "IDENTIFICATION PAROLE",
+        tr(lang, "IDENTIFICATION PAROLE"),
         &audio_rox.id_lang_left,
         false,
+        lang,
     );
 
     let id_table_right = make_one_id_language_table(
         EarSide::Right,
-        "IDENTIFICATION PAROLE",
+        tr(lang, "IDENTIFICATION PAROLE"),
         &audio_rox.id_lang_right,
         false,
+        lang,
     );
 
     // let id_table_bin = make_bin_id_language_table(
     let id_table_bin = make_one_id_language_table(
         EarSide::Free,
-        "IDENTIFICATION PAROLE - BINAURAL",
+        tr(lang, "IDENTIFICATION PAROLE - BINAURAL"),
         &audio_rox.id_lang_bin,
         true,
+        lang,
     );
 
     (id_table_right, id_table_left, id_table_bin)
 }
 
 pub fn make_one_tonal_table(
+    audio_rox: &AudioRox,
     ear_side: EarSide,
     table_name: &str,
-    table_columns: &[(&str, &String)],
+    table_columns: &[(&'static str, field::Column)],
+    lang: Language,
 ) -> Element<'static, Message> {
     let mut table = Row::new();
 
-    for (s, variable) in table_columns.iter() {
-        let message_fn = get_message_fn(s, ear_side);
+    for (s, column) in table_columns.iter() {
+        let path = FieldPath::new(ear_side, Section::TonalAverage, *column);
+        let variable = audio_rox.field(path).map(String::as_str).unwrap_or_default();
 
-        let t_in = text_input("", &variable)
-            .on_input(message_fn)
+        let t_in = field_input("", variable, path)
             .size(TABLE_ENTRY_SIZE)
             .width(Length::Fixed(TONAL_TABLE_COL_WIDTH));
 
 
         let entry = row![
             container(
-                text(*s)
+                text(tr(lang, s))
                     // .style(TABLE_TEXT_COLOR)
                     .size(TABLE_ENTRY_TITLE_SIZE)
             ),
@@ -391,7 +341,6 @@ pub fn make_one_tonal_table(
                 container(
                     text(table_name)
                         .size(TABLE_TITLE_SIZE)
-                        .style(TABLE_TITLE_TEXT_COLOR)
                         .horizontal_alignment(Horizontal::Center)
                 )
                 .padding(3)
@@ -415,26 +364,28 @@ pub fn make_one_tonal_table(
 }
 
 pub fn make_one_vocal_table(
+    audio_rox: &AudioRox,
     ear_side: EarSide,
     table_name: &str,
-    table_columns: &[(&str, &String)],
+    table_columns: &[(&'static str, field::Column)],
+    lang: Language,
 ) -> Element<'static, Message> {
     let mut table = Row::new();
     table = table.push(horizontal_space(Length::Fixed(2.0)));
 
-    for (s, variable) in table_columns.iter() {
-        let message_fn = get_message_fn(s, ear_side);
+    for (s, column) in table_columns.iter() {
+        let path = FieldPath::new(ear_side, Section::SpeechThreshold, *column);
+        let variable = audio_rox.field(path).map(String::as_str).unwrap_or_default();
 
         let entry = row![
             container(
-                text(*s)
+                text(tr(lang, s))
                     // .style(TABLE_TEXT_COLOR)
                     .size(TABLE_ENTRY_TITLE_SIZE)
                     .horizontal_alignment(Horizontal::Right)
             ),
             horizontal_space(3.0),
-            text_input("", &variable)
-                .on_input(message_fn)
+            field_input("", variable, path)
                 .size(TABLE_ENTRY_SIZE)
                 .width(Length::Fixed(TONAL_TABLE_COL_WIDTH)),
             horizontal_space(2.0),
@@ -483,7 +434,6 @@ pub fn add_table_name(table_name: &str) -> Element<'static, Message> {
         container(
             text(table_name)
                 .size(TABLE_TITLE_SIZE)
-                .style(TABLE_TITLE_TEXT_COLOR)
                 .horizontal_alignment(Horizontal::Center),
         )
         .padding(3),
@@ -495,13 +445,13 @@ pub fn add_table_name(table_name: &str) -> Element<'static, Message> {
     table_name.into()
 }
 
-pub fn make_level_list_result(row_title_len: f32) -> Element<'static, Message> {
+pub fn make_level_list_result(row_title_len: f32, lang: Language) -> Element<'static, Message> {
     let row0_height = 29. * 1.3;
     let row0 = row![
         container(
             column![
                 text("dB HL").size(TABLE_ENTRY_TITLE_SIZE * 0.85),
-                text("Niveau").size(TABLE_ENTRY_TITLE_SIZE)
+                text(tr(lang, "Niveau")).size(TABLE_ENTRY_TITLE_SIZE)
             ]
             .align_items(Alignment::Center) 
         )
@@ -513,7 +463,7 @@ pub fn make_level_list_result(row_title_len: f32) -> Element<'static, Message> {
         container(
             column![
                 text(" ").size(TABLE_ENTRY_TITLE_SIZE * 0.85),
-                text("Liste").size(TABLE_ENTRY_TITLE_SIZE)
+                text(tr(lang, "Liste")).size(TABLE_ENTRY_TITLE_SIZE)
             ]
             .align_items(Alignment::Center) 
         )
@@ -525,7 +475,7 @@ pub fn make_level_list_result(row_title_len: f32) -> Element<'static, Message> {
         container(
             column![
                 text("%").size(TABLE_ENTRY_TITLE_SIZE * 0.85),
-                text("Résultat").size(TABLE_ENTRY_TITLE_SIZE)
+                text(tr(lang, "Résultat")).size(TABLE_ENTRY_TITLE_SIZE)
             ]
             .align_items(Alignment::Center)
         )
@@ -545,11 +495,12 @@ pub fn make_one_id_language_table(
     table_columns: &IdLang,
 
     bin: bool,
+    lang: Language,
 ) -> Element<'static, Message> {
 
 
     let row_title_len = 60.0;
-    let row0 = make_level_list_result(row_title_len);
+    let row0 = make_level_list_result(row_title_len, lang);
 
 
     let tih_height1 = TEXT_INPUT_HEIGHT * 1.5;
@@ -557,11 +508,11 @@ pub fn make_one_id_language_table(
 
     let row1 = row![
         container(
-            text_input(
+            field_input(
                 "",
                 &table_columns.level1,
+                FieldPath::new(ear_side, Section::SpeechIdentification, field::Column::Level1),
             )
-            .on_input(get_message_fn("IdParoleLev1", ear_side))
             .size(TABLE_ENTRY_SIZE)
             .width(Length::Fixed(TONAL_TABLE_COL_WIDTH)),
         )
@@ -570,8 +521,7 @@ pub fn make_one_id_language_table(
         .align_x(Horizontal::Center),
         horizontal_space(2.0),
         container(
-            text_input("", &table_columns.list1,)
-                .on_input(get_message_fn("IdParoleList1", ear_side))
+            field_input("", &table_columns.list1, FieldPath::new(ear_side, Section::SpeechIdentification, field::Column::List1))
                 .size(TABLE_ENTRY_SIZE)
                 .width(Length::Fixed(TONAL_TABLE_COL_WIDTH)),
         )
@@ -580,8 +530,7 @@ pub fn make_one_id_language_table(
         .align_x(Horizontal::Center),
         horizontal_space(2.0),
         container(
-            text_input("", &table_columns.result1,)
-                .on_input(get_message_fn("IdParoleRes1", ear_side))
+            field_input("", &table_columns.result1, FieldPath::new(ear_side, Section::SpeechIdentification, field::Column::Result1))
                 .size(TABLE_ENTRY_SIZE)
                 .width(Length::Fixed(TONAL_TABLE_COL_WIDTH)),
         )
@@ -593,27 +542,25 @@ pub fn make_one_id_language_table(
     
     let row2 = row![
         container(
-            text_input(
+            field_input(
                 "",
                 &table_columns.level2,
+                FieldPath::new(ear_side, Section::SpeechIdentification, field::Column::Level2),
             )
-            .on_input(get_message_fn("IdParoleLev2", ear_side))
             .size(TABLE_ENTRY_SIZE)
             .width(Length::Fixed(TONAL_TABLE_COL_WIDTH )),
         )
         .height(Length::Fixed(TEXT_INPUT_HEIGHT* tih_height2)),
         horizontal_space(2.0),
         container(
-            text_input("", &table_columns.list2,)
-                .on_input(get_message_fn("IdParoleList2", ear_side))
+            field_input("", &table_columns.list2, FieldPath::new(ear_side, Section::SpeechIdentification, field::Column::List2))
                 .size(TABLE_ENTRY_SIZE)
                 .width(Length::Fixed(TONAL_TABLE_COL_WIDTH)),
         )
         .height(Length::Fixed(TEXT_INPUT_HEIGHT* tih_height2)),
         horizontal_space(2.0),
         container(
-            text_input("", &table_columns.result2,)
-                .on_input(get_message_fn("IdParoleRes2", ear_side))
+            field_input("", &table_columns.result2, FieldPath::new(ear_side, Section::SpeechIdentification, field::Column::Result2))
                 .size(TABLE_ENTRY_SIZE)
                 .width(Length::Fixed(TONAL_TABLE_COL_WIDTH)),
         )
@@ -635,7 +582,7 @@ pub fn make_one_id_language_table(
     if bin {
         final_table = final_table.push(column![
             vertical_space(57.0),
-            text("Avec lecture\nlabiale  ")
+            text(tr(lang, "Avec lecture\nlabiale  "))
                 .size(14)
                 .horizontal_alignment(Horizontal::Center),
         ]);
@@ -660,7 +607,6 @@ pub fn make_one_id_language_table(
                 container(
                     text(table_name)
                         .size(TABLE_TITLE_SIZE)
-                        .style(TABLE_TITLE_TEXT_COLOR)
                         .horizontal_alignment(Horizontal::Center)
                 )
                 .padding(3)
@@ -684,11 +630,13 @@ pub fn make_one_id_language_table(
 }
 
 pub fn tympa(audio_rox: &AudioRox) -> (Element<Message>, Element<Message>) {
+    let lang = audio_rox.report_language;
+
     let tympa_table_left =
-        make_one_tympa_table(EarSide::Left, "TYMPANOMÉTRIE", &audio_rox.tympa_left);
+        make_one_tympa_table(EarSide::Left, tr(lang, "TYMPANOMÉTRIE"), &audio_rox.tympa_left, lang);
 
     let tympa_table_right =
-        make_one_tympa_table(EarSide::Right, "TYMPANOMÉTRIE", &audio_rox.tympa_right);
+        make_one_tympa_table(EarSide::Right, tr(lang, "TYMPANOMÉTRIE"), &audio_rox.tympa_right, lang);
 
     (tympa_table_right, tympa_table_left)
 }
@@ -697,6 +645,7 @@ pub fn make_one_tympa_table(
     ear_side: EarSide,
     table_name: &str,
     table_columns: &Tympa,
+    lang: Language,
 ) -> Element<'static, Message> {
     let mut table = Column::new();
     let col1 = row![
@@ -704,7 +653,7 @@ pub fn make_one_tympa_table(
         horizontal_space(2.0),
         container(
             column![
-                text("Volume")
+                text(tr(lang, "Volume"))
                     .size(TABLE_ENTRY_TITLE_SIZE)
                     .horizontal_alignment(Horizontal::Center),
                 text("ml")
@@ -717,8 +666,7 @@ pub fn make_one_tympa_table(
         .width(Length::Shrink),
         //
         container(
-            text_input("", &table_columns.volume,)
-                .on_input(get_message_fn("TympaVolume", ear_side))
+            field_input("", &table_columns.volume, FieldPath::new(ear_side, Section::Tympanometry, field::Column::Volume))
                 .size(TABLE_ENTRY_SIZE)
                 .width(Length::Fixed(TYMPA_TABLE_COL_WIDTH)),
         ),
@@ -727,7 +675,7 @@ pub fn make_one_tympa_table(
         //
         container(
             column![
-                text("Pression")
+                text(tr(lang, "Pression"))
                     // .style(TABLE_TEXT_COLOR)
                     .size(TABLE_ENTRY_TITLE_SIZE)
                     .horizontal_alignment(Horizontal::Center),
@@ -742,12 +690,11 @@ pub fn make_one_tympa_table(
         .width(Length::Shrink),
         //
         container(
-            text_input(
+            field_input(
                 "    \u{2014}",
                 &table_columns.pressure,
-                
+                FieldPath::new(ear_side, Section::Tympanometry, field::Column::Pressure),
             )
-            .on_input(get_message_fn("TympaPressure", ear_side))
             .size(TABLE_ENTRY_SIZE)
             .width(Length::Fixed(TYMPA_TABLE_COL_WIDTH)),
         ),
@@ -757,7 +704,7 @@ pub fn make_one_tympa_table(
         //
         container(
             column![
-                text("Compliance")
+                text(tr(lang, "Compliance"))
                     // .style(TABLE_TEXT_COLOR)
                     .size(TABLE_ENTRY_TITLE_SIZE)
                     .horizontal_alignment(Horizontal::Center),
@@ -773,12 +720,11 @@ pub fn make_one_tympa_table(
         .width(Length::Shrink),
         //
         container(
-            text_input(
+            field_input(
                 "    \u{2014}",
                 &table_columns.compliance,
-
+                FieldPath::new(ear_side, Section::Tympanometry, field::Column::Compliance),
             )
-            .on_input(get_message_fn("TympaCompliance", ear_side))
             .size(TABLE_ENTRY_SIZE)
             .width(Length::Fixed(TYMPA_TABLE_COL_WIDTH)),
         ),
@@ -797,7 +743,6 @@ pub fn make_one_tympa_table(
                 container(
                     text(table_name)
                         .size(TABLE_TITLE_SIZE)
-                        .style(TABLE_TITLE_TEXT_COLOR)
                         .horizontal_alignment(Horizontal::Center)
                 )
                 .padding(3)
@@ -822,16 +767,20 @@ pub fn make_one_tympa_table(
 }
 
 pub fn stap(audio_rox: &AudioRox) -> (Element<Message>, Element<Message>) {
+    let lang = audio_rox.report_language;
+
     let stap_table_left = make_one_stap_table(
         EarSide::Left,
-        "RÉFLEXE STAPÉDIEN - dB",
+        tr(lang, "RÉFLEXE STAPÉDIEN - dB"),
         &audio_rox.stap_left,
+        lang,
     );
 
     let stap_table_right = make_one_stap_table(
         EarSide::Right,
-        "RÉFLEXE STAPÉDIEN - dB",
+        tr(lang, "RÉFLEXE STAPÉDIEN - dB"),
         &audio_rox.stap_right,
+        lang,
     );
 
     (stap_table_right, stap_table_left)
@@ -841,6 +790,7 @@ pub fn make_one_stap_table(
     ear_side: EarSide,
     table_name: &str,
     table_columns: &Stap,
+    lang: Language,
 ) -> Element<'static, Message> {
     let mut table = Column::new();
 
@@ -850,7 +800,7 @@ pub fn make_one_stap_table(
 
     let top_row = row![
         container(
-            text("Stimulation")
+            text(tr(lang, "Stimulation"))
                 // .style(TABLE_TEXT_COLOR)
                 .size(TABLE_ENTRY_TITLE_SIZE)
                 .horizontal_alignment(Horizontal::Center),
@@ -889,7 +839,7 @@ pub fn make_one_stap_table(
 
     let second_row = row![
         container(
-            text("Ipsilatérale")
+            text(tr(lang, "Ipsilatérale"))
                 // .style(TABLE_TEXT_COLOR)
                 .size(TABLE_ENTRY_TITLE_SIZE)
                 .horizontal_alignment(Horizontal::Center),
@@ -897,36 +847,33 @@ pub fn make_one_stap_table(
         .width(first_col_width)
         .align_x(Horizontal::Right),
         container(
-            text_input(
+            field_input(
                 "",
                 &table_columns.ipsi.khz_500,
-
+                FieldPath::new(ear_side, Section::ReflexIpsi, field::Column::Khz500),
             )
-            .on_input(get_message_fn("StapIpsi500", ear_side))
             .size(TABLE_ENTRY_SIZE)
             .width(Length::Fixed(text_input_width)),
         )
         .width(col_width)
         .align_x(Horizontal::Center),
         container(
-            text_input(
+            field_input(
                 "",
                 &table_columns.ipsi.khz_1000,
-
+                FieldPath::new(ear_side, Section::ReflexIpsi, field::Column::Khz1000),
             )
-            .on_input(get_message_fn("StapIpsi1000", ear_side))
             .size(TABLE_ENTRY_SIZE)
             .width(Length::Fixed(text_input_width)),
         )
         .width(col_width)
         .align_x(Horizontal::Center),
         container(
-            text_input(
+            field_input(
                 "",
                 &table_columns.ipsi.khz_2000,
-
+                FieldPath::new(ear_side, Section::ReflexIpsi, field::Column::Khz2000),
             )
-            .on_input(get_message_fn("StapIpsi2000", ear_side))
             .size(TABLE_ENTRY_SIZE)
             .width(Length::Fixed(text_input_width)),
         )
@@ -938,7 +885,7 @@ pub fn make_one_stap_table(
 
     let third_row = row![
         container(
-            text("Controlatérale")
+            text(tr(lang, "Controlatérale"))
                 // .style(TABLE_TEXT_COLOR)
                 .size(TABLE_ENTRY_TITLE_SIZE)
                 .horizontal_alignment(Horizontal::Center),
@@ -946,35 +893,33 @@ pub fn make_one_stap_table(
         .width(first_col_width)
         .align_x(Horizontal::Right),
         container(
-            text_input(
+            field_input(
                 "",
                 &table_columns.control.khz_500,
+                FieldPath::new(ear_side, Section::ReflexContra, field::Column::Khz500),
             )
-            .on_input(get_message_fn("StapControl500", ear_side))
             .size(TABLE_ENTRY_SIZE)
             .width(Length::Fixed(text_input_width)),
         )
         .width(col_width)
         .align_x(Horizontal::Center),
         container(
-            text_input(
+            field_input(
                 "",
                 &table_columns.control.khz_1000,
-
+                FieldPath::new(ear_side, Section::ReflexContra, field::Column::Khz1000),
             )
-            .on_input(get_message_fn("StapControl1000", ear_side))
             .size(TABLE_ENTRY_SIZE)
             .width(Length::Fixed(text_input_width)),
         )
         .width(col_width)
         .align_x(Horizontal::Center),
         container(
-            text_input(
+            field_input(
                 "",
                 &table_columns.control.khz_2000,
-
+                FieldPath::new(ear_side, Section::ReflexContra, field::Column::Khz2000),
             )
-            .on_input(get_message_fn("StapControl2000", ear_side))
             .size(TABLE_ENTRY_SIZE)
             .width(Length::Fixed(text_input_width)),
         )
@@ -996,7 +941,6 @@ pub fn make_one_stap_table(
                 container(
                     text(table_name)
                         .size(TABLE_TITLE_SIZE)
-                        .style(TABLE_TITLE_TEXT_COLOR)
                         .horizontal_alignment(Horizontal::Center)
                 )
                 .padding(3)
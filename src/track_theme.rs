@@ -0,0 +1,215 @@
+use std::sync::OnceLock;
+
+use iced::theme::{Palette, Theme};
+use iced::Color;
+
+use palette::convert::FromColorUnclamped;
+use palette::{rgb::Rgb, LabHue, Lch, Shade};
+
+use serde::{Deserialize, Serialize};
+
+macro_rules! color {
+    ($red:expr, $green:expr, $blue:expr) => {
+        Color::from_rgba(
+            $red as f32 / 255.0,
+            $green as f32 / 255.0,
+            $blue as f32 / 255.0,
+            1.0,
+        )
+    };
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThemeVariant {
+    #[default]
+    Light,
+    Dark,
+    HighContrast,
+}
+
+pub const THEME_VARIANTS: [ThemeVariant; 3] = [
+    ThemeVariant::Light,
+    ThemeVariant::Dark,
+    ThemeVariant::HighContrast,
+];
+
+impl ThemeVariant {
+    /// The variant `theme` was built from. The background tells them apart.
+    pub fn of(theme: &Theme) -> Self {
+        let background = theme.palette().background;
+        THEME_VARIANTS
+            .into_iter()
+            .find(|variant| TrackTheme::new(*variant).palette.background == background)
+            .unwrap_or_default()
+    }
+}
+
+impl std::fmt::Display for ThemeVariant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ThemeVariant::Light => "Clair",
+            ThemeVariant::Dark => "Sombre",
+            ThemeVariant::HighContrast => "Contraste élevé",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Colours of the report, on top of the palette iced styles its widgets with.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrackTheme {
+    pub palette: Palette,
+
+    pub section_background: Color,
+    pub section_title_text: Color,
+    pub section_border: Color,
+
+    pub table_border: Color,
+    pub table_title_text: Color,
+
+    pub grid: Color,
+    pub threshold_20: Color,
+    pub axis_label: Color,
+    pub curve: Color,
+    pub overlay_label: Color,
+
+    pub legend_text: Color,
+    pub legend_title: Color,
+    pub legend_border: Color,
+    pub legend_symbol: Color,
+
+    pub link: Color,
+    pub link_hovered: Color,
+}
+
+/// The themes, in the order of `THEME_VARIANTS`, built on first use.
+static TRACK_THEMES: OnceLock<[TrackTheme; 3]> = OnceLock::new();
+
+impl TrackTheme {
+    pub fn new(variant: ThemeVariant) -> &'static Self {
+        let themes = TRACK_THEMES.get_or_init(|| [Self::light(), Self::dark(), Self::high_contrast()]);
+        match variant {
+            ThemeVariant::Light => &themes[0],
+            ThemeVariant::Dark => &themes[1],
+            ThemeVariant::HighContrast => &themes[2],
+        }
+    }
+
+    /// The paper is white whatever the theme of the interface, so exports and
+    /// printing always use the light colours.
+    pub fn print() -> &'static Self {
+        Self::new(ThemeVariant::Light)
+    }
+
+    /// The colours of the report drawn with `theme`, for the canvases and the styles
+    /// that only get the iced theme.
+    pub fn of(theme: &Theme) -> &'static Self {
+        Self::new(ThemeVariant::of(theme))
+    }
+
+    pub fn theme(&self) -> Theme {
+        Theme::custom(self.palette)
+    }
+
+    fn lch_to_rgb(lch: Lch) -> Color {
+        let rgb: Rgb = Rgb::from_color_unclamped(lch);
+        Color::from_rgb(rgb.red, rgb.green, rgb.blue)
+    }
+
+    fn light() -> Self {
+        Self {
+            palette: Palette::LIGHT,
+
+            section_background: Color::from_rgb(0.98, 0.98, 0.98),
+            section_title_text: Color::from_rgb(0.02, 0.02, 0.02),
+            section_border: Color::from_rgb(0.5, 0.25, 0.25),
+
+            table_border: Color::from_rgb(0.5, 0.5, 0.5),
+            table_title_text: Color::from_rgb(0.05, 0.02, 0.030),
+
+            grid: Color::from_rgb(0.7, 0.7, 0.7),
+            threshold_20: Color::from_rgb(0.5, 0.5, 0.5),
+            axis_label: Color::from_rgb(0.15, 0.15, 0.157),
+            curve: Color::from_rgba(0.83, 0.83, 0.83, 0.83),
+            overlay_label: Color::from_rgb(0.45, 0.45, 0.45),
+
+            legend_text: Color::from_rgb(0.05, 0.05, 0.05),
+            legend_title: Color::from_rgb(0.05, 0.05, 0.05),
+            legend_border: Color::from_rgb(0.5, 0.5, 0.5),
+            legend_symbol: Color::from_rgb(0.4, 0.4, 0.4),
+
+            link: Color::from_rgb(0.2, 0.3, 0.1),
+            link_hovered: Color::from_rgb(0.1, 0.15, 0.8),
+        }
+    }
+
+    /// Shades of a single bluish grey, from the background to the text.
+    fn dark() -> Self {
+        let base = Lch::new(14.0, 6.0, LabHue::from_degrees(250.0));
+        let shade = |factor: f32| Self::lch_to_rgb(base.lighten(factor));
+
+        Self {
+            palette: Palette {
+                background: shade(0.0),
+                text: shade(0.9),
+                primary: color!(110, 150, 230),
+                success: color!(80, 170, 120),
+                danger: color!(210, 90, 90),
+            },
+
+            section_background: shade(0.08),
+            section_title_text: shade(0.9),
+            section_border: color!(150, 90, 90),
+
+            table_border: shade(0.4),
+            table_title_text: shade(0.85),
+
+            grid: shade(0.3),
+            threshold_20: shade(0.5),
+            axis_label: shade(0.75),
+            curve: shade(0.8),
+            overlay_label: shade(0.6),
+
+            legend_text: shade(0.85),
+            legend_title: shade(0.85),
+            legend_border: shade(0.4),
+            legend_symbol: shade(0.7),
+
+            link: color!(150, 200, 130),
+            link_hovered: color!(110, 150, 230),
+        }
+    }
+
+    fn high_contrast() -> Self {
+        Self {
+            palette: Palette {
+                background: Color::BLACK,
+                text: Color::WHITE,
+                primary: color!(255, 214, 0),
+                success: color!(0, 230, 118),
+                danger: color!(255, 82, 82),
+            },
+
+            section_background: color!(40, 40, 40),
+            section_title_text: Color::WHITE,
+            section_border: Color::WHITE,
+
+            table_border: Color::WHITE,
+            table_title_text: Color::WHITE,
+
+            grid: color!(170, 170, 170),
+            threshold_20: Color::WHITE,
+            axis_label: Color::WHITE,
+            curve: Color::WHITE,
+            overlay_label: color!(255, 214, 0),
+
+            legend_text: Color::WHITE,
+            legend_title: Color::WHITE,
+            legend_border: Color::WHITE,
+            legend_symbol: Color::WHITE,
+
+            link: color!(255, 214, 0),
+            link_hovered: Color::WHITE,
+        }
+    }
+}
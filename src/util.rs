@@ -1,16 +1,35 @@
 
 use serde::{Deserialize, Serialize};
 
-use std::fs::File;
-use std::io::Write;
 
-use super::partners:: PartnerAndSuccursale;
+use super::partners::{PartnerAndSuccursale, TemplateChoice};
 
 use super::tonal_tables::{IsRecorded, Lang,   TonalTable,};
 
-use super::plot::{ PlotInfo, Shape};
+use super::plot::{Audiogram, EarSide, FreeField, FreeFieldChart, PlotInfo, Shape, ThresholdModifier};
+
+use super::export::ExportDpi;
+use super::clinician::{ClinicianField, ClinicianProfile};
+use super::delivery::{DeliveryField, DeliveryRecord, SmtpSecurity};
+use super::cursor::AudiogramCursor;
+use super::distribution::{deserialize_recipient, CCCategory, Recipient, RecipientField};
+use super::field::{FieldPath, FocusMove};
+use super::history::{History, TextField};
+use super::locale::Language;
+use super::notes::{Notes, StandardSentence};
+use super::overlay::Overlays;
+use super::recovery;
+use super::handicap::{Formula, HearingHandicap};
+use super::paediatric::{Audience, Paediatric, ReliabilityNote, SchoolRecommendation, SpeechTest, Stimulus};
+use super::screening::{NoiseScreening, Sex};
+use super::settings::UserSettings;
+use super::signing::{ReportSignature, RevisionLink, VerifyError};
+use super::track_theme::ThemeVariant;
+use super::print::{Margin, PageSetup, PaperSize, PrintPreview};
 
 use iced::font;
+use iced::keyboard::KeyCode;
+use iced::advanced::widget::Id;
 
 
 #[derive(Debug, Clone)]
@@ -22,6 +41,40 @@ pub enum Message {
 
     SaveFile,
     LoadFile,
+    ConfirmLoad,
+    CancelLoad,
+    Undo,
+    Redo,
+    Autosave,
+    RestoreSession,
+    DiscardSession,
+    ExportPng,
+    ExportDpiChanged(ExportDpi),
+    ExportPdf,
+    ExportFhir,
+
+    ShowPrintPreview,
+    ClosePrintPreview,
+    PrintReport,
+    PaperSizeChanged(PaperSize),
+    MarginChanged(Margin),
+    FitToPageChanged(bool),
+    PatientNameChanged(String),
+    ReportLanguageChanged(Language),
+    UiLanguageChanged(Language),
+    ThemeChanged(ThemeVariant),
+    ZoomIn,
+    ZoomOut,
+    ZoomReset,
+    WindowResized(u32),
+    MoveFocus(FocusMove),
+    FocusFound(Option<Id>, FocusMove),
+    AudiogramKey(KeyCode),
+    AudiogramCursorPlaced(EarSide, u32, f32),
+    ThresholdModifierToggled(ThresholdModifier),
+    Escape,
+    ShowShortcuts,
+    HideShortcuts,
 
     ShowParnerChoices,
     ShowSuccursaleChoices,
@@ -29,16 +82,59 @@ pub enum Message {
     CancelSuccursaleChoices,
 
 
-    CCPatientChanged(bool),
-    CCAudioProChanged(bool),
-    CCFamilyDocChanged(bool),
-    CCORLChanged(bool),
-    CCOtherChanged(bool),
-    CCReadapt(bool),
+    CCChanged(CCCategory, bool),
+    RecipientEdited(CCCategory, RecipientField, String),
+    ShowRecipients,
+    HideRecipients,
+    DistributeReport,
+    ShowDelivery,
+    HideDelivery,
+    DeliverySettingChanged(DeliveryField, String),
+    SmtpSecurityChanged(SmtpSecurity),
+    PartnerContactChanged(String),
+    DeliverReport,
+    DeliveryFinished(Vec<DeliveryRecord>),
+    NoteChanged(usize, String),
+    NoteSubmitted(usize),
+    StandardSentenceChanged(StandardSentence, bool),
+    ExaminerChanged(ClinicianProfile),
+    ShowClinicians,
+    HideClinicians,
+    ClinicianAdded,
+    ClinicianRemoved(usize),
+    ClinicianEdited(usize, ClinicianField, String),
+    ConfirmFinalize,
+    FinalizeReport,
+    CancelFinalize,
+    AmendReport,
+    VerifyReport,
+    HideVerification,
+    ScreeningChanged(bool),
+    ScreeningAgeChanged(String),
+    ScreeningFileNumberChanged(String),
+    ScreeningBirthDateChanged(String),
+    ScreeningSexChanged(Sex),
+    AgeCorrectionChanged(bool),
+    SaveBaseline,
+    LoadBaseline,
+    PaediatricChanged(bool),
+    StimulusChanged(Stimulus),
+    SpeechTestChanged(SpeechTest),
+    ReliabilityNoteChanged(ReliabilityNote, bool),
+    AudienceChanged(Audience),
+    SchoolRecommendationChanged(SchoolRecommendation, bool),
+    HandicapChanged(bool),
+    FormulaChanged(Formula),
 
     PartnerChanged(PartnerAndSuccursale),
     SuccursaleChanged(PartnerAndSuccursale),
+    TemplateChanged(TemplateChoice),
     AdequateRestPeriodChanged(bool),
+    ExtendedFrequenciesChanged(bool),
+    FreeFieldChartChanged(FreeFieldChart),
+    DegreeOfLossChanged(bool),
+    SpeechBananaChanged(bool),
+    FamiliarSoundsChanged(bool),
     AnteriorThresholdDateChanged(String),
     AudiometerNameChanged(String),
     TympanometerNameChanged(String),
@@ -47,89 +143,97 @@ pub enum Message {
     ValidityChanged(Validity),
     MethodChanged(MethodEval),
     TransductorChanged(Transductor),
+    FieldChanged(FieldPath, String),
 
-    // SuccursaleChanged(Succursale),
-    MSPRightChanged(String),
-    MSP4RightChanged(String),
-    FLCHRightChanged(String),
-
-    MSPLeftChanged(String),
-    MSP4LeftChanged(String),
-    FLCHLeftChanged(String),
-
-    SDPRightChanged(String),
-    SRPRightChanged(String),
-
-    MiscRightChanged(String),
-
-    SDPLeftChanged(String),
-    SRPLeftChanged(String),
-
-    MiscLeftChanged(String),
-
-    SDPFreeChanged(String),
-    SRPFreeChanged(String),
-    MiscBinChanged(String),
-
-    // VocalMiscRightChanged(String),
-    // VocalMiscLeftChanged(String),
-    // VocalMiscBinChanged(String),
     VocalLangChanged(Lang),
     IsRecordedChanged(IsRecorded),
 
-    IdLanRes1LeftChanged(String),
-    IdLanRes2LeftChanged(String),
-    IdLanLev1LeftChanged(String),
-    IdLanLev2LeftChanged(String),
-    IdLanList1LeftChanged(String),
-    IdLanList2LeftChanged(String),
-
-    IdLanRes1RightChanged(String),
-    IdLanRes2RightChanged(String),
-    IdLanLev1RightChanged(String),
-    IdLanLev2RightChanged(String),
-    IdLanList1RightChanged(String),
-    IdLanList2RightChanged(String),
-
-    IdLanRes1BinChanged(String),
-    IdLanRes2BinChanged(String),
-    IdLanLev1BinChanged(String),
-    IdLanLev2BinChanged(String),
-    IdLanList1BinChanged(String),
-    IdLanList2BinChanged(String),
-
-    TympaVolumeLeftChanged(String),
-    TympaPressureLeftChanged(String),
-    TympaComplianceLeftChanged(String),
-
-    TympaVolumeRightChanged(String),
-    TympaPressureRightChanged(String),
-    TympaComplianceRightChanged(String),
-
-    StapIpsi500LeftChanged(String),
-    StapIpsi1000LeftChanged(String),
-    StapIpsi2000LeftChanged(String),
-    StapIpsi4000LeftChanged(String),
-
-    StapControl500LeftChanged(String),
-    StapControl1000LeftChanged(String),
-    StapControl2000LeftChanged(String),
-    StapControl4000LeftChanged(String),
-
-    StapIpsi500RightChanged(String),
-    StapIpsi1000RightChanged(String),
-    StapIpsi2000RightChanged(String),
-    StapIpsi4000RightChanged(String),
-
-    StapControl500RightChanged(String),
-    StapControl1000RightChanged(String),
-    StapControl2000RightChanged(String),
-    StapControl4000RightChanged(String),
-
     None,
 }
 
 
+impl Message {
+    /// The text input edited by the message, if any, used to merge keystrokes in the
+    /// undo history.
+    pub fn text_field(&self) -> Option<TextField> {
+        match self {
+            Message::FieldChanged(path, _) => Some(TextField::Table(*path)),
+            Message::NoteChanged(index, _) => Some(TextField::Note(*index)),
+            Message::RecipientEdited(category, field, _) => Some(TextField::Recipient(*category, *field)),
+            Message::PatientNameChanged(_)
+            | Message::AnteriorThresholdDateChanged(_)
+            | Message::AudiometerNameChanged(_)
+            | Message::TympanometerNameChanged(_)
+            | Message::ScreeningAgeChanged(_)
+            | Message::ScreeningFileNumberChanged(_)
+            | Message::ScreeningBirthDateChanged(_) => {
+                Some(TextField::Other(std::mem::discriminant(self)))
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the message can change the report. The others only change the interface,
+    /// the user settings or the files, and are neither snapshotted nor undone. Loading
+    /// replaces the report rather than editing it.
+    pub fn edits_report(&self) -> bool {
+        !matches!(
+            self,
+            Message::FontLoaded(_)
+                | Message::SaveFile
+                | Message::LoadFile
+                | Message::ConfirmLoad
+                | Message::CancelLoad
+                | Message::Autosave
+                | Message::ExportPng
+                | Message::ExportDpiChanged(_)
+                | Message::ExportPdf
+                | Message::ExportFhir
+                | Message::ShowPrintPreview
+                | Message::ClosePrintPreview
+                | Message::PrintReport
+                | Message::PaperSizeChanged(_)
+                | Message::MarginChanged(_)
+                | Message::FitToPageChanged(_)
+                | Message::UiLanguageChanged(_)
+                | Message::ThemeChanged(_)
+                | Message::ZoomIn
+                | Message::ZoomOut
+                | Message::ZoomReset
+                | Message::WindowResized(_)
+                | Message::MoveFocus(_)
+                | Message::FocusFound(..)
+                | Message::AudiogramCursorPlaced(..)
+                | Message::Escape
+                | Message::ShowShortcuts
+                | Message::HideShortcuts
+                | Message::ShowParnerChoices
+                | Message::HideSuccursaleMenu
+                | Message::CancelSuccursaleChoices
+                | Message::ShowRecipients
+                | Message::HideRecipients
+                | Message::DistributeReport
+                | Message::ShowDelivery
+                | Message::HideDelivery
+                | Message::DeliverySettingChanged(..)
+                | Message::SmtpSecurityChanged(_)
+                | Message::PartnerContactChanged(_)
+                | Message::DeliverReport
+                | Message::DeliveryFinished(_)
+                | Message::NoteSubmitted(_)
+                | Message::ShowClinicians
+                | Message::HideClinicians
+                | Message::ClinicianAdded
+                | Message::ClinicianRemoved(_)
+                | Message::ConfirmFinalize
+                | Message::CancelFinalize
+                | Message::VerifyReport
+                | Message::HideVerification
+        )
+    }
+}
+
+
 
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)] 
@@ -173,6 +277,7 @@ impl Default for Transductor {
 }
 
 
+
 #[derive(Default, Serialize, Deserialize)]
 pub struct VocalTable {
     pub sdp: String,
@@ -224,27 +329,22 @@ impl Default for Succursale {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+/// Recipients of a copy of the report, one per category of the CC list.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct CC {
-    pub patient: bool,
-    pub audioprothesiste: bool,
-    pub family_doctor: bool,
-    pub orl: bool,
-    pub other: bool,
-    pub readapt: bool,
-}
-
-impl Default for CC {
-    fn default() -> Self {
-        Self {
-            patient: false,
-            audioprothesiste: false,
-            family_doctor: false,
-            orl: false,
-            other: false,
-            readapt: false,
-        }
-    }
+    #[serde(deserialize_with = "deserialize_recipient")]
+    pub patient: Recipient,
+    #[serde(deserialize_with = "deserialize_recipient")]
+    pub audioprothesiste: Recipient,
+    #[serde(deserialize_with = "deserialize_recipient")]
+    pub family_doctor: Recipient,
+    #[serde(deserialize_with = "deserialize_recipient")]
+    pub orl: Recipient,
+    #[serde(deserialize_with = "deserialize_recipient")]
+    pub other: Recipient,
+    #[serde(deserialize_with = "deserialize_recipient")]
+    pub readapt: Recipient,
 }
 
 #[derive( Serialize, Deserialize)]
@@ -252,6 +352,14 @@ pub enum Modals {
     None,
     Partner,
     Succursale,
+    RestoreSession,
+    ConfirmLoad,
+    Shortcuts,
+    Clinicians,
+    ConfirmFinalize,
+    Verification,
+    Recipients,
+    Delivery,
 }
 
 impl Default for Modals {
@@ -260,7 +368,11 @@ impl Default for Modals {
     }
 }
 
+/// File the report is saved to and loaded from, until another one is chosen.
+pub const REPORT_FILE: &str = "data.json";
+
 #[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AudioRox {
     pub show_partner_choices: bool,
     #[serde(skip_serializing, skip_deserializing)]
@@ -272,6 +384,11 @@ pub struct AudioRox {
     pub version: usize,
 
     pub partner: PartnerAndSuccursale,
+    /// Template chosen for this report, by name. None for the template of the partner.
+    pub template: Option<String>,
+    pub patient_name: String,
+    /// Language the report is written in, whatever the language of the interface.
+    pub report_language: Language,
     pub default_checkbox: bool,
     pub custom_checkbox: bool,
     pub validity: Validity, // TODO: these three should be somewhere else
@@ -281,6 +398,13 @@ pub struct AudioRox {
     pub tonal_table_right: TonalTable,
     pub tonal_table_free: TonalTable,
 
+    pub audiogram_right: Audiogram,
+    pub audiogram_left: Audiogram,
+    pub free_field: FreeField,
+    /// Shows the frequencies above 8 kHz on the audiograms.
+    pub extended_frequencies: bool,
+    pub overlays: Overlays,
+
     pub vocal_table_left: VocalTable,
     pub vocal_table_right: VocalTable,
     pub vocal_table_free: VocalTable,
@@ -306,6 +430,60 @@ pub struct AudioRox {
     pub vocal_lang: Lang,
     pub is_recorded: IsRecorded,
     pub cc: CC,
+    pub notes: Notes,
+    /// Profile of the audiologist signing the report, as it was when chosen.
+    pub examiner: ClinicianProfile,
+    /// Signature of the finalized report, which is then read-only.
+    pub signature: Option<ReportSignature>,
+    /// Finalized revision amended by this draft.
+    pub amends: Option<RevisionLink>,
+    /// Occupational noise screening, compared to the baseline of the worker.
+    pub screening: NoiseScreening,
+    /// Evaluation of a child, with the sound-field thresholds on the binaural chart.
+    pub paediatric: Paediatric,
+    /// Percentage of hearing loss, for insurance and compensation claims.
+    pub handicap: HearingHandicap,
+    /// Result of the last verification of the signature.
+    #[serde(skip_serializing, skip_deserializing)]
+    pub verification: Option<Result<ReportSignature, VerifyError>>,
+    /// Deliveries of the log, shown in the delivery window.
+    #[serde(skip_serializing, skip_deserializing)]
+    pub delivery_log: Vec<DeliveryRecord>,
+    /// Whether copies are being sent, until the server has answered for all of them.
+    #[serde(skip_serializing, skip_deserializing)]
+    pub delivering: bool,
+
+    #[serde(skip_serializing, skip_deserializing)]
+    pub settings: UserSettings,
+    /// Width of the window at the scale of the screen, before the page is scaled to it.
+    #[serde(skip_serializing, skip_deserializing)]
+    pub window_width: f32,
+    /// File the report was loaded from or last saved to.
+    #[serde(skip_serializing, skip_deserializing)]
+    pub report_path: String,
+    /// Cursor of the keyboard entry of thresholds on the audiograms.
+    #[serde(skip_serializing, skip_deserializing)]
+    pub audiogram_cursor: Option<AudiogramCursor>,
+    #[serde(skip_serializing, skip_deserializing)]
+    pub export_dpi: ExportDpi,
+    #[serde(skip_serializing, skip_deserializing)]
+    pub page_setup: PageSetup,
+    #[serde(skip_serializing, skip_deserializing)]
+    pub print_preview: Option<PrintPreview>,
+    #[serde(skip_serializing, skip_deserializing)]
+    pub history: History,
+    /// Report as last saved or loaded, to tell whether there are unsaved changes.
+    #[serde(skip_serializing, skip_deserializing)]
+    pub saved_snapshot: String,
+    /// Whether the report changed since it was last saved or loaded, kept up to date by
+    /// `update` so that the window title does not serialize the report.
+    #[serde(skip_serializing, skip_deserializing)]
+    pub dirty: bool,
+    #[serde(skip_serializing, skip_deserializing)]
+    pub autosaved_snapshot: String,
+    /// Report found in the recovery journal at startup.
+    #[serde(skip_serializing, skip_deserializing)]
+    pub recovered_session: Option<String>,
 
     #[serde(skip_serializing, skip_deserializing)]
     pub _plot_right: PlotInfo,
@@ -314,14 +492,80 @@ pub struct AudioRox {
 }
 
 impl AudioRox {
+    pub fn refresh_print_preview(&mut self) {
+        match PrintPreview::new(self) {
+            Ok(preview) => self.print_preview = Some(preview),
+            Err(e) => println!("Failed to render print preview: {}", e),
+        }
+    }
 
 
+    /// The report as saved in the report file, without the state of the interface.
+    pub fn snapshot(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+
+    pub fn restore_snapshot(&mut self, snapshot: &str) -> serde_json::Result<()> {
+        let report: Self = serde_json::from_str(snapshot)?;
+        self.replace_report(report);
+        Ok(())
+    }
+
+    /// Replaces the report while keeping the state of the interface and the history.
+    pub fn replace_report(&mut self, mut report: AudioRox) {
+        report.succursale_overlay_menu = std::mem::take(&mut self.succursale_overlay_menu);
+        report.settings = std::mem::take(&mut self.settings);
+        report.delivery_log = std::mem::take(&mut self.delivery_log);
+        report.delivering = self.delivering;
+        report.window_width = self.window_width;
+        report.report_path = std::mem::take(&mut self.report_path);
+        report.audiogram_cursor = self.audiogram_cursor.take();
+        report.export_dpi = self.export_dpi;
+        report.page_setup = self.page_setup;
+        report.print_preview = self.print_preview.take();
+        report.history = std::mem::take(&mut self.history);
+        report.saved_snapshot = std::mem::take(&mut self.saved_snapshot);
+        report.dirty = self.dirty;
+        report.autosaved_snapshot = std::mem::take(&mut self.autosaved_snapshot);
+        report.recovered_session = self.recovered_session.take();
+        *self = report;
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    pub fn mark_saved(&mut self) {
+        self.saved_snapshot = self.snapshot();
+        self.dirty = false;
+        self.autosaved_snapshot.clear();
+        recovery::remove_journal();
+    }
+
+    /// Writes the report to the recovery journal if it changed since the last save. The
+    /// journal of the previous session is left alone until it is restored or discarded.
+    pub fn autosave(&mut self) {
+        if self.recovered_session.is_some() {
+            return;
+        }
+        if !self.dirty {
+            recovery::remove_journal();
+            return;
+        }
+        let snapshot = self.snapshot();
+        if snapshot != self.autosaved_snapshot {
+            match recovery::write_journal(&snapshot) {
+                Ok(_) => self.autosaved_snapshot = snapshot,
+                Err(e) => println!("Failed to autosave: {}", e),
+            }
+        }
+    }
+
     pub fn save_to_file(&self, filename: &str) -> std::io::Result<()> {
         let data = self;
 
         let json = serde_json::to_string(&data).unwrap();
-        let mut file = File::create(filename)?;
-        file.write_all(json.as_bytes())
+        recovery::write_atomically(filename, &json)
     }
 
     pub fn load_from_file(
@@ -334,14 +578,12 @@ impl AudioRox {
     }
 }
 
+pub const FONTS: [&[u8]; 3] = [
+    include_bytes!("../fonts/Roboto-Medium.ttf").as_slice(),
+    include_bytes!("../fonts/FiraSans-Light.ttf").as_slice(),
+    include_bytes!("../fonts/Lato-Bold.ttf").as_slice(),
+];
+
 pub fn load_fonts() -> iced::Command<Message> {
-    iced::Command::batch([
-        iced::font::load(include_bytes!("../fonts/Roboto-Medium.ttf").as_slice())
-            .map(Message::FontLoaded),
-            font::load(include_bytes!("../fonts/FiraSans-Light.ttf").as_slice())
-            .map(Message::FontLoaded),
-            font::load(include_bytes!("../fonts/Lato-Bold.ttf").as_slice())
-            .map(Message::FontLoaded),
-    ])
+    iced::Command::batch(FONTS.map(|bytes| font::load(bytes).map(Message::FontLoaded)))
 }
-
@@ -2,30 +2,47 @@
 
 
 use super::util::*;
-use super::partners::{get_all_partners, get_all_succursales, modal, };
+use super::partners::{
+    get_all_partners, get_all_succursales, get_chosen_succursale, modal, PartnerAndSuccursale,
+    ReportFooter, ReportSection, ReportTemplate, TemplateChoice, PARENTS_SECTIONS,
+};
 
 use super::tonal_tables::{
-     identification_language, make_tonal_tables, seuils_vocaux_tables, stap, tympa,
+     identification_language, make_frequency_table, make_tonal_tables, seuils_vocaux_tables, stap, tympa,
 
 };
 
 use super::config::{
-     LegendCustomStyle, TitleContainerCustomStyle,
+     LegendCustomStyle, PrintPreviewCustomStyle, TitleContainerCustomStyle,
     DEFAULT_TEXT_INPUT_CONTENT_SIZE, IMMIT_CANVAS_WIDTH, LEGEND_BOTTOM_SPACE,
-    LEGEND_WIDTH,  RADIO_SIZE, RADIO_SPACING, RADIO_TEXT_SIZE, RADIO_TITLE_SIZE,
+    LEGEND_WIDTH, PAGE_WIDTH, PLOT_CANVAS_WIDTH,  RADIO_SIZE, RADIO_SPACING, RADIO_TEXT_SIZE, RADIO_TITLE_SIZE,
     SECTION_SEPARATOR_SPACE,  SECTION_TITLE_HORIZONTAL_SPACE,
      SPACE_BELOW_SECTION_TITLE,  TEXT_LINE_VSPACE,
 };
+use super::clinician::CLINICIAN_FIELDS;
+use super::cursor::AudiogramCursor;
+use super::delivery::{self, DeliveryField, DELIVERY_FIELDS, SMTP_SECURITIES};
+use super::distribution::{CCCategory, CC_CATEGORIES, RECIPIENT_FIELDS};
+use super::notes::{self, NOTE_LINES, STANDARD_SENTENCES};
+use super::export::EXPORT_DPIS;
+use super::locale::{tr, Language, Translated, LANGUAGES};
+use super::handicap::{self, Formula, AMA_FREQUENCIES, FORMULAS};
+use super::paediatric::{
+    plain_hearing, SpeechTest, AUDIENCES, RELIABILITY_NOTES, SCHOOL_RECOMMENDATIONS, SPEECH_TESTS, STIMULI,
+};
+use super::screening::{Sex, RECORDABLE_LEVEL, STS_SHIFT};
+use super::print::{preview_pages, PrintPreview, MARGINS, PAPER_SIZES};
 use super::immi_plot::im_plot;
 use super::legend::draw_legend;
-use super::plot::{plot, EarSide,   Shape};
+use super::plot::{plot, Audiogram, EarSide, FreeFieldChart, FREE_FIELD_CHARTS};
+use super::track_theme::THEME_VARIANTS;
 
 // use super::{AudioRox, Message, };
 use iced::alignment::{Horizontal, Vertical};
 
 use iced::theme;
 
-use iced::widget::{Column, scrollable,
+use iced::widget::{Column, scrollable, pick_list, image,
      button, checkbox, column, container, horizontal_space,
     radio, row, text, text_input, vertical_space, Rule,
 };
@@ -36,7 +53,157 @@ use iced::{
 
 
 
-pub fn view(audiorox: &AudioRox) ->  Element<Message> {
+/// The report page itself, as shown on screen and rasterized by the exports. The
+/// sections, their order, the logos and the footer come from the template chosen for the
+/// report, or else from the template of the partner.
+/// Editing aids, such as the cursor of the keyboard entry, are only shown on screen.
+/// The report of a child for the parents and the school keeps only the sections they need.
+pub fn report(audiorox: &AudioRox, on_screen: bool) -> Element<'_, Message> {
+    let mut template = audiorox
+        .settings
+        .templates
+        .for_report(audiorox.template.as_deref(), &audiorox.partner);
+    if audiorox.paediatric.for_parents() {
+        template.sections = PARENTS_SECTIONS.to_vec();
+    }
+    let cursor = audiorox.audiogram_cursor.as_ref().filter(|_| on_screen);
+
+    let mut content = Column::new();
+    let sections = template
+        .sections
+        .iter()
+        .filter(|section| match section {
+            ReportSection::NoiseScreening => audiorox.screening.enabled,
+            ReportSection::Paediatric => audiorox.paediatric.enabled,
+            ReportSection::HearingHandicap => audiorox.handicap.enabled,
+            _ => true,
+        });
+    for (index, section) in sections.enumerate() {
+        if index > 0 {
+            content = content.push(vertical_space(section_spacing(*section)));
+        }
+
+        content = content.push(match section {
+            ReportSection::Header => report_header(audiorox, &template),
+            ReportSection::TonalAudiometry => tonal_audiometry(audiorox, cursor),
+            ReportSection::NoiseScreening => noise_screening(audiorox, on_screen),
+            ReportSection::Paediatric => paediatric(audiorox, on_screen),
+            ReportSection::VocalAudiometry => vocal_audiometry(audiorox),
+            ReportSection::SpeechIdentification => speech_identification(audiorox),
+            ReportSection::Immittance => immittance(audiorox),
+            ReportSection::HearingHandicap => hearing_handicap(audiorox, on_screen),
+            ReportSection::Conclusion => conclusion(audiorox, on_screen),
+        });
+    }
+
+    if let Some(footer) = report_footer(audiorox, &template) {
+        content = content.push(footer);
+    }
+    if let Some(stamp) = signature_stamp(audiorox) {
+        content = content.push(stamp);
+    }
+
+    let final_content = container(content.align_items(Alignment::Center))
+        .width(Length::Fill)
+        // .height(Length::Fill)
+        ;
+
+    final_content.into()
+}
+
+/// Space left above a section when it follows another one.
+fn section_spacing(section: ReportSection) -> f32 {
+    match section {
+        ReportSection::NoiseScreening
+        | ReportSection::Paediatric
+        | ReportSection::VocalAudiometry
+        | ReportSection::Immittance
+        | ReportSection::HearingHandicap => {
+            SECTION_SEPARATOR_SPACE
+        }
+        ReportSection::SpeechIdentification => 8.0,
+        _ => 0.0,
+    }
+}
+
+fn report_header<'a>(audiorox: &'a AudioRox, template: &ReportTemplate) -> Element<'a, Message> {
+    let lang = audiorox.report_language;
+    // create a header with two columns of text: on the left and one on the right
+    let text_vspace = TEXT_LINE_VSPACE;
+
+
+
+
+    let succursale = super::partners::make_succursale_element(&audiorox.partner, lang);
+
+    let header_logo: Element<'_, Message> = match &template.header_logo {
+        Some(logo) => container(image(logo.as_str()).width(70))
+            .padding([0, 0, 0, 10])
+            .width(Length::Fixed(80.))
+            .into(),
+        None => horizontal_space(50.0).into(),
+    };
+
+    let header = row![
+        header_logo,
+        container(
+            column![
+                text(&audiorox.examiner.name)
+                    .font(super::config::FIRA)
+                    .size(30)
+                    .horizontal_alignment(Horizontal::Left),
+                text(tr(lang, &audiorox.examiner.title))
+                    .size(20)
+                    .horizontal_alignment(Horizontal::Left),
+            ]
+            .align_items(Alignment::Center)
+            
+        ).width(Length::FillPortion(2))
+        .align_x(Horizontal::Left),
+        horizontal_space(45),
+        container(column![
+            text(tr(lang, "ÉVALUATION AUDIOLOGIQUE"))
+                .size(27)
+                .horizontal_alignment(Horizontal::Center)
+                .width(Length::Fill),
+            vertical_space(15.)
+        ])
+        .height(Length::Fill)
+        .align_x(Horizontal::Center)
+        .align_y(Vertical::Bottom)
+        .width(Length::FillPortion(3)),
+        horizontal_space(1),
+
+        column![
+            vertical_space(Length::Fixed(13.)),
+            container(row![
+                text(tr(lang, "Date de l'évaluation : "))
+                    .size(18.0)
+                    .vertical_alignment(Vertical::Center),
+
+                column![vertical_space(text_vspace), Rule::horizontal(1.0),]
+            ])
+            .height(Length::Fixed(20.0)),
+            vertical_space(Length::Fixed(20.)),
+
+            succursale
+            .height(Length::Fixed(120. + 60.)),
+            vertical_space(Length::Fixed(2.)),
+
+        ]
+
+        .width(Length::FillPortion(2))
+    ]
+    .align_items(Alignment::Center)
+    .padding([0, 5, 0, 5])
+    .height(Length::Fixed(120. + 60.))
+    .width(Length::Fill);
+
+    header.into()
+}
+
+fn tonal_audiometry<'a>(audiorox: &'a AudioRox, cursor: Option<&AudiogramCursor>) -> Element<'a, Message> {
+    let lang = audiorox.report_language;
     //
     let r_size = RADIO_SIZE;
     let t_size = RADIO_TEXT_SIZE;
@@ -45,7 +212,7 @@ pub fn view(audiorox: &AudioRox) ->  Element<Message> {
     let validity = audiorox.validity;
 
     let good_validity = radio(
-        "Bonne",
+        tr(lang, "Bonne"),
         Validity::Good,
         Some(validity),
         Message::ValidityChanged,
@@ -55,7 +222,7 @@ pub fn view(audiorox: &AudioRox) ->  Element<Message> {
     .text_size(t_size);
 
     let medium_validity = radio(
-        "Moyenne",
+        tr(lang, "Moyenne"),
         Validity::Medium,
         Some(validity),
         Message::ValidityChanged,
@@ -65,7 +232,7 @@ pub fn view(audiorox: &AudioRox) ->  Element<Message> {
     .text_size(t_size);
 
     let null_validity = radio(
-        "Nulle",
+        tr(lang, "Nulle"),
         Validity::Poor,
         Some(validity),
         Message::ValidityChanged,
@@ -78,7 +245,7 @@ pub fn view(audiorox: &AudioRox) ->  Element<Message> {
         .spacing(3)
         .width(Length::Shrink);
 
-    let validity_title = text("VALIDITÉ")
+    let validity_title = text(tr(lang, "VALIDITÉ"))
         .size(RADIO_TITLE_SIZE)
         .width(Length::Shrink);
 
@@ -97,7 +264,7 @@ pub fn view(audiorox: &AudioRox) ->  Element<Message> {
     .text_size(13.7);
 
     let cond_play = radio(
-        "Jeu",
+        tr(lang, "Jeu"),
         MethodEval::Play,
         Some(audiorox.method),
         Message::MethodChanged,
@@ -107,7 +274,7 @@ pub fn view(audiorox: &AudioRox) ->  Element<Message> {
     .text_size(RADIO_TEXT_SIZE);
 
     let cond_visual = radio(
-        "Visuel",
+        tr(lang, "Visuel"),
         MethodEval::Visual,
         Some(audiorox.method),
         Message::MethodChanged,
@@ -117,7 +284,7 @@ pub fn view(audiorox: &AudioRox) ->  Element<Message> {
     .text_size(RADIO_TEXT_SIZE);
 
     let method_eval = column![
-        text("MÉTHODE D'ÉVALUATION : \nCONDITIONNEMENT").size(RADIO_TITLE_SIZE),
+        text(tr(lang, "MÉTHODE D'ÉVALUATION : \nCONDITIONNEMENT")).size(RADIO_TITLE_SIZE),
         // vertical_space(2.0),
         cond_standard,
         cond_play,
@@ -132,7 +299,7 @@ pub fn view(audiorox: &AudioRox) ->  Element<Message> {
     ///////////////////////////////////////////// standard /////////////////////////////////////////////
     // text_input for audiometer name
     let audiometer_type = row![
-        text("Audiomètre: ")
+        text(tr(lang, "Audiomètre: "))
             .size(14)
             .horizontal_alignment(Horizontal::Left),
         text_input(
@@ -147,7 +314,7 @@ pub fn view(audiorox: &AudioRox) ->  Element<Message> {
     .align_items(Alignment::Center);
 
     let anterior_thresholds_date = row![
-        text("Date seuils antérieurs (•) : ")
+        text(tr(lang, "Date seuils antérieurs (•) : "))
             .size(14)
             .horizontal_alignment(Horizontal::Left),
         text_input(
@@ -162,7 +329,7 @@ pub fn view(audiorox: &AudioRox) ->  Element<Message> {
 
     // a checkbox for adequate rest period
     let adequate_rest_period = checkbox(
-        "Repos sonore inadéquat (<16h)",
+        tr(lang, "Repos sonore inadéquat (<16h)"),
         audiorox.adequate_rest_period,
         Message::AdequateRestPeriodChanged,
     )
@@ -184,7 +351,7 @@ pub fn view(audiorox: &AudioRox) ->  Element<Message> {
     let transductor = audiorox.transductor;
 
     let intra = radio(
-        "Intra",
+        tr(lang, "Intra"),
         Transductor::Intra,
         Some(transductor),
         Message::TransductorChanged,
@@ -194,7 +361,7 @@ pub fn view(audiorox: &AudioRox) ->  Element<Message> {
     .text_size(t_size);
 
     let supra = radio(
-        "Supra",
+        tr(lang, "Supra"),
         Transductor::Supra,
         Some(transductor),
         Message::TransductorChanged,
@@ -204,7 +371,7 @@ pub fn view(audiorox: &AudioRox) ->  Element<Message> {
     .text_size(t_size);
 
     let free = radio(
-        "Haut-parleurs",
+        tr(lang, "Haut-parleurs"),
         Transductor::Free,
         Some(transductor),
         Message::TransductorChanged,
@@ -215,7 +382,7 @@ pub fn view(audiorox: &AudioRox) ->  Element<Message> {
 
     let transductor_section = column![intra, supra, free].spacing(3).width(Length::Shrink);
 
-    let transductor_title = text("ÉCOUTEURS")
+    let transductor_title = text(tr(lang, "ÉCOUTEURS"))
         .size(RADIO_TITLE_SIZE)
         .width(Length::Shrink);
 
@@ -223,89 +390,31 @@ pub fn view(audiorox: &AudioRox) ->  Element<Message> {
     ///////////////////////////////////////////// TRANSDUCTOR /////////////////////////////////////////////
 
     let (tonal_table_right, tonal_table_left) = make_tonal_tables(&audiorox);
-    let (vocal_table_right, vocal_table_left, vocal_lang, voice) = seuils_vocaux_tables(&audiorox);
-    let (id_lang_table_right, id_lang_table_left, id_lang_table_bin) =
-        identification_language(&audiorox);
-    let (tympa_table_right, tympa_table_left) = tympa(&audiorox);
-    let (stap_table_right, stap_table_left) = stap(&audiorox);
-
-    // create a header with two columns of text: on the left and one on the right
-    let text_vspace = TEXT_LINE_VSPACE;
-
-
-
-
-    let succursale = super::partners::make_succursale_element(&audiorox.partner);
-
-
-
-    let header = row![
-        horizontal_space(50.0),
-        container(
-            column![
-                text("Roxanne Bolduc")
-                    .font(super::config::FIRA)
-                    .size(30)
-                    .horizontal_alignment(Horizontal::Left),
-                text("Audiologiste")
-                    .size(20)
-                    .horizontal_alignment(Horizontal::Left),
-            ]
-            .align_items(Alignment::Center)
-            
-        ).width(Length::FillPortion(2))
-        .align_x(Horizontal::Left),
-        horizontal_space(45),
-        container(column![
-            text("ÉVALUATION AUDIOLOGIQUE")
-                .size(27)
-                .horizontal_alignment(Horizontal::Center)
-                .width(Length::Fill),
-            vertical_space(15.)
-        ])
-        .height(Length::Fill)
-        .align_x(Horizontal::Center)
-        .align_y(Vertical::Bottom)
-        .width(Length::FillPortion(3)),
-        horizontal_space(1),
-
-        column![
-            vertical_space(Length::Fixed(13.)),
-            container(row![
-                text("Date de l'évaluation : ")
-                    .size(18.0)
-                    .vertical_alignment(Vertical::Center),
-
-                column![vertical_space(text_vspace), Rule::horizontal(1.0),]
-            ])
-            .height(Length::Fixed(20.0)),
-            vertical_space(Length::Fixed(20.)),
-
-            succursale
-            .height(Length::Fixed(120. + 60.)),
-            vertical_space(Length::Fixed(2.)),
 
-        ]
+    let extended = audiorox.extended_frequencies;
+    let free_field = &audiorox.free_field;
+    let free_field_on = |chart: FreeFieldChart| (free_field.chart == chart).then_some(free_field);
 
-        .width(Length::FillPortion(2))
-    ]
-    .align_items(Alignment::Center)
-    .padding([0, 5, 0, 5])
-    .height(Length::Fixed(120. + 60.))
-    .width(Length::Fill);
-    let data1 = vec![10.0, 20.0, 30.0, 10.0, 60.0, 65.0];
-    let data2 = data1.iter().map(|x| x + 10.0).collect::<Vec<f32>>();
-    let audiogram_right =
-        container(plot(data1.clone(), Shape::Less, EarSide::Right)).align_x(Horizontal::Center);
+    let audiogram_right = container(plot(
+        &audiorox.audiogram_right,
+        free_field_on(FreeFieldChart::Right),
+        EarSide::Right,
+        extended,
+        audiorox.overlays,
+        cursor,
+        lang,
+    ))
+    .align_x(Horizontal::Center);
 
-    let immit_graph = container(im_plot()).align_x(Horizontal::Center);
-    // .style(theme::Container::Custom(Box::new(
-    //     TableContainerCustomStyle,
-    // )));
 
 
+    let dynamic_range_table = |audiogram: &Audiogram| {
+        let dynamic_range = audiogram.dynamic_range();
+        (!dynamic_range.is_empty())
+            .then(|| make_frequency_table(tr(lang, "PLAGE DYNAMIQUE - dB"), dynamic_range))
+    };
 
-    let audio_right = column![
+    let mut audio_right = column![
         audiogram_right,
         vertical_space(15.0),
         row![
@@ -316,14 +425,30 @@ pub fn view(audiorox: &AudioRox) ->  Element<Message> {
     ]
     .align_items(Alignment::Center);
 
+    if let Some(table) = dynamic_range_table(&audiorox.audiogram_right) {
+        audio_right = audio_right.push(vertical_space(10.0)).push(row![
+            horizontal_space(10.0),
+            table,
+            horizontal_space(10.0),
+        ]);
+    }
+
     // .style(theme::Container::Custom(Box::new(
     //     TableContainerCustomStyle,
     // )));
 
-    let audiorgam_left = plot(data2.clone(), Shape::X, EarSide::Left);
+    let audiorgam_left = plot(
+        &audiorox.audiogram_left,
+        free_field_on(FreeFieldChart::Left),
+        EarSide::Left,
+        extended,
+        audiorox.overlays,
+        cursor,
+        lang,
+    );
 
 
-    let audio_left = column![
+    let mut audio_left = column![
         audiorgam_left,
         vertical_space(15.0),
         row![
@@ -333,7 +458,13 @@ pub fn view(audiorox: &AudioRox) ->  Element<Message> {
     ]
     .align_items(Alignment::Center);
 
-    let legend = container(draw_legend())
+    if let Some(table) = dynamic_range_table(&audiorox.audiogram_left) {
+        audio_left = audio_left
+            .push(vertical_space(10.0))
+            .push(row![table, horizontal_space(10.0)]);
+    }
+
+    let legend = container(draw_legend(lang))
         .width(Length::Shrink);
 
     let val_and_trans = row![
@@ -359,7 +490,7 @@ pub fn view(audiorox: &AudioRox) ->  Element<Message> {
             vertical_space(5.0),
             row![horizontal_space(8.0), method_eval],
             vertical_space(2.0),
-            text("Normes ANSI S3 en vigueur").size(RADIO_TEXT_SIZE),
+            text(tr(lang, "Normes ANSI S3 en vigueur")).size(RADIO_TEXT_SIZE),
             vertical_space(2.0),
             standard_container,
         ])
@@ -373,31 +504,61 @@ pub fn view(audiorox: &AudioRox) ->  Element<Message> {
 
     let mid_audiograph = container(mid_col).width(Length::Shrink);
 
-    let tonal_audiogram_title = make_title("AUDIOMÉTRIE TONALE");
+    // without results by ear, a child is only tested in sound field
+    let sound_field_only = audiorox.paediatric.enabled && !audiorox.ear_specific();
+    let functional_gain = free_field.functional_gain();
 
-    let tonal_audiogram_title_container = container(tonal_audiogram_title)
-        .width(Length::Fill)
-        .style(theme::Container::Custom(Box::new(
-            TitleContainerCustomStyle,
-        )));
-
-    let vocal_audiogram_title = make_title("AUDIOMÉTRIE VOCALE");
-    let immitance_title = make_title("IMMITANCEMÉTRIE");
+    let tonal_audiogram_title = if sound_field_only {
+        make_single_title(tr(lang, "AUDIOMÉTRIE EN CHAMP LIBRE"))
+    } else {
+        make_title(tr(lang, "AUDIOMÉTRIE TONALE"), lang)
+    };
 
-    let vocal_audiogram_title_container = container(vocal_audiogram_title)
+    let tonal_audiogram_title_container = container(tonal_audiogram_title)
         .width(Length::Fill)
         .style(theme::Container::Custom(Box::new(
             TitleContainerCustomStyle,
         )));
 
-    let immitance_title_container =
-        container(immitance_title)
-            .width(Length::Fill)
-            .style(theme::Container::Custom(Box::new(
-                TitleContainerCustomStyle,
-            )));
-
-
+    if sound_field_only {
+        let gain: Element<'_, Message> = if functional_gain.is_empty() {
+            horizontal_space(PLOT_CANVAS_WIDTH).into()
+        } else {
+            make_frequency_table(tr(lang, "GAIN FONCTIONNEL - dB"), functional_gain)
+        };
+        let sound_field = plot(
+            &Audiogram::default(),
+            Some(free_field),
+            EarSide::Free,
+            extended,
+            audiorox.overlays,
+            cursor,
+            lang,
+        );
+
+        return column![
+            row![
+                horizontal_space(SECTION_TITLE_HORIZONTAL_SPACE),
+                tonal_audiogram_title_container,
+                horizontal_space(SECTION_TITLE_HORIZONTAL_SPACE),
+            ],
+            row![
+                container(sound_field)
+                    .width(Length::FillPortion(1))
+                    .align_x(Horizontal::Right),
+                container(mid_audiograph)
+                    .width(Length::Shrink)
+                    .align_x(Horizontal::Center),
+                horizontal_space(6),
+                container(gain)
+                    .width(Length::FillPortion(1))
+                    .align_x(Horizontal::Left),
+            ]
+            .align_items(Alignment::Center),
+        ]
+        .align_items(Alignment::Center)
+        .into();
+    }
 
     let audiograms = column![
         row![
@@ -420,9 +581,45 @@ pub fn view(audiorox: &AudioRox) ->  Element<Message> {
     ]
     .align_items(Alignment::Center);
 
-    let tonal_audiogram_content = column![header, audiograms];
+    // the sound-field thresholds of a child are entered on the binaural chart
+    if free_field.is_empty() && !audiorox.paediatric.enabled {
+        return audiograms.into();
+    }
+
+    let mut free_field_row = row![].spacing(10).align_items(Alignment::Center);
+    if let Some(free_field) = free_field_on(FreeFieldChart::Binaural) {
+        free_field_row = free_field_row.push(plot(
+            &Audiogram::default(),
+            Some(free_field),
+            EarSide::Free,
+            extended,
+            audiorox.overlays,
+            cursor,
+            lang,
+        ));
+    }
+    if !functional_gain.is_empty() {
+        let table = make_frequency_table(tr(lang, "GAIN FONCTIONNEL - dB"), functional_gain);
+        free_field_row = free_field_row.push(container(table).width(Length::Fixed(PLOT_CANVAS_WIDTH)));
+    }
 
+    column![audiograms, free_field_row]
+        .spacing(10)
+        .align_items(Alignment::Center)
+        .into()
+}
+
+fn vocal_audiometry(audiorox: &AudioRox) -> Element<'_, Message> {
+    let lang = audiorox.report_language;
+    let (vocal_table_right, vocal_table_left, vocal_lang, voice) = seuils_vocaux_tables(&audiorox);
 
+    let vocal_audiogram_title = make_title(tr(lang, "AUDIOMÉTRIE VOCALE"), lang);
+
+    let vocal_audiogram_title_container = container(vocal_audiogram_title)
+        .width(Length::Fill)
+        .style(theme::Container::Custom(Box::new(
+            TitleContainerCustomStyle,
+        )));
 
     let vocal_tables = row![
         horizontal_space(10),
@@ -441,6 +638,156 @@ pub fn view(audiorox: &AudioRox) ->  Element<Message> {
     .width(Length::Shrink)
     .align_items(Alignment::Center);
 
+    let vocal_audiogram_content = column![
+        row![
+            horizontal_space(SECTION_TITLE_HORIZONTAL_SPACE),
+            vocal_audiogram_title_container,
+            horizontal_space(SECTION_TITLE_HORIZONTAL_SPACE),
+        ],
+
+        vertical_space(Length::Fixed(SPACE_BELOW_SECTION_TITLE)),
+        vocal_tables,
+
+    ]
+    .align_items(Alignment::Center);
+
+    vocal_audiogram_content.into()
+}
+
+/// Summary of the screening of a worker exposed to noise: the shift of each ear from
+/// its baseline, with the standard threshold shifts and those that are recordable.
+fn noise_screening(audiorox: &AudioRox, on_screen: bool) -> Element<'_, Message> {
+    let lang = audiorox.report_language;
+    let screening = &audiorox.screening;
+
+    let title = container(make_title(tr(lang, "DÉPISTAGE — BRUIT"), lang))
+        .width(Length::Fill)
+        .style(theme::Container::Custom(Box::new(TitleContainerCustomStyle)));
+
+    let ear_summary = |ear: EarSide, audiogram: &Audiogram| -> Element<'_, Message> {
+        if screening.baseline.is_none() {
+            return text("").into();
+        }
+        let Some(shift) = screening.shift(ear, &audiogram.air) else {
+            return text(tr(lang, "Seuils à 2, 3 et 4 kHz incomplets"))
+                .size(16)
+                .into();
+        };
+
+        let mut lines = column![
+            text(format!("{} {:.1} dB HL", tr(lang, "Référence (2-3-4 kHz) :"), shift.baseline)).size(16),
+            text(format!("{} {:.1} dB HL", tr(lang, "Actuel (2-3-4 kHz) :"), shift.current)).size(16),
+        ]
+        .spacing(2);
+        if let Some(correction) = shift.age_correction {
+            lines = lines.push(text(format!("{} {:.1} dB", tr(lang, "Correction selon l'âge :"), correction)).size(16));
+        }
+        lines = lines.push(text(format!("{} {:.1} dB", tr(lang, "Écart :"), shift.shift)).size(16));
+
+        let flag = if shift.recordable {
+            text(tr(lang, "DTS ENREGISTRABLE")).style(iced::Color::from_rgb(0.8, 0.1, 0.1))
+        } else if shift.sts {
+            text(tr(lang, "DTS")).style(iced::Color::from_rgb(0.85, 0.45, 0.0))
+        } else {
+            text(tr(lang, "Aucun DTS"))
+        };
+        lines.push(flag.size(18)).into()
+    };
+
+    let baseline = match &screening.baseline {
+        Some(baseline) => format!("{} {}", tr(lang, "Audiogramme de référence du"), baseline.date),
+        None => tr(lang, "Aucun audiogramme de référence").to_string(),
+    };
+
+    let mut middle = column![
+        text(baseline).size(16),
+        row![
+            text(tr(lang, "Dossier :")).size(16),
+            text_input("", &screening.file_number)
+                .on_input(Message::ScreeningFileNumberChanged)
+                .size(DEFAULT_TEXT_INPUT_CONTENT_SIZE)
+                .width(Length::Fixed(90.0)),
+            horizontal_space(10),
+            text(tr(lang, "Naissance :")).size(16),
+            text_input(tr(lang, "AAAA-MM-JJ"), &screening.birth_date)
+                .on_input(Message::ScreeningBirthDateChanged)
+                .size(DEFAULT_TEXT_INPUT_CONTENT_SIZE)
+                .width(Length::Fixed(110.0)),
+        ]
+        .spacing(5)
+        .align_items(Alignment::Center),
+        row![
+            text(tr(lang, "Âge :")).size(16),
+            text_input("", &screening.age)
+                .on_input(Message::ScreeningAgeChanged)
+                .size(DEFAULT_TEXT_INPUT_CONTENT_SIZE)
+                .width(Length::Fixed(50.0)),
+            horizontal_space(10),
+            radio(tr(lang, "Homme"), Sex::Male, Some(screening.sex), Message::ScreeningSexChanged)
+                .size(RADIO_SIZE)
+                .text_size(RADIO_TEXT_SIZE),
+            radio(tr(lang, "Femme"), Sex::Female, Some(screening.sex), Message::ScreeningSexChanged)
+                .size(RADIO_SIZE)
+                .text_size(RADIO_TEXT_SIZE),
+        ]
+        .spacing(5)
+        .align_items(Alignment::Center),
+        checkbox(tr(lang, "Correction selon l'âge"), screening.age_correction, Message::AgeCorrectionChanged)
+            .size(RADIO_SIZE)
+            .text_size(RADIO_TEXT_SIZE),
+        text(format!(
+            "{} {} dB, {} {} dB HL",
+            tr(lang, "DTS : écart moyen d'au moins"),
+            STS_SHIFT,
+            tr(lang, "enregistrable à partir de"),
+            RECORDABLE_LEVEL
+        ))
+        .size(12),
+    ]
+    .spacing(5)
+    .align_items(Alignment::Center);
+    if on_screen {
+        middle = middle.push(
+            row![
+                button(text(tr(lang, "Définir comme référence")).size(14)).on_press(Message::SaveBaseline),
+                button(text(tr(lang, "Charger la référence")).size(14)).on_press(Message::LoadBaseline),
+            ]
+            .spacing(5),
+        );
+    }
+
+    let content = row![
+        horizontal_space(10),
+        container(ear_summary(EarSide::Right, &audiorox.audiogram_right))
+            .width(Length::FillPortion(4))
+            .center_x(),
+        horizontal_space(10),
+        container(middle).width(Length::FillPortion(4)).center_x(),
+        horizontal_space(10),
+        container(ear_summary(EarSide::Left, &audiorox.audiogram_left))
+            .width(Length::FillPortion(4))
+            .center_x(),
+        horizontal_space(10),
+    ]
+    .align_items(Alignment::Center);
+
+    column![
+        row![
+            horizontal_space(SECTION_TITLE_HORIZONTAL_SPACE),
+            title,
+            horizontal_space(SECTION_TITLE_HORIZONTAL_SPACE),
+        ],
+        vertical_space(Length::Fixed(SPACE_BELOW_SECTION_TITLE)),
+        content,
+    ]
+    .align_items(Alignment::Center)
+    .into()
+}
+
+fn speech_identification(audiorox: &AudioRox) -> Element<'_, Message> {
+    let (id_lang_table_right, id_lang_table_left, id_lang_table_bin) =
+        identification_language(&audiorox);
+
     let id_lang_tables = row![
         horizontal_space(10),
         container(id_lang_table_right).width(Length::FillPortion(4)),
@@ -455,21 +802,27 @@ pub fn view(audiorox: &AudioRox) ->  Element<Message> {
     .width(Length::Shrink)
     .align_items(Alignment::Center);
 
-    let vocal_audiogram_content = column![
-        row![
-            horizontal_space(SECTION_TITLE_HORIZONTAL_SPACE),
-            vocal_audiogram_title_container,
-            horizontal_space(SECTION_TITLE_HORIZONTAL_SPACE),
-        ],
+    id_lang_tables.into()
+}
 
-        vertical_space(Length::Fixed(SPACE_BELOW_SECTION_TITLE)),
-        vocal_tables,
+fn immittance(audiorox: &AudioRox) -> Element<'_, Message> {
+    let lang = audiorox.report_language;
+    let (tympa_table_right, tympa_table_left) = tympa(&audiorox);
+    let (stap_table_right, stap_table_left) = stap(&audiorox);
 
-    ]
-    .align_items(Alignment::Center);
+    let immit_graph = container(im_plot(lang)).align_x(Horizontal::Center);
+
+    let immitance_title = make_title(tr(lang, "IMMITANCEMÉTRIE"), lang);
+
+    let immitance_title_container =
+        container(immitance_title)
+            .width(Length::Fill)
+            .style(theme::Container::Custom(Box::new(
+                TitleContainerCustomStyle,
+            )));
 
     let tympanometer_type = row![
-        text("Tympanomètre: ")
+        text(tr(lang, "Tympanomètre: "))
             .size(16)
             .horizontal_alignment(Horizontal::Left),
         text_input(
@@ -515,85 +868,123 @@ pub fn view(audiorox: &AudioRox) ->  Element<Message> {
         tympa_content
     ];
 
+    immitance_content.into()
+}
+
+/// Notes, CC, logo of the order and signature.
+fn conclusion(audiorox: &AudioRox, on_screen: bool) -> Element<'_, Message> {
+    let lang = audiorox.report_language;
     //
     let note_vspace = 16.0;
-    let note_line = container(row![
-        text("Notes: ")
+    let notes = &audiorox.notes;
+    let mut note_line = row![
+        text(tr(lang, "Notes : "))
             .size(note_vspace)
             .vertical_alignment(Vertical::Center),
+    ];
+    if on_screen {
+        let count = if notes.overflow() > 0 {
+            text(format!("{} {}", notes.overflow(), tr(lang, "caractères ne seront pas imprimés")))
+                .style(iced::Color::from_rgb(0.8, 0.1, 0.1))
+        } else {
+            text(format!("{} {}", notes.remaining(), tr(lang, "caractères restants")))
+        };
+        note_line = note_line
+            .push(horizontal_space(Length::Fill))
+            .push(count.size(12).vertical_alignment(Vertical::Center));
+    }
 
-    ])
-    .align_y(Vertical::Top);
-
-
-    let note = column![
-        note_line,
-        vertical_space(Length::Fixed(TEXT_LINE_VSPACE - 10.0)),
-        Rule::horizontal(1.),
-        vertical_space(Length::Fixed(TEXT_LINE_VSPACE)),
-        Rule::horizontal(1.),
-        vertical_space(Length::Fixed(TEXT_LINE_VSPACE)),
-        Rule::horizontal(1.),
-        vertical_space(Length::Fixed(10.0)),
-        text("Voir rapport audiologique complet ci-joint.").size(note_vspace),
-        text("Évaluation globale des besoins faite.").size(note_vspace),
-    ]
-    .width(Length::Fixed(450.0));
+    let mut note = column![
+        container(note_line).align_y(Vertical::Top),
+        vertical_space(Length::Fixed(5.0)),
+    ];
+
+    // each line of the notes takes the place of a ruled line, left ruled when empty
+    for index in 0..NOTE_LINES {
+        let line: Element<'_, Message> = if on_screen {
+            text_input("", notes.line(index))
+                .id(notes::input_id(index))
+                .on_input(move |value| Message::NoteChanged(index, value))
+                .on_submit(Message::NoteSubmitted(index))
+                .size(note_vspace)
+                .padding(1)
+                .into()
+        } else if notes.line(index).is_empty() {
+            Rule::horizontal(1.).into()
+        } else {
+            text(notes.printed_line(index)).size(note_vspace).into()
+        };
+        note = note.push(
+            container(line)
+                .width(Length::Fill)
+                .height(Length::Fixed(TEXT_LINE_VSPACE + 1.0))
+                .align_y(Vertical::Bottom),
+        );
+    }
+    note = note.push(vertical_space(Length::Fixed(10.0)));
+
+    for sentence in STANDARD_SENTENCES {
+        if on_screen {
+            note = note.push(
+                checkbox(tr(lang, sentence.text()), notes.has_sentence(sentence), move |checked| {
+                    Message::StandardSentenceChanged(sentence, checked)
+                })
+                .size(note_vspace)
+                .text_size(note_vspace),
+            );
+        } else if notes.has_sentence(sentence) {
+            note = note.push(text(tr(lang, sentence.text())).size(note_vspace));
+        }
+    }
+    let note = note.width(Length::Fixed(450.0));
 
-    let cc = column![
+    let mut cc = column![
         text("CC").size(note_vspace),
         vertical_space(5.0),
-        checkbox("Patient", audiorox.cc.patient, Message::CCPatientChanged)
-            .size(note_vspace)
-            .text_size(note_vspace),
-        checkbox(
-            "Audioprothésiste",
-            audiorox.cc.audioprothesiste,
-            Message::CCAudioProChanged
-        )
-        .size(note_vspace)
-        .text_size(note_vspace),
-        checkbox(
-            "Médecin de famille",
-            audiorox.cc.family_doctor,
-            Message::CCFamilyDocChanged
-        )
-        .size(note_vspace)
-        .text_size(note_vspace),
-        checkbox(
-            "Centre de réadaptation",
-            audiorox.cc.readapt,
-            Message::CCReadapt
-        ).size(note_vspace)
-        .text_size(note_vspace),
-        checkbox("ORL", audiorox.cc.orl, Message::CCORLChanged)
-            .size(note_vspace)
-            .text_size(note_vspace),
-        checkbox(
-            "_____________________",
-            audiorox.cc.other,
-            Message::CCOtherChanged
-        )
-        .size(note_vspace)
-        .text_size(note_vspace),
     ]
     .spacing(2);
+    for category in CC_CATEGORIES {
+        let recipient = audiorox.cc.recipient(category);
+        // the other recipient is named, or left blank to be filled by hand
+        let label = match category {
+            CCCategory::Other if recipient.name.is_empty() => "_____________________",
+            CCCategory::Other => recipient.name.as_str(),
+            _ => tr(lang, category.label()),
+        };
+        cc = cc.push(
+            checkbox(label, recipient.selected, move |value| Message::CCChanged(category, value))
+                .size(note_vspace)
+                .text_size(note_vspace),
+        );
+    }
 
-    let logo_ordre = column![
-        vertical_space(1.0),
-        container(
-            iced::widget::image::Image::new("images/ordre256.jpg").width(150) 
-        )
-        .width(Length::Fixed(150.))
-    ];
+    let examiner = &audiorox.examiner;
+    let mut logo_ordre = column![vertical_space(1.0)].width(Length::Fixed(150.));
+    if !examiner.order_logo.is_empty() {
+        logo_ordre = logo_ordre.push(
+            container(image(examiner.order_logo.as_str()).width(150)).width(Length::Fixed(150.)),
+        );
+    }
+
+    let signed: Element<'_, Message> = if examiner.signature_image.is_empty() {
+        vertical_space(40.0).into()
+    } else {
+        row![image(examiner.signature_image.as_str()).height(40), horizontal_space(25.)].into()
+    };
+    let name = if examiner.credentials.is_empty() {
+        examiner.name.clone()
+    } else {
+        format!("{}  {},", examiner.name, examiner.credentials)
+    };
+    let licence = format!("{} {}", tr(lang, &examiner.title), examiner.licence);
 
     let signature = row![
 
         column![
-            vertical_space(40.0),
+            signed,
             Rule::horizontal(1.),
             row![
-                text("Roxanne Bolduc  MPA,\nAudiologiste OOAQ #4182").size(22),
+                text(format!("{}\n{}", name, licence.trim())).size(22),
                 horizontal_space(25.)
             ],
         ]
@@ -613,27 +1004,489 @@ pub fn view(audiorox: &AudioRox) ->  Element<Message> {
     ]
     .align_items(Alignment::End);
 
-    let content = column![
-        tonal_audiogram_content,
-        vertical_space(SECTION_SEPARATOR_SPACE),
-        vocal_audiogram_content,
-        vertical_space(8),
-        id_lang_tables,
-        vertical_space(SECTION_SEPARATOR_SPACE),
-        immitance_content,
-        bottom_content // .style(theme::Container::Custom(Box::new(TableTitleCustomStyle,)))
-    ];
+    bottom_content.into()
+}
 
-    let final_content = container(content.align_items(Alignment::Center))
+/// Revision, signer and hash of a finalized report, printed at the bottom of the page.
+fn signature_stamp(audiorox: &AudioRox) -> Option<Element<'_, Message>> {
+    let lang = audiorox.report_language;
+    let signature = audiorox.signature.as_ref()?;
+
+    let signed_at = chrono::DateTime::parse_from_rfc3339(&signature.signed_at)
+        .map(|date| date.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|_| signature.signed_at.clone());
+    let mut stamp = format!(
+        "{} {} — {} {} — {}",
+        tr(lang, "Rapport finalisé, révision"),
+        signature.revision,
+        tr(lang, "signé par"),
+        signature.signed_by,
+        signed_at
+    );
+    if let Some(amends) = &signature.amends {
+        stamp = format!("{} — {} {}", stamp, tr(lang, "amende"), &amends[..amends.len().min(16)]);
+    }
+
+    Some(
+        column![
+            text(stamp).size(11),
+            text(format!("SHA-256 {}", signature.hash)).size(11),
+        ]
+        .align_items(Alignment::Center)
+        .padding([0, 20, 5, 20])
+        .into(),
+    )
+}
+
+fn report_footer<'a>(audiorox: &'a AudioRox, template: &ReportTemplate) -> Option<Element<'a, Message>> {
+    let footer = match template.footer {
+        ReportFooter::None => return None,
+        ReportFooter::Succursale => {
+            let (clinic, succursale) = get_chosen_succursale(&audiorox.partner);
+            if clinic.is_empty() {
+                return None;
+            }
+            format!("{} — {}", clinic, succursale)
+        }
+    };
+
+    Some(
+        column![
+            vertical_space(10.0),
+            Rule::horizontal(1.),
+            text(footer)
+                .size(13)
+                .horizontal_alignment(Horizontal::Center)
+                .width(Length::Fill),
+        ]
+        .padding([0, 20, 5, 20])
+        .into(),
+    )
+}
+
+/// Evaluation of a child: the stimulus of the sound-field thresholds, the speech test
+/// and the reliability of the responses. For the parents and the school, the hearing of
+/// each ear is put in plain words, followed by recommendations for the class.
+fn paediatric(audiorox: &AudioRox, on_screen: bool) -> Element<'_, Message> {
+    let lang = audiorox.report_language;
+    let paediatric = &audiorox.paediatric;
+    let for_parents = paediatric.for_parents();
+    let ear_specific = audiorox.ear_specific();
+
+    let title = container(make_title(tr(lang, "ÉVALUATION PÉDIATRIQUE"), lang))
         .width(Length::Fill)
-        // .height(Length::Fill)
-        ;
+        .style(theme::Container::Custom(Box::new(TitleContainerCustomStyle)));
+
+    let hearing = |label: &str, audiogram: &Audiogram| -> Element<'_, Message> {
+        match plain_hearing(&audiogram.air) {
+            Some(hearing) if ear_specific => column![
+                text(tr(lang, label)).size(14),
+                text(tr(lang, hearing)).size(18),
+            ]
+            .align_items(Alignment::Center)
+            .into(),
+            _ => text("").into(),
+        }
+    };
+
+    let mut middle = column![].spacing(5).align_items(Alignment::Center);
+    if on_screen {
+        middle = middle
+            .push(
+                row![
+                    text(tr(lang, "Stimulus en champ libre :")).size(14),
+                    pick_list(
+                        Translated::all(&STIMULI, lang),
+                        Some(Translated { value: paediatric.stimulus, language: lang }),
+                        |stimulus| Message::StimulusChanged(stimulus.value)
+                    )
+                    .text_size(14),
+                ]
+                .spacing(5)
+                .align_items(Alignment::Center),
+            )
+            .push(
+                row![
+                    text(tr(lang, "Épreuve vocale :")).size(14),
+                    pick_list(&SPEECH_TESTS[..], Some(paediatric.speech_test), Message::SpeechTestChanged)
+                        .text_size(14),
+                ]
+                .spacing(5)
+                .align_items(Alignment::Center),
+            )
+            .push(
+                row![
+                    text(tr(lang, "Rapport destiné :")).size(14),
+                    pick_list(&AUDIENCES[..], Some(paediatric.audience), Message::AudienceChanged).text_size(14),
+                ]
+                .spacing(5)
+                .align_items(Alignment::Center),
+            );
+    } else {
+        // each stimulus with the frequencies of the thresholds obtained with it
+        let stimuli: Vec<String> = paediatric
+            .frequencies_by_stimulus()
+            .into_iter()
+            .map(|(stimulus, frequencies)| {
+                let stimulus = tr(lang, &stimulus.to_string()).to_string();
+                if frequencies.is_empty() {
+                    return stimulus;
+                }
+                let frequencies: Vec<String> = frequencies.iter().map(|frequency| frequency.to_string()).collect();
+                format!("{} ({} Hz)", stimulus, frequencies.join(", "))
+            })
+            .collect();
+        middle = middle.push(
+            text(format!("{} {}", tr(lang, "Stimulus en champ libre :"), stimuli.join(", "))).size(16),
+        );
+        if paediatric.speech_test != SpeechTest::None {
+            middle = middle.push(
+                text(format!(
+                    "{} {}",
+                    tr(lang, "Épreuve vocale :"),
+                    tr(lang, &paediatric.speech_test.to_string())
+                ))
+                .size(16),
+            );
+        }
+    }
+    if let Some(hearing) = plain_hearing(&audiorox.free_field.unaided).filter(|_| for_parents) {
+        middle = middle.push(
+            text(format!("{} {}", tr(lang, "Champ libre, meilleure oreille :"), tr(lang, hearing))).size(16),
+        );
+    }
+
+    let mut reliability = column![text(tr(lang, "Fiabilité des réponses :")).size(14)].spacing(3);
+    for note in RELIABILITY_NOTES {
+        if on_screen {
+            reliability = reliability.push(
+                checkbox(tr(lang, note.text()), paediatric.has_note(note), move |checked| {
+                    Message::ReliabilityNoteChanged(note, checked)
+                })
+                .size(RADIO_SIZE)
+                .text_size(RADIO_TEXT_SIZE),
+            );
+        } else if paediatric.has_note(note) {
+            reliability = reliability.push(text(tr(lang, note.text())).size(14));
+        }
+    }
+    middle = middle.push(reliability);
+
+    let content = row![
+        horizontal_space(10),
+        container(hearing("Oreille droite", &audiorox.audiogram_right))
+            .width(Length::FillPortion(4))
+            .center_x(),
+        horizontal_space(10),
+        container(middle).width(Length::FillPortion(5)).center_x(),
+        horizontal_space(10),
+        container(hearing("Oreille gauche", &audiorox.audiogram_left))
+            .width(Length::FillPortion(4))
+            .center_x(),
+        horizontal_space(10),
+    ]
+    .align_items(Alignment::Center);
+
+    let mut section = column![
+        row![
+            horizontal_space(SECTION_TITLE_HORIZONTAL_SPACE),
+            title,
+            horizontal_space(SECTION_TITLE_HORIZONTAL_SPACE),
+        ],
+        vertical_space(Length::Fixed(SPACE_BELOW_SECTION_TITLE)),
+        content,
+    ]
+    .align_items(Alignment::Center);
+
+    if for_parents {
+        let mut recommendations = column![text(tr(lang, "Recommandations pour la classe :")).size(16)].spacing(3);
+        for recommendation in SCHOOL_RECOMMENDATIONS {
+            if on_screen {
+                recommendations = recommendations.push(
+                    checkbox(
+                        tr(lang, recommendation.text()),
+                        paediatric.has_recommendation(recommendation),
+                        move |checked| Message::SchoolRecommendationChanged(recommendation, checked),
+                    )
+                    .size(RADIO_SIZE)
+                    .text_size(RADIO_TEXT_SIZE),
+                );
+            } else if paediatric.has_recommendation(recommendation) {
+                recommendations = recommendations.push(text(format!("• {}", tr(lang, recommendation.text()))).size(16));
+            }
+        }
+        section = section
+            .push(vertical_space(10))
+            .push(row![horizontal_space(40), recommendations.width(Length::Fill)]);
+    }
+
+    section.into()
+}
+
+/// Percentage of hearing loss of each ear and of both ears, computed from the air
+/// conduction thresholds with the chosen formula.
+fn hearing_handicap(audiorox: &AudioRox, on_screen: bool) -> Element<'_, Message> {
+    let lang = audiorox.report_language;
+    let formula = audiorox.handicap.formula;
+    let result = handicap::compute(
+        formula,
+        &audiorox.settings.bareme,
+        &audiorox.audiogram_right.air,
+        &audiorox.audiogram_left.air,
+    );
+
+    let title = container(make_title(tr(lang, "HANDICAP AUDITIF"), lang))
+        .width(Length::Fill)
+        .style(theme::Container::Custom(Box::new(TitleContainerCustomStyle)));
+
+    let frequencies: Vec<String> = match formula {
+        Formula::Ama => AMA_FREQUENCIES.to_vec(),
+        Formula::Bareme => audiorox.settings.bareme.frequencies.clone(),
+    }
+    .iter()
+    .map(|frequency| format!("{}", *frequency as f32 / 1000.0))
+    .collect();
+
+    let ear_summary = |average: Option<f32>, monaural: Option<f32>| -> Element<'_, Message> {
+        let (Some(average), Some(monaural)) = (average, monaural) else {
+            return text(format!("{} {} kHz", tr(lang, "Seuils incomplets à"), frequencies.join("-")))
+                .size(16)
+                .into();
+        };
+        column![
+            text(format!("{} {:.1} dB HL", tr(lang, "Moyenne :"), average)).size(16),
+            text(format!("{} {:.1} %", tr(lang, "Déficit monaural :"), monaural)).size(18),
+        ]
+        .spacing(2)
+        .align_items(Alignment::Center)
+        .into()
+    };
+
+    let formula_line: Element<'_, Message> = if on_screen {
+        row![
+            text(tr(lang, "Formule :")).size(14),
+            pick_list(&FORMULAS[..], Some(formula), Message::FormulaChanged).text_size(14),
+        ]
+        .spacing(5)
+        .align_items(Alignment::Center)
+        .into()
+    } else {
+        text(format!("{} {}", tr(lang, "Formule :"), tr(lang, &formula.to_string()))).size(14).into()
+    };
+
+    let middle = column![
+        text(match result.binaural {
+            Some(binaural) => format!("{} {:.1} %", tr(lang, "Déficit binaural :"), binaural),
+            None => String::new(),
+        })
+        .size(18),
+        formula_line,
+        text(format!("{} {} kHz", tr(lang, "Fréquences :"), frequencies.join("-"))).size(12),
+    ]
+    .spacing(5)
+    .align_items(Alignment::Center);
+
+    let content = row![
+        horizontal_space(10),
+        container(ear_summary(result.average_right, result.monaural_right))
+            .width(Length::FillPortion(4))
+            .center_x(),
+        horizontal_space(10),
+        container(middle).width(Length::FillPortion(4)).center_x(),
+        horizontal_space(10),
+        container(ear_summary(result.average_left, result.monaural_left))
+            .width(Length::FillPortion(4))
+            .center_x(),
+        horizontal_space(10),
+    ]
+    .align_items(Alignment::Center);
+
+    column![
+        row![
+            horizontal_space(SECTION_TITLE_HORIZONTAL_SPACE),
+            title,
+            horizontal_space(SECTION_TITLE_HORIZONTAL_SPACE),
+        ],
+        vertical_space(Length::Fixed(SPACE_BELOW_SECTION_TITLE)),
+        content,
+    ]
+    .align_items(Alignment::Center)
+    .into()
+}
+
+pub fn toolbar(audiorox: &AudioRox) -> Element<'_, Message> {
+    let ui = audiorox.settings.ui_language;
+
+    let finalization: Element<'_, Message> = match &audiorox.signature {
+        Some(signature) => row![
+            text(format!("{} {}", tr(ui, "Finalisé, révision"), signature.revision)).size(14),
+            button(text(tr(ui, "Amender")).size(14)).on_press(Message::AmendReport),
+        ]
+        .spacing(5)
+        .align_items(Alignment::Center)
+        .into(),
+        None => button(text(tr(ui, "Finaliser")).size(14))
+            .on_press(Message::ConfirmFinalize)
+            .into(),
+    };
+
+    let export = row![
+        button(text(tr(ui, "Aperçu avant impression")).size(14)).on_press(Message::ShowPrintPreview),
+        button(text(tr(ui, "Exporter PNG")).size(14)).on_press(Message::ExportPng),
+        pick_list(
+            &EXPORT_DPIS[..],
+            Some(audiorox.export_dpi),
+            Message::ExportDpiChanged
+        )
+        .text_size(14),
+        button(text(tr(ui, "Exporter FHIR")).size(14)).on_press(Message::ExportFhir),
+        horizontal_space(20),
+        text(tr(ui, "Examinateur :")).size(14),
+        pick_list(
+            &audiorox.settings.clinicians[..],
+            Some(audiorox.examiner.clone()),
+            Message::ExaminerChanged
+        )
+        .text_size(14),
+        button(text(tr(ui, "Profils")).size(14)).on_press(Message::ShowClinicians),
+        checkbox(tr(ui, "Dépistage bruit"), audiorox.screening.enabled, Message::ScreeningChanged)
+            .size(14)
+            .text_size(14),
+        checkbox(tr(ui, "Mode pédiatrique"), audiorox.paediatric.enabled, Message::PaediatricChanged)
+            .size(14)
+            .text_size(14),
+        checkbox(tr(ui, "Handicap auditif"), audiorox.handicap.enabled, Message::HandicapChanged)
+            .size(14)
+            .text_size(14),
+        horizontal_space(20),
+        finalization,
+        button(text(tr(ui, "Vérifier")).size(14)).on_press(Message::VerifyReport),
+        button(text(tr(ui, "Destinataires")).size(14)).on_press(Message::ShowRecipients),
+        button(text(tr(ui, "Envoyer")).size(14)).on_press(Message::ShowDelivery),
+        horizontal_space(Length::Fill),
+        text(tr(ui, "Thème :")).size(14),
+        pick_list(
+            &THEME_VARIANTS[..],
+            Some(audiorox.settings.theme),
+            Message::ThemeChanged
+        )
+        .text_size(14),
+        text(tr(ui, "Rapport :")).size(14),
+        pick_list(
+            &LANGUAGES[..],
+            Some(audiorox.report_language),
+            Message::ReportLanguageChanged
+        )
+        .text_size(14),
+        text(tr(ui, "Interface :")).size(14),
+        pick_list(
+            &LANGUAGES[..],
+            Some(audiorox.settings.ui_language),
+            Message::UiLanguageChanged
+        )
+        .text_size(14),
+    ]
+    .spacing(5)
+    .align_items(Alignment::Center);
+
+    let overlays = audiorox.overlays;
+    let audiogram = row![
+        checkbox(
+            tr(ui, "Hautes fréquences"),
+            audiorox.extended_frequencies,
+            Message::ExtendedFrequenciesChanged
+        )
+        .size(14)
+        .text_size(14),
+        text(tr(ui, "Champ libre :")).size(14),
+        pick_list(
+            Translated::all(&FREE_FIELD_CHARTS, ui),
+            Some(Translated { value: audiorox.free_field.chart, language: ui }),
+            |chart| Message::FreeFieldChartChanged(chart.value)
+        )
+        .text_size(14),
+        text(tr(ui, "Modèle :")).size(14),
+        pick_list(
+            audiorox.settings.templates.choices(),
+            Some(TemplateChoice::of(&audiorox.template)),
+            Message::TemplateChanged
+        )
+        .text_size(14),
+        checkbox(tr(ui, "Degrés de perte"), overlays.degree_of_loss, Message::DegreeOfLossChanged)
+            .size(14)
+            .text_size(14),
+        checkbox(tr(ui, "Banane de la parole"), overlays.speech_banana, Message::SpeechBananaChanged)
+            .size(14)
+            .text_size(14),
+        checkbox(tr(ui, "Sons familiers"), overlays.familiar_sounds, Message::FamiliarSoundsChanged)
+            .size(14)
+            .text_size(14),
+        horizontal_space(Length::Fill),
+        text(tr(ui, "Zoom :")).size(14),
+        button(text("-").size(14)).on_press(Message::ZoomOut),
+        text(format!("{:.0} %", audiorox.settings.zoom * 100.0)).size(14),
+        button(text("+").size(14)).on_press(Message::ZoomIn),
+        button(text(tr(ui, "Raccourcis")).size(14)).on_press(Message::ShowShortcuts),
+    ]
+    .spacing(5)
+    .align_items(Alignment::Center);
+
+    container(column![export, audiogram].spacing(3))
+        .padding([3, 5, 3, 5])
+        .width(Length::Fill)
+        .into()
+}
+
+pub fn print_preview<'a>(audiorox: &'a AudioRox, preview: &'a PrintPreview) -> Element<'a, Message> {
+    let setup = audiorox.page_setup;
+    let ui = audiorox.settings.ui_language;
+
+    let page_setup = row![
+        text(tr(ui, "Aperçu avant impression")).size(16),
+        horizontal_space(10),
+        pick_list(&PAPER_SIZES[..], Some(setup.paper), Message::PaperSizeChanged).text_size(14),
+        pick_list(&MARGINS[..], Some(setup.margin), Message::MarginChanged).text_size(14),
+        checkbox(tr(ui, "Ajuster à une page"), setup.fit_to_page, Message::FitToPageChanged)
+            .size(14)
+            .text_size(14),
+        text_input("Patient", &audiorox.patient_name)
+            .on_input(Message::PatientNameChanged)
+            .size(14)
+            .width(Length::Fixed(180.)),
+        horizontal_space(Length::Fill),
+        button(text(tr(ui, "Imprimer")).size(14)).on_press(Message::PrintReport),
+        button(text(tr(ui, "Enregistrer PDF")).size(14)).on_press(Message::ExportPdf),
+        button(text(tr(ui, "Fermer")).size(14)).on_press(Message::ClosePrintPreview),
+    ]
+    .spacing(5)
+    .align_items(Alignment::Center);
+
+    let pages = container(preview_pages(preview))
+        .padding(15)
+        .width(Length::Fill)
+        .center_x()
+        .style(theme::Container::Custom(Box::new(PrintPreviewCustomStyle)));
+
+    column![
+        container(page_setup).padding([3, 5, 3, 5]),
+        scrollable(pages)
+    ]
+    .into()
+}
+
+pub fn view(audiorox: &AudioRox) -> Element<'_, Message> {
+    if let Some(preview) = &audiorox.print_preview {
+        return print_preview(audiorox, preview);
+    }
+
+    let ui = audiorox.settings.ui_language;
+    let final_content = report(audiorox, true);
 
     if let Modals::Partner = audiorox.succursale_overlay_menu {
         
         let modal_content = container(
             column![
-                text("Partenaire").size(24),
+                text(tr(ui, "Partenaire")).size(24),
                 column![
                     get_all_partners(&audiorox.partner),
                     button(text("OK")).on_press(Message::HideSuccursaleMenu),
@@ -652,7 +1505,7 @@ pub fn view(audiorox: &AudioRox) ->  Element<Message> {
     } else if let Modals::Succursale = audiorox.succursale_overlay_menu {
         let modal_content = container(
             column![
-                text("Succursale").size(24),
+                text(tr(ui, "Succursale")).size(24),
                 column![
                     get_all_succursales(&audiorox.partner).1,
                     button(text("OK")).on_press(Message::CancelSuccursaleChoices),
@@ -668,17 +1521,365 @@ pub fn view(audiorox: &AudioRox) ->  Element<Message> {
         modal::Modal::new(final_content, modal_content)
             .on_blur(Message::HideSuccursaleMenu)
             .into()
+    } else if let Modals::RestoreSession = audiorox.succursale_overlay_menu {
+        let modal_content = confirmation(
+            tr(ui, "Session non enregistrée"),
+            tr(ui, "Un rapport non enregistré a été récupéré. Voulez-vous le restaurer ?"),
+            (tr(ui, "Restaurer"), Message::RestoreSession),
+            (tr(ui, "Ignorer"), Message::DiscardSession),
+        );
+
+        modal::Modal::new(final_content, modal_content).into()
+    } else if let Modals::ConfirmLoad = audiorox.succursale_overlay_menu {
+        let modal_content = confirmation(
+            tr(ui, "Modifications non enregistrées"),
+            tr(ui, "Les modifications non enregistrées seront perdues. Charger quand même ?"),
+            (tr(ui, "Charger"), Message::ConfirmLoad),
+            (tr(ui, "Annuler"), Message::CancelLoad),
+        );
+
+        modal::Modal::new(final_content, modal_content)
+            .on_blur(Message::CancelLoad)
+            .into()
+    } else if let Modals::ConfirmFinalize = audiorox.succursale_overlay_menu {
+        let modal_content = confirmation(
+            tr(ui, "Finaliser le rapport"),
+            tr(ui, "Le rapport sera signé par l'examinateur et ne pourra plus être modifié sans créer une nouvelle révision. Finaliser ?"),
+            (tr(ui, "Finaliser"), Message::FinalizeReport),
+            (tr(ui, "Annuler"), Message::CancelFinalize),
+        );
+
+        modal::Modal::new(final_content, modal_content)
+            .on_blur(Message::CancelFinalize)
+            .into()
+    } else if let Modals::Verification = audiorox.succursale_overlay_menu {
+        let result = match &audiorox.verification {
+            Some(Ok(signature)) => format!(
+                "{}\n{} {}, {} {}\nSHA-256 {}",
+                tr(ui, "La signature est valide."),
+                tr(ui, "Révision"),
+                signature.revision,
+                tr(ui, "signée par"),
+                signature.signed_by,
+                signature.hash
+            ),
+            Some(Err(e)) => tr(ui, e.message()).to_string(),
+            None => String::new(),
+        };
+        let modal_content = container(
+            column![
+                text(tr(ui, "Vérification de la signature")).size(24),
+                text(result).size(14),
+                row![
+                    horizontal_space(Length::Fill),
+                    button(text("OK")).on_press(Message::HideVerification),
+                ],
+            ]
+            .spacing(15),
+        )
+        .width(560)
+        .padding(10)
+        .style(theme::Container::Box);
+
+        modal::Modal::new(final_content, modal_content)
+            .on_blur(Message::HideVerification)
+            .into()
+    } else if let Modals::Recipients = audiorox.succursale_overlay_menu {
+        modal::Modal::new(final_content, recipients(audiorox))
+            .on_blur(Message::HideRecipients)
+            .into()
+    } else if let Modals::Delivery = audiorox.succursale_overlay_menu {
+        modal::Modal::new(final_content, delivery_window(audiorox))
+            .on_blur(Message::HideDelivery)
+            .into()
+    } else if let Modals::Clinicians = audiorox.succursale_overlay_menu {
+        modal::Modal::new(final_content, clinician_profiles(audiorox))
+            .on_blur(Message::HideClinicians)
+            .into()
+    } else if let Modals::Shortcuts = audiorox.succursale_overlay_menu {
+        modal::Modal::new(final_content, shortcuts(ui))
+            .on_blur(Message::HideShortcuts)
+            .into()
+    } else {
+        let page = container(final_content).width(Length::Fixed(PAGE_WIDTH));
+
+        // the page is narrower than the window unless zoomed in
+        let page = if audiorox.settings.zoom > 1.0 {
+            scrollable(page).direction(scrollable::Direction::Both {
+                vertical: scrollable::Properties::default(),
+                horizontal: scrollable::Properties::default(),
+            })
+        } else {
+            scrollable(container(page).width(Length::Fill).center_x())
+        };
+
+        column![toolbar(audiorox), page].into()
+    }
+}
+
+/// Editor of the recipients of the CC list, from which a copy is generated for each
+/// checked recipient.
+fn recipients(audiorox: &AudioRox) -> Element<'_, Message> {
+    let ui = audiorox.settings.ui_language;
+
+    let mut list = Column::new().spacing(15);
+    for category in CC_CATEGORIES {
+        let recipient = audiorox.cc.recipient(category);
+
+        let mut fields = Column::new().spacing(4);
+        for field in RECIPIENT_FIELDS {
+            fields = fields.push(
+                row![
+                    text(tr(ui, field.label())).size(14).width(Length::Fixed(180.0)),
+                    text_input("", recipient.get(field))
+                        .on_input(move |value| Message::RecipientEdited(category, field, value))
+                        .size(14),
+                ]
+                .align_items(Alignment::Center),
+            );
+        }
+
+        list = list.push(column![
+            checkbox(tr(ui, category.label()), recipient.selected, move |value| {
+                Message::CCChanged(category, value)
+            })
+            .size(16)
+            .text_size(18),
+            fields,
+            Rule::horizontal(1.),
+        ]
+        .spacing(5));
+    }
+
+    container(
+        column![
+            text(tr(ui, "Destinataires")).size(24),
+            scrollable(list).height(Length::Fixed(450.0)),
+            row![
+                button(text(tr(ui, "Distribuer"))).on_press(Message::DistributeReport),
+                horizontal_space(Length::Fill),
+                button(text("OK")).on_press(Message::HideRecipients),
+            ],
+        ]
+        .spacing(15),
+    )
+    .width(600)
+    .padding(10)
+    .style(theme::Container::Box)
+    .into()
+}
+
+/// Settings of the mail server, the recipients the copies will be sent to and the log
+/// of the copies sent for the patient.
+fn delivery_window(audiorox: &AudioRox) -> Element<'_, Message> {
+    let ui = audiorox.settings.ui_language;
+    let settings = &audiorox.settings.delivery;
+
+    let mut server = Column::new().spacing(4);
+    for field in DELIVERY_FIELDS {
+        let mut input = text_input("", &settings.get(field))
+            .on_input(move |value| Message::DeliverySettingChanged(field, value))
+            .size(14);
+        if field == DeliveryField::Password {
+            input = input.password();
+        }
+        server = server.push(
+            row![text(tr(ui, field.label())).size(14).width(Length::Fixed(200.0)), input]
+                .align_items(Alignment::Center),
+        );
+    }
+    server = server.push(
+        row![
+            text(tr(ui, "Sécurité")).size(14).width(Length::Fixed(200.0)),
+            pick_list(&SMTP_SECURITIES[..], Some(settings.security), Message::SmtpSecurityChanged)
+                .text_size(14),
+        ]
+        .align_items(Alignment::Center),
+    );
+    if audiorox.partner != PartnerAndSuccursale::None {
+        server = server.push(
+            row![
+                text(tr(ui, "Partenaire : télécopieur ou courriel")).size(14).width(Length::Fixed(200.0)),
+                text_input("", settings.partner_contact(audiorox.partner))
+                    .on_input(Message::PartnerContactChanged)
+                    .size(14),
+            ]
+            .align_items(Alignment::Center),
+        );
+    }
+
+    let mut addressees = Column::new().spacing(2);
+    for recipient in delivery::addressees(audiorox) {
+        let address = settings
+            .address(&recipient.contact)
+            .unwrap_or_else(|| tr(ui, "aucune adresse utilisable").to_string());
+        addressees = addressees.push(text(format!("{} — {}", recipient.name, address)).size(14));
+    }
+
+    let mut log = Column::new().spacing(2);
+    for record in audiorox.delivery_log.iter().rev() {
+        let status = match &record.error {
+            None => tr(ui, "envoyé").to_string(),
+            Some(error) => format!("{} : {}", tr(ui, "échec"), error),
+        };
+        let date = record.sent_at.get(..16).unwrap_or(&record.sent_at).replace('T', " ");
+        log = log.push(text(format!("{} {} <{}> {}", date, record.recipient, record.address, status)).size(12));
+    }
+
+    let send = if audiorox.delivering {
+        button(text(tr(ui, "Envoi en cours…")))
     } else {
-        let final_element: Column<Message> = column![final_content];
+        button(text(tr(ui, "Envoyer"))).on_press(Message::DeliverReport)
+    };
+
+    container(
+        column![
+            text(tr(ui, "Envoi du rapport")).size(24),
+            server,
+            Rule::horizontal(1.),
+            text(tr(ui, "Destinataires")).size(18),
+            addressees,
+            Rule::horizontal(1.),
+            text(tr(ui, "Journal des envois")).size(18),
+            scrollable(log).height(Length::Fixed(150.0)),
+            row![send, horizontal_space(Length::Fill), button(text("OK")).on_press(Message::HideDelivery)],
+        ]
+        .spacing(10),
+    )
+    .width(640)
+    .padding(10)
+    .style(theme::Container::Box)
+    .into()
+}
+
+/// Editor of the clinician profiles kept in the user settings.
+fn clinician_profiles(audiorox: &AudioRox) -> Element<'_, Message> {
+    let ui = audiorox.settings.ui_language;
+
+    let mut profiles = Column::new().spacing(15);
+    for (index, profile) in audiorox.settings.clinicians.iter().enumerate() {
+        let mut fields = Column::new().spacing(4);
+        for field in CLINICIAN_FIELDS {
+            fields = fields.push(
+                row![
+                    text(tr(ui, field.label())).size(14).width(Length::Fixed(160.0)),
+                    text_input("", profile.get(field))
+                        .on_input(move |value| Message::ClinicianEdited(index, field, value))
+                        .size(14),
+                ]
+                .align_items(Alignment::Center),
+            );
+        }
+
+        profiles = profiles.push(column![
+            row![
+                text(profile.to_string()).size(18),
+                horizontal_space(Length::Fill),
+                button(text(tr(ui, "Supprimer")).size(14)).on_press(Message::ClinicianRemoved(index)),
+            ]
+            .align_items(Alignment::Center),
+            fields,
+            Rule::horizontal(1.),
+        ]
+        .spacing(5));
+    }
+
+    container(
+        column![
+            text(tr(ui, "Profils cliniciens")).size(24),
+            scrollable(profiles).height(Length::Fixed(450.0)),
+            row![
+                button(text(tr(ui, "Ajouter un profil"))).on_press(Message::ClinicianAdded),
+                horizontal_space(Length::Fill),
+                button(text("OK")).on_press(Message::HideClinicians),
+            ],
+        ]
+        .spacing(15),
+    )
+    .width(560)
+    .padding(10)
+    .style(theme::Container::Box)
+    .into()
+}
+
+/// Cheat sheet of the keyboard shortcuts, opened with F1.
+fn shortcuts(ui: Language) -> Element<'static, Message> {
+    const SHORTCUTS: [(&str, &str); 18] = [
+        ("Tab / Entrée", "Champ suivant des tableaux"),
+        ("Maj + Tab", "Champ précédent des tableaux"),
+        ("\u{2190} \u{2192}", "Fréquence précédente ou suivante sur l'audiogramme"),
+        ("\u{2191} \u{2193}", "Seuil plus fort ou plus faible de 5 dB"),
+        ("0-9, -", "Saisir un seuil"),
+        ("Entrée", "Enregistrer le seuil à la fréquence du curseur"),
+        ("Suppr", "Effacer le seuil à la fréquence du curseur"),
+        ("B", "Basculer entre conduction aérienne et osseuse, ou sans et avec appareils"),
+        ("U", "Basculer vers les seuils d'inconfort et revenir"),
+        ("N", "Pas de réponse au seuil du curseur"),
+        ("V", "Réponse vibrotactile au seuil du curseur"),
+        ("M", "Masque insuffisant au seuil du curseur"),
+        ("Échap", "Masquer le curseur"),
+        ("Ctrl + Z / Ctrl + Maj + Z", "Annuler / Rétablir"),
+        ("Ctrl + S / Ctrl + L", "Enregistrer / Charger"),
+        ("Ctrl + E / Ctrl + P", "Exporter en PNG / Imprimer"),
+        ("Ctrl + + / Ctrl + - / Ctrl + 0", "Zoom avant / arrière / 100 %"),
+        ("F1", "Afficher les raccourcis"),
+    ];
 
-        scrollable(final_element).into()
+    let mut list = Column::new().spacing(4);
+    for (keys, action) in SHORTCUTS {
+        list = list.push(row![
+            text(tr(ui, keys)).size(14).width(Length::Fixed(200.0)),
+            text(tr(ui, action)).size(14),
+        ]);
     }
+
+    container(
+        column![
+            text(tr(ui, "Raccourcis clavier")).size(24),
+            list,
+            row![
+                horizontal_space(Length::Fill),
+                button(text("OK")).on_press(Message::HideShortcuts),
+            ],
+        ]
+        .spacing(15),
+    )
+    .width(560)
+    .padding(10)
+    .style(theme::Container::Box)
+    .into()
 }
 
-pub fn make_title(title: &str) -> Element<Message> {
+/// Content of a modal asking to confirm an action.
+fn confirmation<'a>(
+    title: &'a str,
+    question: &'a str,
+    confirm: (&'a str, Message),
+    cancel: (&'a str, Message),
+) -> Element<'a, Message> {
+    container(
+        column![
+            text(title).size(24),
+            text(question).size(16),
+            row![
+                horizontal_space(Length::Fill),
+                button(text(cancel.0)).on_press(cancel.1),
+                button(text(confirm.0)).on_press(confirm.1),
+            ]
+            .spacing(10),
+        ]
+        .spacing(15),
+    )
+    .width(400)
+    .padding(10)
+    .style(theme::Container::Box)
+    .into()
+}
+
+pub fn make_title(title: &str, lang: Language) -> Element<'_, Message> {
     let title_bar = column![row![
         container(
-            text("OREILLE DROITE")
+            text(tr(lang, "OREILLE DROITE"))
                 .size(22)
                 .horizontal_alignment(Horizontal::Center) 
         )
@@ -695,7 +1896,7 @@ pub fn make_title(title: &str) -> Element<Message> {
         .align_x(Horizontal::Center)
         .align_y(Vertical::Top),
         container(
-            text("OREILLE GAUCHE")
+            text(tr(lang, "OREILLE GAUCHE"))
                 .size(22)
                 .horizontal_alignment(Horizontal::Center)
         )
@@ -711,3 +1912,17 @@ pub fn make_title(title: &str) -> Element<Message> {
 
     return title_bar.into();
 }
+
+/// Title bar of a section that is not laid out by ear.
+pub fn make_single_title(title: &str) -> Element<'_, Message> {
+    container(
+        text(title)
+            .size(24)
+            .horizontal_alignment(Horizontal::Center),
+    )
+    .width(Length::Fill)
+    .height(Length::Fixed(32.0))
+    .align_x(Horizontal::Center)
+    .align_y(Vertical::Top)
+    .into()
+}